@@ -205,6 +205,65 @@ const templateManager = {
 // Export to global scope
 window.templateManager = templateManager;
 
+// ========================================================================
+// Stack Roles - Chat role (system/user/assistant) of top-level block stacks
+// ========================================================================
+
+const CHAT_ROLES = ['system', 'user', 'assistant'];
+
+const stackRoles = {
+    // Top block id -> role (saved in the "roles" field of the project file)
+    roles: {},
+
+    /**
+     * Get the role of a stack
+     * @param {string} blockId - Top block id
+     * @returns {string|null} Role or null
+     */
+    get(blockId) {
+        return this.roles[blockId] || null;
+    },
+
+    /**
+     * Set (or clear with null) the role of a stack
+     * @param {string} blockId - Top block id
+     * @param {string|null} role - Role
+     */
+    set(blockId, role) {
+        if (role) {
+            this.roles[blockId] = role;
+        } else {
+            delete this.roles[blockId];
+        }
+    },
+
+    /**
+     * Roles of the current top-level stacks (stale ids are dropped)
+     * @returns {Object} Top block id -> role
+     */
+    getAll() {
+        const result = {};
+        if (workspace) {
+            for (const block of workspace.getTopBlocks(false)) {
+                if (this.roles[block.id]) {
+                    result[block.id] = this.roles[block.id];
+                }
+            }
+        }
+        return result;
+    },
+
+    /**
+     * Replace all roles (on project load / new project)
+     * @param {Object} roles - Top block id -> role
+     */
+    load(roles) {
+        this.roles = Object.assign({}, roles || {});
+    }
+};
+
+window.stackRoles = stackRoles;
+
 // Create JavaScript generator
 const javascriptGenerator = Blockly.JavaScript || new Blockly.Generator('JavaScript');
 
@@ -221,6 +280,19 @@ function tt(key, fallback) {
     return fallback;
 }
 
+/**
+ * Quote block text for the DSL when it would not survive tokenizing
+ * (same rules as quote_text in src/tokenizer.rs)
+ * @param {string} text - Block text
+ * @returns {string} Text unchanged, or "..." with " and \ escaped
+ */
+function quoteDslText(text) {
+    if (!/[ \t\u3000"\\\r\n]/.test(text)) {
+        return text;
+    }
+    return '"' + text.replace(/["\\]/g, '\\$&').replace(/[\r\n]/g, ' ') + '"';
+}
+
 /**
  * Register all block definitions with current locale translations
  * This function can be called multiple times to update block labels
@@ -244,7 +316,7 @@ function registerBlockDefinitions() {
 
     javascriptGenerator.forBlock['promps_noun'] = function(block, generator) {
         const text = block.getFieldValue('TEXT');
-        return '_N:' + text + ' ';
+        return '_N:' + quoteDslText(text) + ' ';
     };
 
     // ========================================================================
@@ -268,6 +340,72 @@ function registerBlockDefinitions() {
         return text + ' ';
     };
 
+    // ========================================================================
+    // Modifier Blocks (adjective / adverb)
+    // ========================================================================
+    Blockly.Blocks['promps_adjective'] = {
+        init: function() {
+            this.appendDummyInput()
+                .appendField(tt('blockly.adjective.label', 'Adjective:'))
+                .appendField(new Blockly.FieldTextInput("detailed"), "TEXT");
+            this.setPreviousStatement(true, null);
+            this.setNextStatement(true, null);
+            this.setColour(60);
+            this.setTooltip(() => tt('blockly.adjective.tooltip', 'Adjective block (_ADJ: prefix)'));
+            this.setHelpUrl("");
+        }
+    };
+
+    javascriptGenerator.forBlock['promps_adjective'] = function(block, generator) {
+        const text = block.getFieldValue('TEXT');
+        return '_ADJ:' + quoteDslText(text) + ' ';
+    };
+
+    Blockly.Blocks['promps_adverb'] = {
+        init: function() {
+            this.appendDummyInput()
+                .appendField(tt('blockly.adverb.label', 'Adverb:'))
+                .appendField(new Blockly.FieldTextInput("briefly"), "TEXT");
+            this.setPreviousStatement(true, null);
+            this.setNextStatement(true, null);
+            this.setColour(60);
+            this.setTooltip(() => tt('blockly.adverb.tooltip', 'Adverb block (_ADV: prefix)'));
+            this.setHelpUrl("");
+        }
+    };
+
+    javascriptGenerator.forBlock['promps_adverb'] = function(block, generator) {
+        const text = block.getFieldValue('TEXT');
+        return '_ADV:' + quoteDslText(text) + ' ';
+    };
+
+    // ========================================================================
+    // Example Block (few-shot input -> expected output)
+    // ========================================================================
+    Blockly.Blocks['promps_example'] = {
+        init: function() {
+            this.appendDummyInput()
+                .appendField(tt('blockly.example.label', 'Example'));
+            this.appendDummyInput()
+                .appendField(tt('blockly.example.input', 'Input:'))
+                .appendField(new Blockly.FieldTextInput(""), "INPUT");
+            this.appendDummyInput()
+                .appendField(tt('blockly.example.output', 'Output:'))
+                .appendField(new Blockly.FieldTextInput(""), "OUTPUT");
+            this.setPreviousStatement(true, null);
+            this.setNextStatement(true, null);
+            this.setColour(180);
+            this.setTooltip(() => tt('blockly.example.tooltip', 'Example block (_IN: / _OUT: pair)'));
+            this.setHelpUrl("");
+        }
+    };
+
+    javascriptGenerator.forBlock['promps_example'] = function(block, generator) {
+        const input = block.getFieldValue('INPUT');
+        const output = block.getFieldValue('OUTPUT');
+        return '_IN:' + quoteDslText(input) + ' _OUT:' + quoteDslText(output) + ' ';
+    };
+
     // ========================================================================
     // Particle Blocks
     // ========================================================================
@@ -352,6 +490,22 @@ function registerBlockDefinitions() {
         return tt('blockly.particle.to.output', 'と ');
     };
 
+    // そして (then - joins two actions)
+    Blockly.Blocks['promps_conjunction_then'] = {
+        init: function() {
+            this.appendDummyInput()
+                .appendField(new Blockly.FieldLabel(tt('blockly.conjunction.then.label', 'そして')));
+            this.setPreviousStatement(true, null);
+            this.setNextStatement(true, null);
+            this.setColour(230);
+            this.setTooltip(() => tt('blockly.conjunction.then.tooltip', 'Then - starts the next action'));
+            this.setHelpUrl("");
+        }
+    };
+    javascriptGenerator.forBlock['promps_conjunction_then'] = function(block, generator) {
+        return tt('blockly.conjunction.then.output', 'そして ');
+    };
+
     // へ (direction marker)
     Blockly.Blocks['promps_particle_he'] = {
         init: function() {
@@ -727,7 +881,7 @@ function registerBlockDefinitions() {
     };
     javascriptGenerator.forBlock['promps_verb_custom'] = function(block, generator) {
         const text = block.getFieldValue('TEXT');
-        return '_V:' + text + ' ';
+        return '_V:' + quoteDslText(text) + ' ';
     };
 
     // ========================================================================
@@ -938,6 +1092,7 @@ function buildToolbox() {
                     particles.push({ "kind": "block", "type": "promps_particle_kara" });
                     particles.push({ "kind": "block", "type": "promps_particle_made" });
                     particles.push({ "kind": "block", "type": "promps_particle_yori" });
+                    particles.push({ "kind": "block", "type": "promps_conjunction_then" });
                     return particles;
                 })()
             },
@@ -981,6 +1136,16 @@ function buildToolbox() {
                     { "kind": "block", "type": "promps_verb_custom" }
                 ]
             },
+            // Modifier category (adjectives and adverbs)
+            {
+                "kind": "category",
+                "name": tt('blockly.category.modifier', 'Modifier'),
+                "colour": "60",
+                "contents": [
+                    { "kind": "block", "type": "promps_adjective" },
+                    { "kind": "block", "type": "promps_adverb" }
+                ]
+            },
             // Punctuation category (language-specific to avoid duplicates)
             {
                 "kind": "category",
@@ -1016,7 +1181,8 @@ function buildToolbox() {
                 "name": tt('blockly.category.other', 'Other'),
                 "colour": "20",
                 "contents": [
-                    { "kind": "block", "type": "promps_other" }
+                    { "kind": "block", "type": "promps_other" },
+                    { "kind": "block", "type": "promps_example" }
                 ]
             },
             // My Templates category (dynamic)
@@ -1181,6 +1347,52 @@ function registerTemplateContextMenu() {
     }
 }
 
+/**
+ * Register context menu items for assigning chat roles to block stacks
+ */
+function registerRoleContextMenu() {
+    if (typeof Blockly === 'undefined' || !Blockly.ContextMenuRegistry) {
+        return;
+    }
+
+    CHAT_ROLES.forEach((role, index) => {
+        const id = 'set_stack_role_' + role;
+        if (Blockly.ContextMenuRegistry.registry.getItem(id)) {
+            return;
+        }
+
+        Blockly.ContextMenuRegistry.registry.register({
+            id: id,
+            weight: 20 + index,
+            displayText: function(scope) {
+                const current = scope.block ? stackRoles.get(scope.block.id) : null;
+                const label = tt('role.' + role, 'Role: ' + role);
+                return (current === role ? '✓ ' : '') + label;
+            },
+            preconditionFn: function(scope) {
+                // Only top-level stacks in the main workspace have a role
+                if (scope.block && !scope.block.isInFlyout && !scope.block.getParent()) {
+                    return 'enabled';
+                }
+                return 'hidden';
+            },
+            callback: function(scope) {
+                // Selecting the current role clears it
+                const current = stackRoles.get(scope.block.id);
+                stackRoles.set(scope.block.id, current === role ? null : role);
+
+                if (window.projectManager && typeof window.projectManager.markDirty === 'function') {
+                    window.projectManager.markDirty();
+                }
+                if (typeof updatePreview === 'function') {
+                    updatePreview(getWorkspaceCode());
+                }
+            },
+            scopeType: Blockly.ContextMenuRegistry.ScopeType.BLOCK
+        });
+    });
+}
+
 /**
  * Register a dynamic block type for a template (drag-and-drop from toolbox)
  * @param {Object} template - Template object
@@ -1437,6 +1649,9 @@ function initBlockly() {
     // Register template context menu (global, only once)
     registerTemplateContextMenu();
 
+    // Register chat role context menu (global, only once)
+    registerRoleContextMenu();
+
     // Register dynamic template category (must be after workspace creation)
     registerTemplateCategory();
 
@@ -1553,33 +1768,44 @@ function onBlocklyChange(event) {
         }
     }
 
-    // Generate DSL code from all blocks
-    let code = '';
-    const topBlocks = workspace.getTopBlocks(true);
-
-    for (const block of topBlocks) {
-        code += javascriptGenerator.blockToCode(block);
-    }
-
     // Update preview
     if (typeof updatePreview === 'function') {
-        updatePreview(code.trim());
+        updatePreview(getWorkspaceCode());
     }
 }
 
+/**
+ * Projects included at the start of the prompt
+ * @returns {string[]} Paths relative to the project file
+ */
+function getProjectIncludes() {
+    return window.projectManager && window.projectManager.getIncludes
+        ? window.projectManager.getIncludes()
+        : [];
+}
+
 /**
  * Get DSL code from current workspace
+ * Stacks with a chat role are prefixed with a _ROLE: marker
  */
 function getWorkspaceCode() {
     if (!workspace) {
         return '';
     }
 
-    let code = '';
+    // Project-level includes come first (see projectManager.getIncludes)
+    let code = getProjectIncludes()
+        .map(path => '_INCLUDE:' + quoteDslText(path) + ' ')
+        .join('');
     const topBlocks = workspace.getTopBlocks(true);
 
     for (const block of topBlocks) {
-        code += javascriptGenerator.blockToCode(block);
+        const stackCode = javascriptGenerator.blockToCode(block);
+        const role = stackRoles.get(block.id);
+        if (role && stackCode) {
+            code += '_ROLE:' + role + ' ';
+        }
+        code += stackCode;
     }
 
     return code.trim();
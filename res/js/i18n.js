@@ -52,12 +52,16 @@ const TRANSLATIONS = {
         'blockly.category.particle': '助詞',
         'blockly.category.article': '冠詞',
         'blockly.category.verb': '動詞',
+        'blockly.category.modifier': '修飾語',
         'blockly.category.punctuation': '句読点',
         'blockly.category.other': 'その他',
 
         // Template (Macro) Feature
         'toolbox.myTemplates': 'マイテンプレート',
         'template.saveAs': 'テンプレートとして保存',
+        'role.system': 'ロール: システム',
+        'role.user': 'ロール: ユーザー',
+        'role.assistant': 'ロール: アシスタント',
         'template.enterName': 'テンプレート名を入力:',
         'template.empty': '保存されたテンプレートはありません',
         'template.delete': '削除',
@@ -69,6 +73,11 @@ const TRANSLATIONS = {
         // Blockly Block Labels (displayed on blocks)
         'blockly.noun.label': '名詞:',
         'blockly.other.label': 'その他:',
+        'blockly.adjective.label': '形容詞:',
+        'blockly.adverb.label': '副詞:',
+        'blockly.example.label': '例',
+        'blockly.example.input': '入力:',
+        'blockly.example.output': '出力:',
         'blockly.verb.label': '動詞:',
 
         // Particle Labels
@@ -81,6 +90,7 @@ const TRANSLATIONS = {
         'blockly.particle.kara.label': 'から',
         'blockly.particle.made.label': 'まで',
         'blockly.particle.yori.label': 'より',
+        'blockly.conjunction.then.label': 'そして',
 
         // Particle DSL Output
         'blockly.particle.ga.output': 'が ',
@@ -92,6 +102,7 @@ const TRANSLATIONS = {
         'blockly.particle.kara.output': 'から ',
         'blockly.particle.made.output': 'まで ',
         'blockly.particle.yori.output': 'より ',
+        'blockly.conjunction.then.output': 'そして ',
 
         // Article Labels (English mode only, but keys needed for consistency)
         'blockly.article.a.label': 'a',
@@ -172,6 +183,9 @@ const TRANSLATIONS = {
         // Blockly Block Tooltips
         'blockly.noun.tooltip': '名詞ブロック (_N: プレフィックス付き)',
         'blockly.other.tooltip': 'その他ブロック (助詞、動詞、形容詞、接続詞など)',
+        'blockly.adjective.tooltip': '形容詞ブロック (_ADJ: プレフィックス付き)',
+        'blockly.adverb.tooltip': '副詞ブロック (_ADV: プレフィックス付き、動詞の前に置きます)',
+        'blockly.example.tooltip': '例ブロック - 入力と期待する出力の組 (_IN: / _OUT:)',
         'blockly.particle.ga.tooltip': '助詞: が（主語を示す）',
         'blockly.particle.wo.tooltip': '助詞: を（目的語を示す）',
         'blockly.particle.ni.tooltip': '助詞: に（方向・対象を示す）',
@@ -181,6 +195,7 @@ const TRANSLATIONS = {
         'blockly.particle.kara.tooltip': '助詞: から（起点を示す）',
         'blockly.particle.made.tooltip': '助詞: まで（終点を示す）',
         'blockly.particle.yori.tooltip': '助詞: より（比較を示す）',
+        'blockly.conjunction.then.tooltip': '接続詞: そして（次の動作につなぐ）',
         'blockly.verb.analyze.tooltip': '動詞: 分析して',
         'blockly.verb.summarize.tooltip': '動詞: 要約して',
         'blockly.verb.translate.tooltip': '動詞: 翻訳して',
@@ -269,12 +284,16 @@ const TRANSLATIONS = {
         'blockly.category.particle': 'Connector',
         'blockly.category.article': 'Article',
         'blockly.category.verb': 'Action',
+        'blockly.category.modifier': 'Modifier',
         'blockly.category.punctuation': 'Punctuation',
         'blockly.category.other': 'Other',
 
         // Template (Macro) Feature
         'toolbox.myTemplates': 'My Templates',
         'template.saveAs': 'Save as Template',
+        'role.system': 'Role: System',
+        'role.user': 'Role: User',
+        'role.assistant': 'Role: Assistant',
         'template.enterName': 'Enter template name:',
         'template.empty': 'No templates saved',
         'template.delete': 'Delete',
@@ -286,6 +305,11 @@ const TRANSLATIONS = {
         // Blockly Block Labels (displayed on blocks)
         'blockly.noun.label': 'Noun:',
         'blockly.other.label': 'Other:',
+        'blockly.adjective.label': 'Adjective:',
+        'blockly.adverb.label': 'Adverb:',
+        'blockly.example.label': 'Example',
+        'blockly.example.input': 'Input:',
+        'blockly.example.output': 'Output:',
         'blockly.verb.label': 'Action:',
 
         // Connector Labels (English equivalents of Japanese particles)
@@ -298,6 +322,7 @@ const TRANSLATIONS = {
         'blockly.particle.kara.label': 'from',
         'blockly.particle.made.label': 'until',
         'blockly.particle.yori.label': 'than',
+        'blockly.conjunction.then.label': 'then',
 
         // Connector DSL Output (empty for subject/object markers, English for others)
         'blockly.particle.ga.output': '',
@@ -309,6 +334,7 @@ const TRANSLATIONS = {
         'blockly.particle.kara.output': 'from ',
         'blockly.particle.made.output': 'until ',
         'blockly.particle.yori.output': 'than ',
+        'blockly.conjunction.then.output': 'then ',
 
         // Article Labels (English mode only)
         'blockly.article.a.label': 'a',
@@ -389,6 +415,9 @@ const TRANSLATIONS = {
         // Blockly Block Tooltips
         'blockly.noun.tooltip': 'Noun block - marks important terms (_N: prefix)',
         'blockly.other.tooltip': 'Other block - for custom text',
+        'blockly.adjective.tooltip': 'Adjective block - describes a noun (_ADJ: prefix)',
+        'blockly.adverb.tooltip': 'Adverb block - describes an action (_ADV: prefix)',
+        'blockly.example.tooltip': 'Example block - an input and its expected output (_IN: / _OUT:)',
         'blockly.particle.ga.tooltip': 'Subject marker - indicates the subject (can be omitted in English)',
         'blockly.particle.wo.tooltip': 'Object marker - indicates the object (can be omitted in English)',
         'blockly.particle.ni.tooltip': 'Direction marker - equivalent to "to"',
@@ -398,6 +427,7 @@ const TRANSLATIONS = {
         'blockly.particle.kara.tooltip': 'Origin marker - equivalent to "from"',
         'blockly.particle.made.tooltip': 'Limit marker - equivalent to "until"',
         'blockly.particle.yori.tooltip': 'Comparison marker - equivalent to "than"',
+        'blockly.conjunction.then.tooltip': 'Conjunction - starts the next action ("then")',
         'blockly.verb.analyze.tooltip': 'Action: analyze',
         'blockly.verb.summarize.tooltip': 'Action: summarize',
         'blockly.verb.translate.tooltip': 'Action: translate',
@@ -486,12 +516,16 @@ const TRANSLATIONS = {
         'blockly.category.particle': 'Connecteur',
         'blockly.category.article': 'Article',
         'blockly.category.verb': 'Action',
+        'blockly.category.modifier': 'Modificateur',
         'blockly.category.punctuation': 'Ponctuation',
         'blockly.category.other': 'Autre',
 
         // Template (Macro) Feature
         'toolbox.myTemplates': 'Mes modèles',
         'template.saveAs': 'Enregistrer comme modèle',
+        'role.system': 'Rôle : Système',
+        'role.user': 'Rôle : Utilisateur',
+        'role.assistant': 'Rôle : Assistant',
         'template.enterName': 'Nom du modèle :',
         'template.empty': 'Aucun modèle enregistré',
         'template.delete': 'Supprimer',
@@ -503,6 +537,11 @@ const TRANSLATIONS = {
         // Blockly Block Labels (displayed on blocks)
         'blockly.noun.label': 'Nom :',
         'blockly.other.label': 'Autre :',
+        'blockly.adjective.label': 'Adjectif :',
+        'blockly.adverb.label': 'Adverbe :',
+        'blockly.example.label': 'Exemple',
+        'blockly.example.input': 'Entrée :',
+        'blockly.example.output': 'Sortie :',
         'blockly.verb.label': 'Action :',
 
         // Connector Labels (French equivalents of Japanese particles)
@@ -515,6 +554,7 @@ const TRANSLATIONS = {
         'blockly.particle.kara.label': 'de',
         'blockly.particle.made.label': 'jusqu\'à',
         'blockly.particle.yori.label': 'que',
+        'blockly.conjunction.then.label': 'puis',
 
         // Connector DSL Output (empty for subject/object markers, French for others)
         'blockly.particle.ga.output': '',
@@ -526,6 +566,7 @@ const TRANSLATIONS = {
         'blockly.particle.kara.output': 'de ',
         'blockly.particle.made.output': 'jusqu\'à ',
         'blockly.particle.yori.output': 'que ',
+        'blockly.conjunction.then.output': 'puis ',
 
         // Article Labels (French mode)
         'blockly.article.a.label': 'un',
@@ -606,6 +647,9 @@ const TRANSLATIONS = {
         // Blockly Block Tooltips
         'blockly.noun.tooltip': 'Bloc nom - marque les termes importants (préfixe _N:)',
         'blockly.other.tooltip': 'Bloc autre - pour le texte personnalisé',
+        'blockly.adjective.tooltip': 'Bloc adjectif - décrit un nom (préfixe _ADJ:)',
+        'blockly.adverb.tooltip': 'Bloc adverbe - décrit une action (préfixe _ADV:)',
+        'blockly.example.tooltip': 'Bloc exemple - une entrée et sa sortie attendue (_IN: / _OUT:)',
         'blockly.particle.ga.tooltip': 'Marqueur de sujet (peut être omis en français)',
         'blockly.particle.wo.tooltip': 'Marqueur d\'objet (peut être omis en français)',
         'blockly.particle.ni.tooltip': 'Marqueur de direction - équivalent à « à »',
@@ -615,6 +659,7 @@ const TRANSLATIONS = {
         'blockly.particle.kara.tooltip': 'Marqueur d\'origine - équivalent à « de »',
         'blockly.particle.made.tooltip': 'Marqueur de limite - équivalent à « jusqu\'à »',
         'blockly.particle.yori.tooltip': 'Marqueur de comparaison - équivalent à « que »',
+        'blockly.conjunction.then.tooltip': 'Conjonction - introduit l\'action suivante (« puis »)',
         'blockly.verb.analyze.tooltip': 'Action : analyser',
         'blockly.verb.summarize.tooltip': 'Action : résumer',
         'blockly.verb.translate.tooltip': 'Action : traduire',
@@ -72,11 +72,54 @@ function updateThemeButton() {
 }
 
 /**
- * Generate prompt from DSL text
+ * Path of the open project file (relative _FILE: paths start there)
+ */
+function getProjectPath() {
+    return window.projectManager && window.projectManager.getCurrentFilePath
+        ? window.projectManager.getCurrentFilePath()
+        : null;
+}
+
+/**
+ * Variables of the open project (for _IF: sections)
+ */
+function getProjectVariables() {
+    return window.projectManager && window.projectManager.getVariables
+        ? window.projectManager.getVariables()
+        : null;
+}
+
+/**
+ * Glossary of the open project (noun definitions)
+ */
+function getProjectGlossary() {
+    return window.projectManager && window.projectManager.getGlossary
+        ? window.projectManager.getGlossary()
+        : null;
+}
+
+/**
+ * Whether the open project collects negative instructions into a "Do not" section
+ */
+function getProjectProhibitionSection() {
+    return window.projectManager && window.projectManager.getProhibitionSection
+        ? window.projectManager.getProhibitionSection()
+        : false;
+}
+
+/**
+ * Generate prompt from DSL text (with _FILE: contents inlined)
  */
 async function generatePrompt(input) {
     try {
-        const result = await invoke('generate_prompt_from_text', { input });
+        const projectPath = getProjectPath();
+        const locale = window.i18n ? window.i18n.getLocale() : 'ja';
+        const variables = getProjectVariables();
+        const glossary = getProjectGlossary();
+        const prohibitionSection = getProjectProhibitionSection();
+        const result = await invoke('generate_prompt_for_project', {
+            input, projectPath, locale, variables, glossary, prohibitionSection
+        });
         console.log('Generated prompt:', result);
         return result;
     } catch (error) {
@@ -92,7 +135,10 @@ async function validateDsl(input) {
     try {
         // Get current locale for language-specific validation
         const locale = window.i18n ? window.i18n.getLocale() : 'ja';
-        const result = await invoke('validate_dsl_sequence', { input, locale });
+        const projectPath = getProjectPath();
+        const variables = getProjectVariables();
+        const glossary = getProjectGlossary();
+        const result = await invoke('validate_dsl_sequence', { input, locale, projectPath, variables, glossary });
         console.log('Validation result:', result);
         return result;
     } catch (error) {
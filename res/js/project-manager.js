@@ -141,6 +141,9 @@ async function newProject(confirmDiscard = true) {
         if (workspace) {
             workspace.clear();
         }
+        if (window.stackRoles) {
+            window.stackRoles.load({});
+        }
 
         // Update title
         updateWindowTitle();
@@ -158,6 +161,9 @@ async function newProject(confirmDiscard = true) {
         if (workspace) {
             workspace.clear();
         }
+        if (window.stackRoles) {
+            window.stackRoles.load({});
+        }
 
         updateWindowTitle();
         return true;
@@ -181,6 +187,8 @@ function createLocalProject(name) {
             author: null
         },
         workspace: {},
+        variables: {},
+        includes: [],
         settings: {
             zoom: 1.0,
             scrollX: 0,
@@ -201,6 +209,10 @@ async function saveProject(saveAs = false) {
     // Update project with current workspace state
     currentProject.workspace = getWorkspaceState();
     currentProject.settings = getWorkspaceSettings();
+    currentProject.roles = window.stackRoles ? window.stackRoles.getAll() : {};
+    currentProject.variables = getVariables();
+    currentProject.includes = getIncludes();
+    currentProject.locale = window.i18n ? window.i18n.getLocale() : 'ja';
     currentProject.metadata.modifiedAt = new Date().toISOString();
 
     // Determine file path
@@ -280,7 +292,10 @@ async function loadProject() {
         currentFilePath = filePath;
         isDirty = false;
 
-        // Restore workspace state
+        // Restore workspace state (roles first, so the preview includes them)
+        if (window.stackRoles) {
+            window.stackRoles.load(project.roles);
+        }
         loadWorkspaceState(project.workspace);
         applyWorkspaceSettings(project.settings);
 
@@ -363,6 +378,107 @@ function getCurrentProject() {
     return currentProject;
 }
 
+/**
+ * Get variables of the current project (for _IF: sections)
+ * @returns {Object} Variable name -> boolean value
+ */
+function getVariables() {
+    return (currentProject && currentProject.variables) || {};
+}
+
+/**
+ * Set a project variable
+ * @param {string} name - Variable name
+ * @param {boolean} value - Variable value
+ */
+function setVariable(name, value) {
+    if (!currentProject) {
+        return;
+    }
+    currentProject.variables = { ...getVariables(), [name]: Boolean(value) };
+    markDirty();
+
+    // Refresh the preview with the new variant
+    if (typeof updatePreview === 'function') {
+        updatePreview(getWorkspaceCode());
+    }
+}
+
+/**
+ * Get projects included at the start of the prompt
+ * @returns {string[]} Paths relative to the project file
+ */
+function getIncludes() {
+    return (currentProject && currentProject.includes) || [];
+}
+
+/**
+ * Set projects included at the start of the prompt
+ * @param {string[]} paths - Paths relative to the project file
+ */
+function setIncludes(paths) {
+    if (!currentProject) {
+        return;
+    }
+    currentProject.includes = paths.filter(path => path && path.trim());
+    markDirty();
+
+    if (typeof updatePreview === 'function') {
+        updatePreview(getWorkspaceCode());
+    }
+}
+
+/**
+ * Get the project glossary
+ * @returns {Object} { entries: [{ term, definition, aliases }], strict }
+ */
+function getGlossary() {
+    return (currentProject && currentProject.glossary) || { entries: [], strict: false };
+}
+
+/**
+ * Set the project glossary
+ * @param {Object} glossary - { entries: [{ term, definition, aliases }], strict }
+ */
+function setGlossary(glossary) {
+    if (!currentProject) {
+        return;
+    }
+    currentProject.glossary = {
+        entries: (glossary.entries || []).filter(entry => entry.term && entry.term.trim()),
+        strict: Boolean(glossary.strict)
+    };
+    markDirty();
+
+    if (typeof updatePreview === 'function') {
+        updatePreview(getWorkspaceCode());
+    }
+}
+
+/**
+ * Whether negative instructions are collected into a "Do not" section
+ * @returns {boolean} True if the prohibitions section is enabled
+ */
+function getProhibitionSection() {
+    return Boolean(currentProject && currentProject.prohibitionSection);
+}
+
+/**
+ * Enable or disable the "Do not" section of the prompt
+ * @param {boolean} enabled - Collect negative instructions into their own section
+ */
+function setProhibitionSection(enabled) {
+    if (!currentProject) {
+        return;
+    }
+    currentProject.prohibitionSection = Boolean(enabled);
+    markDirty();
+
+    if (typeof updatePreview === 'function') {
+        updatePreview(getWorkspaceCode());
+    }
+}
+
 /**
  * Get current file path
  * @returns {string|null} Current file path
@@ -383,6 +499,14 @@ window.projectManager.resetDirtyState = resetDirtyState;
 window.projectManager.hasUnsavedChanges = hasUnsavedChanges;
 window.projectManager.getCurrentProject = getCurrentProject;
 window.projectManager.getCurrentFilePath = getCurrentFilePath;
+window.projectManager.getVariables = getVariables;
+window.projectManager.setVariable = setVariable;
+window.projectManager.getIncludes = getIncludes;
+window.projectManager.setIncludes = setIncludes;
+window.projectManager.getGlossary = getGlossary;
+window.projectManager.setGlossary = setGlossary;
+window.projectManager.getProhibitionSection = getProhibitionSection;
+window.projectManager.setProhibitionSection = setProhibitionSection;
 window.projectManager.updateWindowTitle = updateWindowTitle;
 
 console.log('Project Manager module loaded');
@@ -187,6 +187,9 @@ const validationUI = {
     /**
      * Build block position mapping from workspace
      * Maps token positions to block IDs for error highlighting
+     * (a block may produce zero or several tokens, a stack with a chat
+     * role starts with a _ROLE: token that has no block, and project-level
+     * _INCLUDE: tokens come before all stacks)
      * @returns {Object} Map of position -> blockId
      */
     buildBlockPositions: function() {
@@ -196,14 +199,21 @@ const validationUI = {
 
         const positions = {};
         const topBlocks = workspace.getTopBlocks(true);
-        let position = 0;
+        let position = getProjectIncludes().length;
 
         for (const topBlock of topBlocks) {
+            if (window.stackRoles && window.stackRoles.get(topBlock.id)
+                && javascriptGenerator.blockToCode(topBlock)) {
+                position++;
+            }
+
             let block = topBlock;
             while (block) {
-                // Each block represents one token position
-                positions[position] = block.id;
-                position++;
+                const tokenCount = this.countBlockTokens(block);
+                for (let i = 0; i < tokenCount; i++) {
+                    positions[position] = block.id;
+                    position++;
+                }
                 block = block.getNextBlock();
             }
         }
@@ -211,6 +221,22 @@ const validationUI = {
         return positions;
     },
 
+    /**
+     * Count the DSL tokens generated by a single block
+     * (quoted marker text such as _N:"New York" is one token)
+     * @param {Blockly.Block} block - Block
+     * @returns {number} Token count
+     */
+    countBlockTokens: function(block) {
+        if (typeof javascriptGenerator === 'undefined') {
+            return 1;
+        }
+        const code = javascriptGenerator.blockToCode(block, true);
+        const tokens = (typeof code === 'string' ? code : '')
+            .match(/_[A-Z]+:"(?:[^"\\]|\\.)*"|(?:\\.|[^\s])+/g);
+        return tokens ? tokens.length : 0;
+    },
+
     /**
      * Apply an auto-fix action
      * @param {Object} autofix - AutoFixAction from backend
@@ -226,6 +252,15 @@ const validationUI = {
             const blockPositions = this.buildBlockPositions();
             const targetBlockId = blockPositions[autofix.targetPosition];
 
+            // Replace: change the text of the target block in place
+            if (autofix.actionType === 'replace') {
+                const targetBlock = targetBlockId && workspace.getBlockById(targetBlockId);
+                if (targetBlock && targetBlock.getField('TEXT')) {
+                    targetBlock.setFieldValue(autofix.replacement, 'TEXT');
+                }
+                return;
+            }
+
             // Create the new block
             const newBlock = workspace.newBlock(autofix.blockType);
             newBlock.initSvg();
@@ -476,7 +511,8 @@ const patternUI = {
             }
             // Get all patterns and find the one to apply
             const locale = window.i18n ? window.i18n.getLocale() : 'ja';
-            const patterns = await invoke('get_patterns', { locale });
+            const projectPath = this.getProjectPath();
+            const patterns = await invoke('get_patterns', { locale, projectPath });
             const pattern = patterns.find(p => p.id === patternId);
             if (pattern) {
                 this.completePattern(pattern);
@@ -607,6 +643,7 @@ const patternUI = {
         if (blockType.includes('particle_kara')) return 'particle_kara';
         if (blockType.includes('particle_made')) return 'particle_made';
         if (blockType.includes('particle')) return 'particle_other';
+        if (blockType.includes('conjunction')) return 'conjunction';
         if (blockType.includes('verb')) return 'verb';
         return 'other';
     },
@@ -694,6 +731,15 @@ const patternUI = {
         }
     },
 
+    /**
+     * Path of the open project (for project-embedded templates)
+     */
+    getProjectPath: function() {
+        return window.projectManager && window.projectManager.getCurrentFilePath
+            ? window.projectManager.getCurrentFilePath()
+            : null;
+    },
+
     /**
      * Get Tauri invoke function (compatible with v1 and v2)
      */
@@ -720,7 +766,8 @@ const patternUI = {
             }
             // Get current locale for language-specific patterns
             const locale = window.i18n ? window.i18n.getLocale() : 'ja';
-            const patterns = await invoke('get_patterns', { locale });
+            const projectPath = this.getProjectPath();
+            const patterns = await invoke('get_patterns', { locale, projectPath });
             this.displayPatterns(patterns);
         } catch (error) {
             console.warn('Failed to load patterns:', error);
@@ -739,7 +786,8 @@ const patternUI = {
             }
             // Get current locale for language-specific pattern matching
             const locale = window.i18n ? window.i18n.getLocale() : 'ja';
-            const results = await invoke('analyze_dsl_patterns', { input: dslInput, locale });
+            const projectPath = this.getProjectPath();
+            const results = await invoke('analyze_dsl_patterns', { input: dslInput, locale, projectPath });
             this.displayMatchResults(results);
         } catch (error) {
             console.warn('Failed to analyze patterns:', error);
@@ -3,18 +3,29 @@
 /// This module defines all Tauri commands that bridge the frontend (JS)
 /// and backend (Rust) logic.
 
-use promps::{parse_input, generate_prompt};
+use promps::{parse_input, evaluate_conditions, generate_prompt, generate_prompt_with_glossary, generate_prompt_with_prohibitions, generate_messages, ChatMessage, Glossary, PromptPart};
+use promps::tokenizer::quote_text;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
 // Phase 5-6: Validation module
 use crate::modules::validation::{
-    validate_sequence_with_locale, ValidationResult,
-    get_pattern_templates_by_locale,
-    analyze_patterns_with_locale,
+    validate_sequence_with_locale, validate_sequence_auto, validate_condition_variables, validate_glossary_nouns, ValidationResult,
+    analyze_patterns_with_templates, prohibition_clauses,
     PatternTemplate, PatternMatchResult,
 };
+use crate::modules::pattern_loader::{load_pattern_templates, user_pattern_dir, PatternLoadReport};
+use crate::modules::completion::{count_block_usage, suggest_next as suggest_next_blocks, BlockUsage, Suggestion};
+use crate::modules::instantiate::{instantiate_pattern as instantiate_pattern_template, InstantiationResult};
+use crate::modules::segmentation::{segment_text as segment_plain_text, SegmentationResult};
+use crate::modules::workspace::{dsl_to_workspace, workspace_to_dsl_with_roles};
+use crate::modules::file_include::{project_base_dir, resolve_file_parts, validate_file_includes};
+use crate::modules::locale_detect::{detect_input_locale, AUTO_LOCALE};
+use crate::modules::noun_consistency::validate_noun_consistency;
+use crate::modules::project_include::{resolve_include_parts, validate_project_includes, IncludedProject};
+use crate::modules::translation::{translate_dsl as translate_dsl_sequence, TranslationResult};
 
 /// Generate prompt from DSL input text
 ///
@@ -29,6 +40,78 @@ pub fn generate_prompt_from_text(input: String) -> String {
     generate_prompt(&parts)
 }
 
+/// Parse DSL text for a project: evaluate `_IF:` sections, then inline
+/// `_FILE:` contents and `_INCLUDE:` projects
+fn parse_project_input(
+    input: &str,
+    project_path: Option<&str>,
+    variables: Option<&HashMap<String, bool>>,
+    locale: &str,
+) -> Vec<PromptPart> {
+    let mut parts = evaluate_conditions(&parse_input(input), variables.unwrap_or(&HashMap::new()));
+    let base_dir = project_base_dir(project_path);
+    resolve_file_parts(&mut parts, base_dir.as_deref());
+    resolve_include_parts(&mut parts, project_path, locale, &load_included_project);
+    parts
+}
+
+/// Generate prompt from DSL input text, inlining `_FILE:` contents
+///
+/// # Arguments
+/// * `input` - Raw DSL text (with _N: markers, space-delimited)
+/// * `project_path` - Optional path of the open project (relative `_FILE:` paths start there)
+/// * `locale` - Optional locale code of the constraints section ("ja", "en", "fr")
+/// * `variables` - Optional project variables for `_IF:` sections (unset = false)
+/// * `glossary` - Optional project glossary (definitions of the nouns used are appended)
+/// * `prohibition_section` - Optional flag to move negative instructions into a
+///   "Do not" section (default false)
+///
+/// # Returns
+/// Formatted prompt string (files that cannot be read are left as labels)
+#[tauri::command]
+pub fn generate_prompt_for_project(
+    input: String,
+    project_path: Option<String>,
+    locale: Option<String>,
+    variables: Option<HashMap<String, bool>>,
+    glossary: Option<Glossary>,
+    prohibition_section: Option<bool>,
+) -> String {
+    let locale_str = locale.as_deref().unwrap_or("ja");
+    let parts = parse_project_input(&input, project_path.as_deref(), variables.as_ref(), locale_str);
+    let glossary = glossary.unwrap_or_default();
+    if prohibition_section.unwrap_or(false) {
+        generate_prompt_with_prohibitions(&parts, locale_str, &glossary, &|tokens| prohibition_clauses(tokens, locale_str))
+    } else {
+        generate_prompt_with_glossary(&parts, locale_str, &glossary)
+    }
+}
+
+/// Generate chat messages from DSL input text
+///
+/// `_ROLE:system` / `_ROLE:user` / `_ROLE:assistant` markers start a new
+/// message; text before the first marker is a user message.
+///
+/// # Arguments
+/// * `input` - Raw DSL text (with _N: and _ROLE: markers)
+/// * `project_path` - Optional path of the open project (relative `_FILE:` paths start there)
+/// * `locale` - Optional locale code of constraints sections ("ja", "en", "fr")
+/// * `variables` - Optional project variables for `_IF:` sections (unset = false)
+///
+/// # Returns
+/// Messages as `[{role, content}]`
+#[tauri::command]
+pub fn generate_chat_messages(
+    input: String,
+    project_path: Option<String>,
+    locale: Option<String>,
+    variables: Option<HashMap<String, bool>>,
+) -> Vec<ChatMessage> {
+    let locale_str = locale.as_deref().unwrap_or("ja");
+    let parts = parse_project_input(&input, project_path.as_deref(), variables.as_ref(), locale_str);
+    generate_messages(&parts, locale_str)
+}
+
 /// Health check command
 ///
 /// Simple command to verify Tauri communication is working
@@ -45,14 +128,49 @@ pub fn greet(name: String) -> String {
 ///
 /// # Arguments
 /// * `input` - Space-delimited DSL tokens
-/// * `locale` - Optional locale code ("ja" for Japanese, "en" for English)
+/// * `locale` - Optional locale code ("ja", "en", "fr"); None or "auto" detects
+///   the locale of each sentence
+/// * `project_path` - Optional path of the open project (for `_FILE:` / `_INCLUDE:` checks)
+/// * `variables` - Optional project variables (`_IF:` on other variables is reported)
+/// * `glossary` - Optional project glossary (undefined nouns are reported in strict mode,
+///   aliases are used to find variant spellings of a noun)
 ///
 /// # Returns
-/// ValidationResult with errors and warnings
+/// ValidationResult with errors and warnings (and the detected locales
+/// when detecting)
 #[tauri::command]
-pub fn validate_dsl_sequence(input: String, locale: Option<String>) -> ValidationResult {
-    let locale_str = locale.as_deref().unwrap_or("ja");
-    validate_sequence_with_locale(&input, locale_str)
+pub fn validate_dsl_sequence(
+    input: String,
+    locale: Option<String>,
+    project_path: Option<String>,
+    variables: Option<HashMap<String, bool>>,
+    glossary: Option<Glossary>,
+) -> ValidationResult {
+    let (locale_str, mut result) = match locale.as_deref() {
+        None | Some(AUTO_LOCALE) => (detect_input_locale(&input), validate_sequence_auto(&input)),
+        Some(locale) => (locale, validate_sequence_with_locale(&input, locale)),
+    };
+
+    let base_dir = project_base_dir(project_path.as_deref());
+    for error in validate_file_includes(&input, base_dir.as_deref(), locale_str) {
+        result.add_error(error);
+    }
+    for error in validate_project_includes(&input, project_path.as_deref(), locale_str, &load_included_project) {
+        result.add_error(error);
+    }
+    if let Some(variables) = &variables {
+        for error in validate_condition_variables(&input, variables, locale_str) {
+            result.add_error(error);
+        }
+    }
+    let glossary = glossary.unwrap_or_default();
+    for error in validate_glossary_nouns(&input, &glossary, locale_str)
+        .into_iter()
+        .chain(validate_noun_consistency(&input, &glossary, locale_str))
+    {
+        result.add_error(error);
+    }
+    result
 }
 
 // ============================================================================
@@ -63,27 +181,150 @@ pub fn validate_dsl_sequence(input: String, locale: Option<String>) -> Validatio
 ///
 /// # Arguments
 /// * `locale` - Optional locale code ("ja" for Japanese, "en" for English)
+/// * `project_path` - Optional path of the open project (for project-embedded templates)
+///
+/// # Returns
+/// List of pattern templates (built-in, user and project) with their source
+#[tauri::command]
+pub fn get_patterns(locale: Option<String>, project_path: Option<String>) -> Vec<PatternTemplate> {
+    get_pattern_load_report(locale, project_path).patterns
+}
+
+/// Load pattern templates and report invalid template files
+///
+/// # Arguments
+/// * `locale` - Optional locale code ("ja" for Japanese, "en" for English)
+/// * `project_path` - Optional path of the open project (for project-embedded templates)
 ///
 /// # Returns
-/// List of pattern templates for sentence structures
+/// PatternLoadReport with merged templates and load problems
 #[tauri::command]
-pub fn get_patterns(locale: Option<String>) -> Vec<PatternTemplate> {
+pub fn get_pattern_load_report(locale: Option<String>, project_path: Option<String>) -> PatternLoadReport {
     let locale_str = locale.as_deref().unwrap_or("ja");
-    get_pattern_templates_by_locale(locale_str)
+    let project_patterns = project_path
+        .and_then(|path| load_project(path).ok())
+        .map(|project| project.patterns)
+        .unwrap_or_default();
+    load_pattern_templates(locale_str, user_pattern_dir().as_deref(), &project_patterns)
 }
 
 /// Analyze current input against pattern templates
 ///
 /// # Arguments
 /// * `input` - Space-delimited DSL tokens
-/// * `locale` - Optional locale code ("ja" for Japanese, "en" for English)
+/// * `locale` - Optional locale code ("ja", "en", "fr"); None or "auto" detects
+///   the locale of the input
+/// * `project_path` - Optional path of the open project (for project-embedded templates)
 ///
 /// # Returns
 /// List of pattern match results sorted by match score
 #[tauri::command]
-pub fn analyze_dsl_patterns(input: String, locale: Option<String>) -> Vec<PatternMatchResult> {
-    let locale_str = locale.as_deref().unwrap_or("ja");
-    analyze_patterns_with_locale(&input, locale_str)
+pub fn analyze_dsl_patterns(
+    input: String,
+    locale: Option<String>,
+    project_path: Option<String>,
+) -> Vec<PatternMatchResult> {
+    let locale_str = match locale.as_deref() {
+        None | Some(AUTO_LOCALE) => detect_input_locale(&input).to_string(),
+        Some(locale) => locale.to_string(),
+    };
+    let patterns = get_patterns(Some(locale_str.clone()), project_path);
+    analyze_patterns_with_templates(&input, &locale_str, &patterns)
+}
+
+/// Instantiate a pattern template into DSL
+///
+/// # Arguments
+/// * `pattern_id` - Template id (e.g. "sov_basic")
+/// * `locale` - Optional locale code ("ja" for Japanese, "en" for English)
+/// * `slot_values` - Slot values keyed by block label or block index ("0", "1", ...)
+/// * `project_path` - Optional path of the open project (for project-embedded templates)
+///
+/// # Returns
+/// InstantiationResult with DSL, unfilled slots, validation and completeness, or error message
+#[tauri::command]
+pub fn instantiate_pattern(
+    pattern_id: String,
+    locale: Option<String>,
+    slot_values: HashMap<String, String>,
+    project_path: Option<String>,
+) -> Result<InstantiationResult, String> {
+    let locale_str = locale.as_deref().unwrap_or("ja").to_string();
+    let patterns = get_patterns(Some(locale_str.clone()), project_path);
+    instantiate_pattern_template(&pattern_id, &locale_str, &slot_values, &patterns)
+}
+
+/// Suggest block types that can follow the input
+///
+/// # Arguments
+/// * `input` - Space-delimited DSL tokens up to the cursor
+/// * `locale` - Optional locale code ("ja" for Japanese, "en" for English)
+/// * `project_paths` - Optional paths of the user's projects (for usage frequency)
+///
+/// # Returns
+/// List of suggestions ranked by grammar state, pattern matches and usage
+#[tauri::command]
+pub fn suggest_next(
+    input: String,
+    locale: Option<String>,
+    project_paths: Option<Vec<String>>,
+) -> Vec<Suggestion> {
+    let locale_str = locale.as_deref().unwrap_or("ja").to_string();
+    let patterns = get_patterns(Some(locale_str.clone()), None);
+
+    // Projects that fail to load are skipped; they only affect ranking
+    let mut usage = BlockUsage::new();
+    for path in project_paths.unwrap_or_default() {
+        if let Ok(project) = load_project(path) {
+            count_block_usage(&project.workspace, &mut usage);
+        }
+    }
+
+    suggest_next_blocks(&input, &locale_str, &patterns, &usage)
+}
+
+// ============================================================================
+// Cross-locale Translation
+// ============================================================================
+
+/// Translate DSL between locales (e.g. Japanese SOV to English SVO)
+///
+/// # Arguments
+/// * `input` - Space-delimited DSL tokens
+/// * `from` - Source locale code ("ja", "en", "fr")
+/// * `to` - Target locale code ("ja", "en", "fr")
+///
+/// # Returns
+/// TranslationResult with translated DSL and warnings for unmapped tokens
+#[tauri::command]
+pub fn translate_dsl(input: String, from: String, to: String) -> TranslationResult {
+    translate_dsl_sequence(&input, &from, &to)
+}
+
+/// Import DSL text into Blockly workspace JSON
+///
+/// # Arguments
+/// * `input` - Space-delimited DSL tokens (or plain text)
+/// * `locale` - Optional locale code ("ja" for Japanese, "en" for English)
+///
+/// # Returns
+/// Workspace JSON for `Blockly.serialization.workspaces.load`
+#[tauri::command]
+pub fn import_dsl_to_workspace(input: String, locale: Option<String>) -> serde_json::Value {
+    dsl_to_workspace(&input, locale.as_deref().unwrap_or("ja"))
+}
+
+/// Segment plain text (without `_N:` markers or spaces) into DSL
+///
+/// # Arguments
+/// * `input` - Plain-text prompt
+/// * `locale` - Optional locale code ("ja" for Japanese, "en" for English)
+///
+/// # Returns
+/// SegmentationResult with DSL and a confidence score per token
+#[tauri::command]
+pub fn segment_text(input: String, locale: Option<String>) -> SegmentationResult {
+    segment_plain_text(&input, locale.as_deref().unwrap_or("ja"))
 }
 
 // ============================================================================
@@ -110,6 +351,27 @@ pub struct PrompProject {
     pub metadata: ProjectMetadata,
     pub workspace: serde_json::Value,
     pub settings: serde_json::Value,
+    /// Pattern templates embedded in the project
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<PatternTemplate>,
+    /// Chat role of each top-level block stack (top block id -> role)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub roles: HashMap<String, String>,
+    /// Variables for `_IF:` conditional sections (name -> value)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, bool>,
+    /// Projects included at the start of the prompt (paths relative to this file)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<String>,
+    /// Locale the project is written in (included projects are generated with it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Noun definitions appended to the prompt
+    #[serde(default, skip_serializing_if = "Glossary::is_empty")]
+    pub glossary: Glossary,
+    /// Move negative instructions into a "Do not" section of the prompt
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub prohibition_section: bool,
 }
 
 impl PrompProject {
@@ -131,6 +393,13 @@ impl PrompProject {
                 "scrollX": 0,
                 "scrollY": 0
             }),
+            patterns: Vec::new(),
+            roles: HashMap::new(),
+            variables: HashMap::new(),
+            includes: Vec::new(),
+            locale: None,
+            glossary: Glossary::default(),
+            prohibition_section: false,
         }
     }
 }
@@ -248,6 +517,45 @@ pub fn load_project(path: String) -> Result<PrompProject, String> {
     Ok(project)
 }
 
+/// Compile the workspace of a saved project into DSL
+///
+/// # Arguments
+/// * `path` - File path to load from
+/// * `locale` - Optional locale code ("ja" for Japanese, "en" for English)
+///
+/// # Returns
+/// DSL text (same as the editor preview, including `_ROLE:` markers), or error message
+#[tauri::command]
+pub fn get_project_dsl(path: String, locale: Option<String>) -> Result<String, String> {
+    let project = load_project(path)?;
+    Ok(project_dsl(&project, locale.as_deref().unwrap_or("ja")))
+}
+
+/// DSL of a project: project-level includes as leading `_INCLUDE:` tokens,
+/// then the compiled workspace
+fn project_dsl(project: &PrompProject, locale: &str) -> String {
+    project
+        .includes
+        .iter()
+        .map(|path| format!("_INCLUDE:{}", quote_text(path)))
+        .chain(std::iter::once(workspace_to_dsl_with_roles(&project.workspace, locale, &project.roles)))
+        .filter(|dsl| !dsl.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Load a project for `_INCLUDE:` (includes are resolved recursively by the caller)
+///
+/// A project without its own locale is compiled in the includer's `locale`.
+fn load_included_project(path: &Path, locale: &str) -> Result<IncludedProject, String> {
+    let project = load_project(path.to_string_lossy().to_string())?;
+    Ok(IncludedProject {
+        dsl: project_dsl(&project, project.locale.as_deref().unwrap_or(locale)),
+        locale: project.locale,
+        variables: project.variables,
+    })
+}
+
 /// Create a new empty project
 ///
 /// # Arguments
@@ -337,6 +645,8 @@ pub async fn set_window_title(window: tauri::Window, title: String) -> Result<()
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modules::validation::ValidationErrorCode;
+    use crate::modules::workspace::workspace_to_dsl;
 
     #[test]
     fn test_generate_prompt_from_text() {
@@ -470,6 +780,120 @@ mod tests {
         assert!(result.contains("Order_ID"));
     }
 
+    #[test]
+    fn test_generate_prompt_for_project_inlines_file() {
+        let dir = std::env::temp_dir().join("promps_test_cmd_file");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("log.txt"), "ERROR  at line 3").unwrap();
+        let project_path = dir.join("review.promps").to_string_lossy().to_string();
+
+        let input = "_FILE:log.txt を 分析して".to_string();
+        let result = generate_prompt_for_project(input.clone(), Some(project_path.clone()), None, None, None, None);
+        let validation = validate_dsl_sequence(input, None, Some(project_path.clone()), None, None);
+
+        assert!(result.contains("log.txt (FILE)\n```txt\nERROR  at line 3\n```"));
+        assert!(!validation.errors.iter().any(|e| e.code == ValidationErrorCode::FileNotFound));
+
+        let missing = validate_dsl_sequence("_FILE:nope.txt を 分析して".to_string(), None, Some(project_path), None, None);
+        assert!(missing.errors.iter().any(|e| e.code == ValidationErrorCode::FileNotFound));
+        assert!(!missing.is_valid);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_generate_prompt_for_project_constraints_locale() {
+        let input = "_N:Doc を 要約して _FMT:json _LANG:en".to_string();
+
+        let ja = generate_prompt_for_project(input.clone(), None, None, None, None, None);
+        let en = generate_prompt_for_project(input, None, Some("en".to_string()), None, None, None);
+
+        assert!(ja.ends_with("制約:\n- 形式: JSON\n- 言語: 英語\n"));
+        assert!(en.ends_with("Constraints:\n- Format: JSON\n- Language: English\n"));
+    }
+
+    #[test]
+    fn test_generate_prompt_for_project_prohibition_section() {
+        let input = "_N:Doc を 要約して 。 _N:名前 を 含めないで 。".to_string();
+
+        let plain = generate_prompt_for_project(input.clone(), None, None, None, None, None);
+        let collected = generate_prompt_for_project(input, None, None, None, None, Some(true));
+
+        assert_eq!(plain, "Doc (NOUN) を 要約して 。 名前 (NOUN) を 含めないで 。\n");
+        assert_eq!(collected, "Doc (NOUN) を 要約して 。\n禁止事項:\n- 名前 (NOUN) を 含めないで 。\n");
+    }
+
+    #[test]
+    fn test_prohibition_section_splits_clauses() {
+        let generate = |input: &str, locale: &str| {
+            generate_prompt_for_project(input.to_string(), None, Some(locale.to_string()), None, None, Some(true))
+        };
+
+        assert_eq!(
+            generate("_N:A を 分析して 、 _N:B を 削除しないでください 。", "ja"),
+            "A (NOUN) を 分析して 、\n禁止事項:\n- B (NOUN) を 削除しないでください 。\n"
+        );
+        assert_eq!(
+            generate("use the _N:API , not the _N:CLI .", "en"),
+            "use the API (NOUN) , not the CLI (NOUN) .\n"
+        );
+        assert_eq!(
+            generate("utilisez n'importe quel _N:format .", "fr"),
+            "utilisez n'importe quel format (NOUN) .\n"
+        );
+    }
+
+    #[test]
+    fn test_project_variables_select_variant() {
+        let input = "_N:Doc を 要約して _IF:formal 丁寧に _ELSE 簡潔に _ENDIF _IF:draft 下書き _ENDIF".to_string();
+        let variables = HashMap::from([("formal".to_string(), true)]);
+
+        let formal = generate_prompt_for_project(input.clone(), None, None, Some(variables.clone()), None, None);
+        let casual = generate_prompt_for_project(input.clone(), None, None, None, None, None);
+        assert_eq!(formal, "Doc (NOUN) を 要約して 丁寧に\n");
+        assert_eq!(casual, "Doc (NOUN) を 要約して 簡潔に\n");
+
+        let validation = validate_dsl_sequence(input, None, None, Some(variables), None);
+        let unknown: Vec<usize> = validation
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::UnknownCondition)
+            .map(|e| e.position)
+            .collect();
+        assert_eq!(unknown, vec![8]);
+    }
+
+    #[test]
+    fn test_project_glossary() {
+        let json = r#"{"version": "1.0.0",
+            "metadata": {"name": "Shop", "createdAt": "2026-01-01T00:00:00Z", "modifiedAt": "2026-01-01T00:00:00Z"},
+            "workspace": {}, "settings": {},
+            "glossary": {"strict": true, "entries": [
+                {"term": "Order", "definition": "a customer purchase request", "aliases": ["注文"]}
+            ]}}"#;
+        let project: PrompProject = serde_json::from_str(json).unwrap();
+        let input = "_N:注文 を _N:顧客 に 送って".to_string();
+
+        let prompt = generate_prompt_for_project(input.clone(), None, Some("en".to_string()), None, Some(project.glossary.clone()), None);
+        let validation = validate_dsl_sequence(input, None, None, None, Some(project.glossary));
+
+        assert!(prompt.ends_with("Definitions:\n- Order: a customer purchase request\n"));
+        assert_eq!(
+            validation.errors.iter().filter(|e| e.code == ValidationErrorCode::UndefinedNoun).count(),
+            1
+        );
+        assert!(!serde_json::to_string(&create_new_project("New".to_string())).unwrap().contains("glossary"));
+    }
+
+    #[test]
+    fn test_generate_prompt_from_text_code_block() {
+        let input = "_N:JSON を 検証して\n_CODE:json\n{\n  \"a\":  [1, 2]\n}\n_END".to_string();
+        let result = generate_prompt_from_text(input);
+
+        assert!(result.contains("```json\n{\n  \"a\":  [1, 2]\n}\n```"));
+    }
+
     #[test]
     fn test_greet_with_empty_name() {
         let result = greet("".to_string());
@@ -596,7 +1020,7 @@ mod tests {
 
     #[test]
     fn test_validate_dsl_sequence_valid() {
-        let result = validate_dsl_sequence("_N:User が _N:Document を 分析して".to_string(), None);
+        let result = validate_dsl_sequence("_N:User が _N:Document を 分析して".to_string(), None, None, None, None);
 
         assert!(result.is_valid);
         assert_eq!(result.error_count, 0);
@@ -605,7 +1029,7 @@ mod tests {
 
     #[test]
     fn test_validate_dsl_sequence_particle_without_noun() {
-        let result = validate_dsl_sequence("が _N:User".to_string(), None);
+        let result = validate_dsl_sequence("が _N:User".to_string(), None, None, None, None);
 
         assert!(!result.is_valid);
         assert_eq!(result.error_count, 1);
@@ -614,7 +1038,7 @@ mod tests {
 
     #[test]
     fn test_validate_dsl_sequence_consecutive_particles() {
-        let result = validate_dsl_sequence("_N:User が を".to_string(), None);
+        let result = validate_dsl_sequence("_N:User が を".to_string(), None, None, None, None);
 
         assert!(!result.is_valid);
         assert!(result.error_count >= 1);
@@ -622,7 +1046,7 @@ mod tests {
 
     #[test]
     fn test_validate_dsl_sequence_empty() {
-        let result = validate_dsl_sequence("".to_string(), None);
+        let result = validate_dsl_sequence("".to_string(), None, None, None, None);
 
         assert!(result.is_valid);
         assert_eq!(result.error_count, 0);
@@ -632,7 +1056,7 @@ mod tests {
 
     #[test]
     fn test_validate_dsl_sequence_en_valid() {
-        let result = validate_dsl_sequence("analyze _N:document".to_string(), Some("en".to_string()));
+        let result = validate_dsl_sequence("analyze _N:document".to_string(), Some("en".to_string()), None, None, None);
 
         assert!(result.is_valid);
         assert_eq!(result.error_count, 0);
@@ -640,7 +1064,7 @@ mod tests {
 
     #[test]
     fn test_validate_dsl_sequence_en_with_article() {
-        let result = validate_dsl_sequence("summarize the _N:report".to_string(), Some("en".to_string()));
+        let result = validate_dsl_sequence("summarize the _N:report".to_string(), Some("en".to_string()), None, None, None);
 
         assert!(result.is_valid);
         assert_eq!(result.error_count, 0);
@@ -649,7 +1073,7 @@ mod tests {
     #[test]
     fn test_validate_dsl_sequence_en_article_error() {
         // Article not followed by noun
-        let result = validate_dsl_sequence("the analyze _N:document".to_string(), Some("en".to_string()));
+        let result = validate_dsl_sequence("the analyze _N:document".to_string(), Some("en".to_string()), None, None, None);
 
         assert!(!result.is_valid);
         assert!(result.error_count >= 1);
@@ -657,10 +1081,279 @@ mod tests {
 
     #[test]
     fn test_validate_dsl_sequence_en_consecutive_articles() {
-        let result = validate_dsl_sequence("the a _N:document".to_string(), Some("en".to_string()));
+        let result = validate_dsl_sequence("the a _N:document".to_string(), Some("en".to_string()), None, None, None);
 
         assert!(!result.is_valid);
         // Should have consecutive articles error
         assert!(result.errors.iter().any(|e| format!("{:?}", e.code).contains("ConsecutiveArticles")));
     }
+
+    #[test]
+    fn test_validate_dsl_sequence_detects_locale() {
+        let result = validate_dsl_sequence("the a _N:document".to_string(), None, None, None, None);
+
+        assert_eq!(result.detected_locales[0].locale, "en");
+        assert!(result.errors.iter().any(|e| format!("{:?}", e.code).contains("ConsecutiveArticles")));
+    }
+
+    // Cross-locale Translation Tests
+
+    #[test]
+    fn test_translate_dsl_ja_to_en() {
+        let result = translate_dsl(
+            "_N:ドキュメント を 要約して".to_string(),
+            "ja".to_string(),
+            "en".to_string(),
+        );
+
+        assert_eq!(result.output, "_V:summarize the _N:ドキュメント");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_translate_dsl_output_validates_in_target_locale() {
+        let result = translate_dsl(
+            "_N:英語 から _N:日本語 に _N:メール を 翻訳して".to_string(),
+            "ja".to_string(),
+            "fr".to_string(),
+        );
+        let validation = validate_dsl_sequence(result.output, Some("fr".to_string()), None, None, None);
+
+        assert!(validation.is_valid);
+    }
+
+    // Pattern Template Loading Tests
+
+    #[test]
+    fn test_get_patterns_builtin_source() {
+        let patterns = get_patterns(Some("en".to_string()), None);
+
+        assert!(patterns.len() >= 7);
+        assert!(patterns.iter().any(|p| p.id == "svo_basic"));
+    }
+
+    #[test]
+    fn test_get_patterns_with_project_templates() {
+        let temp_file = std::env::temp_dir().join("promps_test_project_patterns.promps");
+        let mut project = create_new_project("Patterns".to_string());
+        project.patterns.push(PatternTemplate::new(
+            "project_only",
+            "Project Pattern",
+            "",
+            "名詞 を 動詞",
+            "",
+            vec![crate::modules::validation::PatternBlock::placeholder("promps_noun", "目的語")],
+        ));
+        let path = temp_file.to_string_lossy().to_string();
+        save_project(path.clone(), project).unwrap();
+
+        let patterns = get_patterns(None, Some(path.clone()));
+        let results = analyze_dsl_patterns("_N:Doc".to_string(), None, Some(path));
+        let _ = fs::remove_file(&temp_file);
+
+        let project_pattern = patterns.iter().find(|p| p.id == "project_only").unwrap();
+        assert_eq!(project_pattern.source, crate::modules::validation::PatternSource::Project);
+        assert!(results.iter().any(|r| r.pattern_id == "project_only" && r.is_complete));
+    }
+
+    // Completion Tests
+
+    #[test]
+    fn test_suggest_next_uses_project_usage() {
+        let temp_file = std::env::temp_dir().join("promps_test_suggest_usage.promps");
+        let mut project = create_new_project("Usage".to_string());
+        project.workspace = serde_json::json!({
+            "blocks": {"blocks": [{"type": "promps_particle_kara"}, {"type": "promps_particle_kara"}]}
+        });
+        let path = temp_file.to_string_lossy().to_string();
+        save_project(path.clone(), project).unwrap();
+
+        let suggestions = suggest_next("_N:英語".to_string(), None, Some(vec![path]));
+        let _ = fs::remove_file(&temp_file);
+
+        let rank = |bt: &str| suggestions.iter().position(|s| s.block_type == bt).unwrap();
+        assert!(rank("promps_particle_kara") < rank("promps_particle_made"));
+    }
+
+    #[test]
+    fn test_suggest_next_skips_missing_projects() {
+        let suggestions = suggest_next(
+            "_N:Doc を".to_string(),
+            Some("ja".to_string()),
+            Some(vec!["/nonexistent/project.promps".to_string()]),
+        );
+
+        assert!(!suggestions.is_empty());
+    }
+
+    // Pattern Instantiation Tests
+
+    #[test]
+    fn test_instantiate_pattern_command() {
+        let mut values = HashMap::new();
+        values.insert("目的語".to_string(), "ドキュメント".to_string());
+
+        let result = instantiate_pattern("ov_simple".to_string(), None, values, None).unwrap();
+
+        assert_eq!(result.dsl, "_N:ドキュメント を _V:要約して");
+        assert!(result.complete);
+    }
+
+    #[test]
+    fn test_instantiate_pattern_command_unknown_id() {
+        let result = instantiate_pattern("nope".to_string(), None, HashMap::new(), None);
+        assert!(result.is_err());
+    }
+
+    // Workspace Compilation Tests
+
+    #[test]
+    fn test_get_project_dsl() {
+        let temp_file = std::env::temp_dir().join("promps_test_project_dsl.promps");
+        let mut project = create_new_project("Dsl".to_string());
+        project.workspace = serde_json::json!({
+            "blocks": {"languageVersion": 0, "blocks": [{
+                "type": "promps_noun", "x": 10, "y": 10, "fields": {"TEXT": "Doc"},
+                "next": {"block": {"type": "promps_particle_wo",
+                    "next": {"block": {"type": "promps_verb_summarize"}}}}
+            }]}
+        });
+        let path = temp_file.to_string_lossy().to_string();
+        save_project(path.clone(), project).unwrap();
+
+        let dsl = get_project_dsl(path, None);
+        let _ = fs::remove_file(&temp_file);
+
+        assert_eq!(dsl.unwrap(), "_N:Doc を _V:要約して");
+    }
+
+    #[test]
+    fn test_get_project_dsl_with_roles() {
+        let temp_file = std::env::temp_dir().join("promps_test_project_roles.promps");
+        let mut project = create_new_project("Roles".to_string());
+        project.workspace = serde_json::json!({
+            "blocks": {"languageVersion": 0, "blocks": [
+                {"type": "promps_other", "id": "sys", "x": 10, "y": 10, "fields": {"TEXT": "簡潔に"}},
+                {"type": "promps_noun", "id": "usr", "x": 10, "y": 100, "fields": {"TEXT": "Doc"}}
+            ]}
+        });
+        project.roles.insert("sys".to_string(), "system".to_string());
+        let path = temp_file.to_string_lossy().to_string();
+        save_project(path.clone(), project).unwrap();
+
+        let loaded = load_project(path.clone()).unwrap();
+        let dsl = get_project_dsl(path, None);
+        let _ = fs::remove_file(&temp_file);
+
+        assert_eq!(loaded.roles.get("sys").map(String::as_str), Some("system"));
+        assert_eq!(dsl.unwrap(), "_ROLE:system 簡潔に _N:Doc");
+    }
+
+    #[test]
+    fn test_project_without_roles_field() {
+        let json = r#"{"version": "1.0.0",
+            "metadata": {"name": "Old", "createdAt": "2026-01-01T00:00:00Z", "modifiedAt": "2026-01-01T00:00:00Z"},
+            "workspace": {}, "settings": {}}"#;
+        let project: PrompProject = serde_json::from_str(json).unwrap();
+
+        assert!(project.roles.is_empty());
+        assert!(!serde_json::to_string(&project).unwrap().contains("roles"));
+    }
+
+    #[test]
+    fn test_project_includes_resolved_from_saved_projects() {
+        let dir = std::env::temp_dir().join("promps_test_cmd_includes");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut style = create_new_project("Style".to_string());
+        style.locale = Some("en".to_string());
+        style.workspace = serde_json::json!({
+            "blocks": {"languageVersion": 0, "blocks": [
+                {"type": "promps_other", "x": 10, "y": 10, "fields": {"TEXT": "Be concise"}}
+            ]}
+        });
+        save_project(dir.join("style.promps").to_string_lossy().to_string(), style).unwrap();
+
+        let mut main = create_new_project("Main".to_string());
+        main.includes.push("style.promps".to_string());
+        let main_path = dir.join("main.promps").to_string_lossy().to_string();
+        save_project(main_path.clone(), main).unwrap();
+
+        let dsl = get_project_dsl(main_path.clone(), None).unwrap();
+        let prompt = generate_prompt_for_project(
+            format!("{} _N:Doc を 要約して", dsl),
+            Some(main_path.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
+        let cycle = validate_dsl_sequence("_INCLUDE:main.promps".to_string(), None, Some(main_path), None, None);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(dsl, "_INCLUDE:style.promps");
+        assert_eq!(prompt, "Be concise\nDoc (NOUN) を 要約して\n");
+        assert!(cycle.errors.iter().any(|e| e.code == ValidationErrorCode::IncludeCycle));
+    }
+
+    #[test]
+    fn test_included_project_without_locale_uses_includer_locale() {
+        let dir = std::env::temp_dir().join("promps_test_cmd_include_locale");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut shared = create_new_project("Shared".to_string());
+        shared.workspace = serde_json::json!({
+            "blocks": {"languageVersion": 0, "blocks": [
+                {"type": "promps_verb_summarize", "x": 10, "y": 10}
+            ]}
+        });
+        let path = dir.join("shared.promps");
+        save_project(path.to_string_lossy().to_string(), shared).unwrap();
+
+        let en = load_included_project(&path, "en").unwrap();
+        let fr = load_included_project(&path, "fr").unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(en.dsl, "_V:summarize");
+        assert_eq!(fr.dsl, "_V:résumer");
+    }
+
+    #[test]
+    fn test_generate_chat_messages() {
+        let input = "_ROLE:system _N:JSON で 答えて _ROLE:user _N:Doc を 要約して".to_string();
+        let messages = generate_chat_messages(input, None, None, None);
+        let json = serde_json::to_value(&messages).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"role": "system", "content": "JSON (NOUN) で 答えて"},
+                {"role": "user", "content": "Doc (NOUN) を 要約して"}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_get_project_dsl_missing_file() {
+        assert!(get_project_dsl("/nonexistent/project.promps".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_import_dsl_to_workspace_round_trip() {
+        let input = "_N:ユーザー が _N:ドキュメント を _V:分析して 。";
+        let workspace = import_dsl_to_workspace(input.to_string(), None);
+
+        assert_eq!(workspace_to_dsl(&workspace, "ja"), input);
+    }
+
+    #[test]
+    fn test_segment_text_then_import() {
+        let result = segment_text("ドキュメントを要約して".to_string(), None);
+        let workspace = import_dsl_to_workspace(result.dsl.clone(), None);
+
+        assert_eq!(result.dsl, "_N:ドキュメント を _V:要約して");
+        assert_eq!(workspace_to_dsl(&workspace, "ja"), result.dsl);
+    }
 }
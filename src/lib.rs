@@ -3,12 +3,151 @@
 /// This module contains the core DSL parsing and prompt generation logic
 /// from Phase 0, now available as a library for both CLI and GUI (Tauri) usage.
 
+pub mod tokenizer;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+use tokenizer::{quote_text, tokenize, Token};
+
+/// Chat roles accepted by `_ROLE:` markers
+pub const ROLES: [&str; 3] = ["system", "user", "assistant"];
+
+/// Role of text that comes before any `_ROLE:` marker
+pub const DEFAULT_ROLE: &str = "user";
+
+/// Kind of prompt part
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PartKind {
+    /// Regular text (noun or everything else)
+    #[default]
+    Text,
+    /// Verbatim block (`_CODE:lang` ... `_END`), rendered as a fenced block
+    Code { lang: String },
+    /// File inclusion (`_FILE:path`); `content` is filled in by the backend
+    /// at generation time, unresolved files are rendered as a label only
+    File { path: String, content: Option<String> },
+    /// Sub-prompt inclusion (`_INCLUDE:path.promps`); `content` is the
+    /// generated prompt of the included project, filled in by the backend
+    Include { path: String, content: Option<String> },
+    /// Chat role section marker (`_ROLE:system`); `text` holds the role
+    Role,
+    /// Few-shot example (`_IN:"..." _OUT:"..."`); a missing side is empty
+    Example { input: String, output: String },
+    /// Output constraint (`_FMT:json`, `_MAXWORDS:200`, ...), rendered in
+    /// the constraints section at the end of the prompt
+    Constraint { kind: ConstraintKind, value: String },
+    /// Conditional section (`_IF:var` ... `_ELSE` ... `_ENDIF`), evaluated
+    /// against the variable map by `evaluate_conditions`
+    Conditional {
+        condition: Condition,
+        then_parts: Vec<PromptPart>,
+        else_parts: Vec<PromptPart>,
+    },
+    /// List section (`_LIST` ... `_ENDLIST`, numbered with `_LIST:numbered`),
+    /// rendered one item per line
+    List { numbered: bool, items: Vec<Vec<PromptPart>> },
+}
+
+/// Condition of an `_IF:` section: a variable, optionally negated (`_IF:!var`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    pub variable: String,
+    pub negated: bool,
+}
+
+impl Condition {
+    /// Parse the text of an `_IF:` token
+    pub fn parse(text: &str) -> Self {
+        match text.strip_prefix('!') {
+            Some(variable) => Condition { variable: variable.to_string(), negated: true },
+            None => Condition { variable: text.to_string(), negated: false },
+        }
+    }
+
+    /// Evaluate against the variable map (unset variables are false)
+    pub fn evaluate(&self, variables: &HashMap<String, bool>) -> bool {
+        variables.get(&self.variable).copied().unwrap_or(false) != self.negated
+    }
+}
+
+/// Check if a token is a bare keyword such as `_ELSE` or `_ENDIF`
+/// (escaped `\_ELSE` is literal text)
+pub fn is_keyword_token(token: &Token, keyword: &str) -> bool {
+    token.marker.is_none() && !token.escaped && token.text == keyword
+}
+
+/// Words that separate the items of a `_LIST` section (new lines do too)
+pub const LIST_SEPARATORS: [&str; 8] = ["、", ",", "，", ";", "と", "や", "and", "et"];
+
+/// Check if a token opens a list section (`_LIST`, `_LIST:numbered`)
+pub fn is_list_start(token: &Token) -> bool {
+    is_keyword_token(token, "_LIST") || token.marker.as_deref() == Some("LIST")
+}
+
+/// Check if a token separates list items (`_N:A と _N:B`, `_N:A , _N:B`)
+pub fn is_list_separator(token: &Token) -> bool {
+    token.marker.is_none() && !token.escaped && LIST_SEPARATORS.contains(&token.text.to_lowercase().as_str())
+}
+
+/// Output constraint kinds, in the order they are rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConstraintKind {
+    /// `_FMT:` - output format (json, markdown, ...)
+    Format,
+    /// `_MAXWORDS:` - maximum number of words
+    MaxWords,
+    /// `_LANG:` - output language code (en, ja, fr, ...)
+    Language,
+    /// `_TONE:` - tone (formal, casual, ...)
+    Tone,
+}
+
+impl ConstraintKind {
+    /// Constraint kind of a token marker
+    pub fn from_marker(marker: &str) -> Option<Self> {
+        match marker {
+            "FMT" => Some(ConstraintKind::Format),
+            "MAXWORDS" => Some(ConstraintKind::MaxWords),
+            "LANG" => Some(ConstraintKind::Language),
+            "TONE" => Some(ConstraintKind::Tone),
+            _ => None,
+        }
+    }
+
+    /// Token marker of the constraint kind
+    pub fn marker(&self) -> &'static str {
+        match self {
+            ConstraintKind::Format => "FMT",
+            ConstraintKind::MaxWords => "MAXWORDS",
+            ConstraintKind::Language => "LANG",
+            ConstraintKind::Tone => "TONE",
+        }
+    }
+}
+
+/// Role of a `_ROLE:` marker token (case-insensitive)
+///
+/// # Returns
+/// The role name, or `None` if the token is not a role marker or the role is unknown
+pub fn token_role(token: &Token) -> Option<&'static str> {
+    if token.marker.as_deref() != Some("ROLE") {
+        return None;
+    }
+    let role = token.text.to_lowercase();
+    ROLES.iter().find(|r| **r == role).copied()
+}
+
 /// Represents a single prompt part
-/// Two types: "Noun" (名詞) and "Everything else" (それ以外)
-#[derive(Debug, Clone)]
+/// Two types: "Noun" (名詞) and "Everything else" (それ以外),
+/// plus verbatim code blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PromptPart {
     pub is_noun: bool,
     pub text: String,
+    pub kind: PartKind,
 }
 
 impl PromptPart {
@@ -20,12 +159,14 @@ impl PromptPart {
             PromptPart {
                 is_noun: true,
                 text: text.to_string(),
+                kind: PartKind::Text,
             }
         } else {
             // Everything else (それ以外)
             PromptPart {
                 is_noun: false,
                 text: token.to_string(),
+                kind: PartKind::Text,
             }
         }
     }
@@ -35,99 +176,457 @@ impl PromptPart {
 ///
 /// # Arguments
 /// * `input` - Raw input text with space-delimited tokens
+///   (quoted `_N:"New York"`, escaped tokens and `_CODE:` verbatim blocks
+///   are supported, see `tokenizer`)
 ///
 /// # Returns
 /// Vector of PromptPart instances
 pub fn parse_input(input: &str) -> Vec<PromptPart> {
     let mut parts = Vec::new();
 
-    for line in input.lines() {
-        let line = line.trim();
+    // Phase 0-1 behavior: Token-level noun detection
+    // Each _N: token becomes a separate PromptPart with is_noun=true
+    // This allows multiple nouns in a single sentence to each have (NOUN) markers
+    let mut current_text = String::new();
 
-        // Skip empty lines
-        if line.is_empty() {
-            continue;
+    fn flush(parts: &mut Vec<PromptPart>, current_text: &mut String) {
+        if !current_text.is_empty() {
+            parts.push(PromptPart {
+                is_noun: false,
+                text: current_text.trim().to_string(),
+                kind: PartKind::Text,
+            });
+            current_text.clear();
         }
+    }
 
-        // Split by double spaces (sentence delimiter)
-        let sentences = line.split("  ");
+    // Open _LIST section: (numbered, parts before the section, items, separators seen)
+    type OpenList = (bool, Vec<PromptPart>, Vec<Vec<PromptPart>>, bool);
 
-        for sentence in sentences {
-            let sentence = sentence.trim();
-            if sentence.is_empty() {
-                continue;
+    // Open sections, innermost last: conditions and lists share one stack so
+    // that crossed sections (`_LIST _IF:x ... _ENDLIST ... _ENDIF`) cannot mix
+    // their parts; closing a section closes the ones opened inside it
+    enum Section {
+        // (condition, parts before the section, then-branch once _ELSE is seen)
+        Condition(Condition, Vec<PromptPart>, Option<Vec<PromptPart>>),
+        List(OpenList),
+    }
+    let mut open_sections: Vec<Section> = Vec::new();
+
+    fn close_sections(parts: &mut Vec<PromptPart>, open_sections: &mut Vec<Section>, from: usize) {
+        for section in open_sections.drain(from..).rev() {
+            match section {
+                Section::Condition(condition, outer, then_parts) => close_section(parts, (condition, outer, then_parts)),
+                Section::List(list) => close_list(parts, list),
             }
+        }
+    }
 
-            // Collect all tokens in this sentence
-            let tokens: Vec<&str> = sentence.split_whitespace().collect();
-            if tokens.is_empty() {
+    fn close_section(
+        parts: &mut Vec<PromptPart>,
+        (condition, outer, then_parts): (Condition, Vec<PromptPart>, Option<Vec<PromptPart>>),
+    ) {
+        let branch = std::mem::replace(parts, outer);
+        let (then_parts, else_parts) = match then_parts {
+            Some(then_parts) => (then_parts, branch),
+            None => (branch, Vec::new()),
+        };
+        parts.push(PromptPart {
+            is_noun: false,
+            text: String::new(),
+            kind: PartKind::Conditional { condition, then_parts, else_parts },
+        });
+    }
+
+    fn end_item(parts: &mut Vec<PromptPart>, items: &mut Vec<Vec<PromptPart>>) -> bool {
+        if parts.is_empty() {
+            return false;
+        }
+        items.push(std::mem::take(parts));
+        true
+    }
+
+    fn close_list(parts: &mut Vec<PromptPart>, (numbered, outer, mut items, separated): OpenList) {
+        end_item(parts, &mut items);
+        // Without separators, each noun starts an item (`_LIST _N:A _N:B _ENDLIST`)
+        if !separated {
+            items = split_at_nouns(items.concat());
+        }
+        *parts = outer;
+        parts.push(PromptPart {
+            is_noun: false,
+            text: String::new(),
+            kind: PartKind::List { numbered, items },
+        });
+    }
+
+    for token in tokenize(input) {
+        // Sentence delimiter (double space or new line)
+        if token.sentence_break {
+            flush(&mut parts, &mut current_text);
+            // New lines separate list items
+            if let Some(Section::List((_, _, items, separated))) = open_sections.last_mut() {
+                *separated |= end_item(&mut parts, items);
+            }
+        }
+
+        let open_list = open_sections.iter().rposition(|s| matches!(s, Section::List(_)));
+        let open_condition = open_sections.iter().rposition(|s| matches!(s, Section::Condition(..)));
+
+        // List sections (lists do not nest; a stray _ENDLIST stays as text)
+        if is_list_start(&token) && open_list.is_none() {
+            flush(&mut parts, &mut current_text);
+            let numbered = token.text.eq_ignore_ascii_case("numbered");
+            open_sections.push(Section::List((numbered, std::mem::take(&mut parts), Vec::new(), false)));
+            continue;
+        }
+        if let Some(Section::List((_, _, items, separated))) = open_sections.last_mut() {
+            if is_list_separator(&token) {
+                flush(&mut parts, &mut current_text);
+                end_item(&mut parts, items);
+                *separated = true;
+                continue;
+            }
+        }
+        if is_keyword_token(&token, "_ENDLIST") {
+            if let Some(list) = open_list {
+                flush(&mut parts, &mut current_text);
+                close_sections(&mut parts, &mut open_sections, list);
                 continue;
             }
+        }
 
-            // Phase 0-1 behavior: Token-level noun detection
-            // Each _N: token becomes a separate PromptPart with is_noun=true
-            // This allows multiple nouns in a single sentence to each have (NOUN) markers
-            let mut current_text = String::new();
-            let mut current_is_noun = false;
-            let mut first_in_part = true;
-
-            for token in tokens {
-                match token.strip_prefix("_N:") {
-                    Some(stripped) => {
-                        // Found a noun token - flush current part if any
-                        if !current_text.is_empty() {
-                            parts.push(PromptPart {
-                                is_noun: current_is_noun,
-                                text: current_text.trim().to_string(),
-                            });
-                            current_text.clear();
-                            first_in_part = true;
-                        }
-
-                        // Create a new part for this noun
-                        parts.push(PromptPart {
-                            is_noun: true,
-                            text: stripped.to_string(),
-                        });
-                        current_is_noun = false;
-                    }
-                    None => {
-                        // Regular token - accumulate into current part
-                        // Strip _V: prefix (verb marker for validation only)
-                        let token_text = token.strip_prefix("_V:").unwrap_or(token);
-                        if !first_in_part {
-                            current_text.push(' ');
-                        }
-                        current_text.push_str(token_text);
-                        first_in_part = false;
+        // Conditional sections (stray _ELSE / _ENDIF stay as text)
+        if token.marker.as_deref() == Some("IF") {
+            flush(&mut parts, &mut current_text);
+            open_sections.push(Section::Condition(Condition::parse(&token.text), std::mem::take(&mut parts), None));
+            continue;
+        }
+        if is_keyword_token(&token, "_ELSE") {
+            if let Some(condition) = open_condition {
+                if let Section::Condition(_, _, None) = open_sections[condition] {
+                    flush(&mut parts, &mut current_text);
+                    close_sections(&mut parts, &mut open_sections, condition + 1);
+                    if let Some(Section::Condition(_, _, then_parts)) = open_sections.last_mut() {
+                        *then_parts = Some(std::mem::take(&mut parts));
                     }
+                    continue;
                 }
             }
+        }
+        if is_keyword_token(&token, "_ENDIF") {
+            if let Some(condition) = open_condition {
+                flush(&mut parts, &mut current_text);
+                close_sections(&mut parts, &mut open_sections, condition);
+                continue;
+            }
+        }
 
-            // Flush remaining accumulated text
-            if !current_text.is_empty() {
-                parts.push(PromptPart {
-                    is_noun: current_is_noun,
-                    text: current_text.trim().to_string(),
-                });
+        if let Some(content) = token.verbatim {
+            // Verbatim block - kept as its own part, whitespace untouched
+            flush(&mut parts, &mut current_text);
+            parts.push(PromptPart {
+                is_noun: false,
+                text: content,
+                kind: PartKind::Code { lang: token.text },
+            });
+            continue;
+        }
+
+        if let Some(role) = token_role(&token) {
+            // Role marker - starts a new chat section (and ends an open list)
+            flush(&mut parts, &mut current_text);
+            if let Some(list) = open_list {
+                close_sections(&mut parts, &mut open_sections, list);
             }
+            parts.push(PromptPart {
+                is_noun: false,
+                text: role.to_string(),
+                kind: PartKind::Role,
+            });
+            continue;
+        }
+
+        if let Some(kind) = token.marker.as_deref().and_then(ConstraintKind::from_marker) {
+            // Output constraint - collected into the constraints section
+            flush(&mut parts, &mut current_text);
+            parts.push(PromptPart {
+                is_noun: false,
+                text: token.text.clone(),
+                kind: PartKind::Constraint { kind, value: token.text },
+            });
+            continue;
+        }
+
+        if token.marker.as_deref() == Some("IN") {
+            // Example input - waits for its _OUT: token
+            flush(&mut parts, &mut current_text);
+            parts.push(PromptPart {
+                is_noun: false,
+                text: String::new(),
+                kind: PartKind::Example { input: token.text, output: String::new() },
+            });
+            continue;
+        }
+
+        if token.marker.as_deref() == Some("OUT") {
+            // Example output - completes the directly preceding _IN:
+            let pending = if current_text.is_empty() {
+                match parts.last_mut() {
+                    Some(PromptPart { kind: PartKind::Example { output, .. }, .. }) if output.is_empty() => Some(output),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            match pending {
+                Some(output) => *output = token.text,
+                None => {
+                    flush(&mut parts, &mut current_text);
+                    parts.push(PromptPart {
+                        is_noun: false,
+                        text: String::new(),
+                        kind: PartKind::Example { input: String::new(), output: token.text },
+                    });
+                }
+            }
+            continue;
+        }
+
+        if token.marker.as_deref() == Some("FILE") {
+            // File inclusion - resolved later by the backend
+            flush(&mut parts, &mut current_text);
+            parts.push(PromptPart {
+                is_noun: false,
+                text: token.text.clone(),
+                kind: PartKind::File { path: token.text, content: None },
+            });
+            continue;
+        }
+
+        if token.marker.as_deref() == Some("INCLUDE") {
+            // Included project - generated and inlined later by the backend
+            flush(&mut parts, &mut current_text);
+            parts.push(PromptPart {
+                is_noun: false,
+                text: token.text.clone(),
+                kind: PartKind::Include { path: token.text, content: None },
+            });
+            continue;
+        }
+
+        if token.is_noun() {
+            // Found a noun token - flush current part if any
+            flush(&mut parts, &mut current_text);
+
+            // Create a new part for this noun
+            parts.push(PromptPart {
+                is_noun: true,
+                text: token.text,
+                kind: PartKind::Text,
+            });
+            continue;
         }
+
+        // Regular token - accumulate into current part
+        // Strip _V: / _ADJ: / _ADV: prefixes (markers for validation only)
+        let token_text = match token.marker.as_deref() {
+            Some("V" | "ADJ" | "ADV") | None => token.text,
+            Some(marker) => format!("_{}:{}", marker, token.text),
+        };
+        if !current_text.is_empty() {
+            current_text.push(' ');
+        }
+        current_text.push_str(&token_text);
     }
 
+    // Flush remaining accumulated text
+    flush(&mut parts, &mut current_text);
+
+    // Unclosed lists and sections end at the end of the input
+    close_sections(&mut parts, &mut open_sections, 0);
+
     parts
 }
 
+/// Split list content without separators so that each noun starts an item
+///
+/// Text before a noun stays in the noun's item (`_LIST 重要な _N:A _N:B`).
+fn split_at_nouns(parts: Vec<PromptPart>) -> Vec<Vec<PromptPart>> {
+    let mut items: Vec<Vec<PromptPart>> = Vec::new();
+
+    for part in parts {
+        let starts_item = part.is_noun && items.last().is_none_or(|item| item.iter().any(|p| p.is_noun));
+        match items.last_mut() {
+            Some(item) if !starts_item => item.push(part),
+            _ => items.push(vec![part]),
+        }
+    }
+
+    items
+}
+
+/// Evaluate conditional sections against a variable map
+///
+/// # Arguments
+/// * `parts` - Parts from `parse_input`
+/// * `variables` - Variable values (unset variables are false)
+///
+/// # Returns
+/// Flat parts with the chosen branch of every section inlined
+pub fn evaluate_conditions(parts: &[PromptPart], variables: &HashMap<String, bool>) -> Vec<PromptPart> {
+    let mut result = Vec::new();
+
+    for part in parts {
+        match &part.kind {
+            PartKind::Conditional { condition, then_parts, else_parts } => {
+                let branch = if condition.evaluate(variables) { then_parts } else { else_parts };
+                result.extend(evaluate_conditions(branch, variables));
+            }
+            PartKind::List { numbered, items } => {
+                let items = items
+                    .iter()
+                    .map(|item| evaluate_conditions(item, variables))
+                    .filter(|item| !item.is_empty())
+                    .collect();
+                result.push(PromptPart {
+                    kind: PartKind::List { numbered: *numbered, items },
+                    ..part.clone()
+                });
+            }
+            _ => result.push(part.clone()),
+        }
+    }
+
+    result
+}
+
+/// Parts with unevaluated conditional sections resolved (all variables unset)
+fn flatten_conditions(parts: &[PromptPart]) -> Cow<'_, [PromptPart]> {
+    if has_conditions(parts) {
+        Cow::Owned(evaluate_conditions(parts, &HashMap::new()))
+    } else {
+        Cow::Borrowed(parts)
+    }
+}
+
+/// Check for conditional sections, including inside list items
+fn has_conditions(parts: &[PromptPart]) -> bool {
+    parts.iter().any(|p| match &p.kind {
+        PartKind::Conditional { .. } => true,
+        PartKind::List { items, .. } => items.iter().any(|item| has_conditions(item)),
+        _ => false,
+    })
+}
+
 /// Generate formatted prompt from parts
 ///
 /// # Arguments
 /// * `parts` - Vector of PromptPart instances
 ///
 /// # Returns
-/// Formatted prompt string
+/// Formatted prompt string (section headers and labels in English)
 pub fn generate_prompt(parts: &[PromptPart]) -> String {
+    generate_prompt_with_locale(parts, "en")
+}
+
+/// Generate formatted prompt from parts
+///
+/// # Arguments
+/// * `parts` - Vector of PromptPart instances
+/// * `locale` - Locale code ("ja", "en", "fr") of section headers and example labels
+///
+/// # Returns
+/// Formatted prompt string
+pub fn generate_prompt_with_locale(parts: &[PromptPart], locale: &str) -> String {
+    render_prompt(parts, locale, "")
+}
+
+/// Render parts followed by `sections` (prohibitions, definitions)
+///
+/// The constraints section always comes last.
+fn render_prompt(parts: &[PromptPart], locale: &str, sections: &str) -> String {
+    let parts = flatten_conditions(parts);
     let mut output = String::new();
+    let mut example_number = 0;
+    let mut constraints: Vec<(ConstraintKind, &str)> = Vec::new();
 
     for (i, part) in parts.iter().enumerate() {
+        // Constraints are collected and rendered at the end
+        if let PartKind::Constraint { kind, value } = &part.kind {
+            constraints.push((*kind, value));
+            continue;
+        }
+
+        // Examples are numbered across the whole prompt
+        if let PartKind::Example { input, output: expected } = &part.kind {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            example_number += 1;
+            output.push_str(&render_example(example_number, input, expected, locale));
+            continue;
+        }
+
+        // Verbatim blocks go on their own lines as fenced blocks
+        if let PartKind::Code { lang } = &part.kind {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            let fence = code_fence(&part.text);
+            output.push_str(&format!("{}{}\n{}\n{}\n", fence, lang, part.text, fence));
+            continue;
+        }
+
+        // Included files: label line followed by the content as a fenced block
+        if let PartKind::File { path, content: Some(content) } = &part.kind {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            let fence = code_fence(content);
+            let lang = std::path::Path::new(path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            output.push_str(&format!("{} (FILE)\n{}{}\n{}\n{}\n", path, fence, lang, content, fence));
+            continue;
+        }
+
+        // Included projects: their prompt as its own paragraph
+        if let PartKind::Include { content: Some(content), .. } = &part.kind {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(content);
+            if !content.is_empty() && !content.ends_with('\n') {
+                output.push('\n');
+            }
+            continue;
+        }
+
+        // Lists: one item per line ("- " or "1. "), nouns keep their annotation
+        if let PartKind::List { numbered, items } = &part.kind {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            for (n, item) in items.iter().enumerate() {
+                let bullet = if *numbered { format!("{}.", n + 1) } else { "-".to_string() };
+                let text = generate_prompt_with_locale(item, locale);
+                output.push_str(&format!("{} {}\n", bullet, text.trim().replace('\n', " ")));
+            }
+            continue;
+        }
+
+        // Role markers start a labeled section
+        if part.kind == PartKind::Role {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(&format!("[{}]\n", part.text));
+            continue;
+        }
+
         // Add space between parts (but not at the start)
         if i > 0 && !output.is_empty() && !output.ends_with('\n') {
             output.push(' ');
@@ -136,19 +635,496 @@ pub fn generate_prompt(parts: &[PromptPart]) -> String {
         // Add text with noun annotation if applicable
         if part.is_noun {
             output.push_str(&format!("{} (NOUN)", part.text));
+        } else if matches!(part.kind, PartKind::File { .. }) {
+            output.push_str(&format!("{} (FILE)", part.text));
+        } else if matches!(part.kind, PartKind::Include { .. }) {
+            output.push_str(&format!("{} (INCLUDE)", part.text));
         } else {
             output.push_str(&part.text);
         }
     }
 
     // Add final newline if content exists
-    if !output.is_empty() {
+    if !output.is_empty() && !output.ends_with('\n') {
         output.push('\n');
     }
 
+    output.push_str(sections);
+    output.push_str(&render_constraints(&constraints, locale));
+
     output
 }
 
+/// Render a few-shot example with localized labels
+fn render_example(number: usize, input: &str, output: &str, locale: &str) -> String {
+    match locale {
+        "ja" => format!("例 {}:\n入力: {}\n出力: {}\n", number, input, output),
+        "fr" => format!("Exemple {} :\nEntrée : {}\nSortie : {}\n", number, input, output),
+        _ => format!("Example {}:\nInput: {}\nOutput: {}\n", number, input, output),
+    }
+}
+
+/// Render the constraints section
+///
+/// One line per kind in `ConstraintKind` order; when a kind is given more
+/// than once the last value wins (the validator reports the conflict).
+fn render_constraints(constraints: &[(ConstraintKind, &str)], locale: &str) -> String {
+    let mut latest: Vec<(ConstraintKind, &str)> = Vec::new();
+    for (kind, value) in constraints {
+        latest.retain(|(k, _)| k != kind);
+        latest.push((*kind, value));
+    }
+    if latest.is_empty() {
+        return String::new();
+    }
+    latest.sort_by_key(|(kind, _)| *kind);
+
+    let (header, separator) = match locale {
+        "ja" => ("制約:", ": "),
+        "fr" => ("Contraintes :", " : "),
+        _ => ("Constraints:", ": "),
+    };
+
+    let mut section = format!("{}\n", header);
+    for (kind, value) in latest {
+        let (label, text) = match (kind, locale) {
+            (ConstraintKind::Format, "ja") => ("形式", format_name(value)),
+            (ConstraintKind::Format, _) => ("Format", format_name(value)),
+            (ConstraintKind::MaxWords, "ja") => ("長さ", format!("{}語以内", value)),
+            (ConstraintKind::MaxWords, "fr") => ("Longueur", format!("{} mots maximum", value)),
+            (ConstraintKind::MaxWords, _) => ("Length", format!("at most {} words", value)),
+            (ConstraintKind::Language, "ja") => ("言語", language_name(value, locale)),
+            (ConstraintKind::Language, "fr") => ("Langue", language_name(value, locale)),
+            (ConstraintKind::Language, _) => ("Language", language_name(value, locale)),
+            (ConstraintKind::Tone, "ja") => ("トーン", value.to_string()),
+            (ConstraintKind::Tone, "fr") => ("Ton", value.to_string()),
+            (ConstraintKind::Tone, _) => ("Tone", value.to_string()),
+        };
+        section.push_str(&format!("- {}{}{}\n", label, separator, text));
+    }
+    section
+}
+
+/// Display name of an output format
+fn format_name(value: &str) -> String {
+    match value.to_lowercase().as_str() {
+        "json" | "yaml" | "xml" | "csv" | "html" | "toml" => value.to_uppercase(),
+        "markdown" | "md" => "Markdown".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// Display name of a language code in the given locale
+fn language_name(code: &str, locale: &str) -> String {
+    const NAMES: [(&str, &str, &str, &str); 7] = [
+        // (code, ja, en, fr)
+        ("ja", "日本語", "Japanese", "japonais"),
+        ("en", "英語", "English", "anglais"),
+        ("fr", "フランス語", "French", "français"),
+        ("de", "ドイツ語", "German", "allemand"),
+        ("es", "スペイン語", "Spanish", "espagnol"),
+        ("zh", "中国語", "Chinese", "chinois"),
+        ("ko", "韓国語", "Korean", "coréen"),
+    ];
+
+    let lower = code.to_lowercase();
+    match NAMES.iter().find(|(c, ..)| *c == lower) {
+        Some((_, ja, _, _)) if locale == "ja" => ja.to_string(),
+        Some((_, _, _, fr)) if locale == "fr" => fr.to_string(),
+        Some((_, _, en, _)) => en.to_string(),
+        None => code.to_string(),
+    }
+}
+
+/// A chat section: the parts between two `_ROLE:` markers
+#[derive(Debug, Clone)]
+pub struct PromptSection {
+    pub role: String,
+    pub parts: Vec<PromptPart>,
+}
+
+/// Group parts into chat sections
+///
+/// Parts before the first `_ROLE:` marker belong to `DEFAULT_ROLE`.
+/// Sections without any parts are dropped.
+pub fn split_sections(parts: &[PromptPart]) -> Vec<PromptSection> {
+    let mut sections = vec![PromptSection {
+        role: DEFAULT_ROLE.to_string(),
+        parts: Vec::new(),
+    }];
+
+    for part in parts {
+        if part.kind == PartKind::Role {
+            sections.push(PromptSection {
+                role: part.text.clone(),
+                parts: Vec::new(),
+            });
+        } else if let Some(section) = sections.last_mut() {
+            section.parts.push(part.clone());
+        }
+    }
+
+    sections.retain(|s| !s.parts.is_empty());
+    sections
+}
+
+/// A glossary entry: a noun with a one-line definition
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlossaryEntry {
+    pub term: String,
+    pub definition: String,
+    /// Other spellings of the noun (e.g. `Order` for `注文`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+}
+
+impl GlossaryEntry {
+    /// Check if a noun is this term or one of its aliases (case-insensitive)
+    pub fn matches(&self, noun: &str) -> bool {
+        let noun = noun.trim().to_lowercase();
+        std::iter::once(&self.term)
+            .chain(&self.aliases)
+            .any(|name| name.trim().to_lowercase() == noun)
+    }
+}
+
+/// Project glossary
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Glossary {
+    #[serde(default)]
+    pub entries: Vec<GlossaryEntry>,
+    /// Strict mode: nouns without an entry are reported by the validator
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl Glossary {
+    /// Check if the glossary has no entries and is not strict
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && !self.strict
+    }
+
+    /// Entry defining a noun, if any
+    pub fn lookup(&self, noun: &str) -> Option<&GlossaryEntry> {
+        self.entries.iter().find(|entry| entry.matches(noun))
+    }
+}
+
+/// Generate formatted prompt with a definitions section
+///
+/// The section lists the glossary entries of the nouns used in the prompt,
+/// in order of first use.
+///
+/// # Arguments
+/// * `parts` - Vector of PromptPart instances
+/// * `locale` - Locale code ("ja", "en", "fr") of the constraints and definitions sections
+/// * `glossary` - Project glossary
+///
+/// # Returns
+/// Formatted prompt string
+pub fn generate_prompt_with_glossary(parts: &[PromptPart], locale: &str, glossary: &Glossary) -> String {
+    let definitions = render_definitions(&flatten_conditions(parts), glossary, locale);
+    render_prompt(parts, locale, &definitions)
+}
+
+/// Render the definitions section (empty if no glossary noun is used)
+fn render_definitions(parts: &[PromptPart], glossary: &Glossary, locale: &str) -> String {
+    let mut used: Vec<&GlossaryEntry> = Vec::new();
+    for part in noun_parts(parts) {
+        if let Some(entry) = glossary.lookup(&part.text) {
+            if !used.contains(&entry) {
+                used.push(entry);
+            }
+        }
+    }
+    if used.is_empty() {
+        return String::new();
+    }
+
+    let (header, separator) = match locale {
+        "ja" => ("用語定義:", ": "),
+        "fr" => ("Définitions :", " : "),
+        _ => ("Definitions:", ": "),
+    };
+
+    let mut section = format!("{}\n", header);
+    for entry in used {
+        section.push_str(&format!("- {}{}{}\n", entry.term, separator, entry.definition));
+    }
+    section
+}
+
+/// Noun parts in order, including the items of lists
+fn noun_parts(parts: &[PromptPart]) -> Vec<&PromptPart> {
+    let mut nouns = Vec::new();
+    for part in parts {
+        match &part.kind {
+            PartKind::List { items, .. } => nouns.extend(items.iter().flat_map(|item| noun_parts(item))),
+            _ if part.is_noun => nouns.push(part),
+            _ => {}
+        }
+    }
+    nouns
+}
+
+/// English negation words ("do not", "don't", "never", "shouldn't")
+pub const NEGATION_WORDS_EN: [&str; 10] = [
+    "not", "never", "don't", "dont", "doesn't", "shouldn't", "can't", "cannot", "won't", "mustn't",
+];
+
+/// French words that open a negation ("ne … pas"; elided `n'` is also accepted)
+pub const NEGATION_OPENERS_FR: [&str; 2] = ["ne", "n'"];
+
+/// French words that close a negation ("ne … pas", "ne … jamais")
+pub const NEGATION_CLOSERS_FR: [&str; 6] = ["pas", "jamais", "rien", "aucun", "aucune", "plus"];
+
+/// Japanese negative request endings of a verb (〜しないで, 〜しないでください)
+pub const NEGATIVE_ENDINGS_JA: [&str; 5] = ["ないでください", "ないで", "ないこと", "ないように", "するな"];
+
+/// Check if the French word at `i` opens a negation (`ne`, `n'`, `n'utilisez`)
+///
+/// `ne` always does; `n'` (alone or elided) only when a closing word follows
+/// in the same sentence, as `n'importe quel` is not a negation.
+pub fn is_negation_opener_fr(words: &[&str], i: usize) -> bool {
+    let word = words[i].to_lowercase().replace('’', "'");
+    if word == "ne" {
+        return true;
+    }
+    word.starts_with("n'")
+        && words[i + 1..]
+            .iter()
+            .take_while(|w| !is_sentence_end(w))
+            .any(|w| NEGATION_CLOSERS_FR.contains(&w.to_lowercase().as_str()))
+}
+
+/// Splits a sentence (DSL tokens) into clauses and flags the prohibitions
+///
+/// Returns token ranges covering the sentence in order. Detection needs
+/// the per-locale token classes, so the app provides it
+/// (`validation::prohibition_clauses`).
+pub type ProhibitionFinder<'a> = dyn Fn(&[String]) -> Vec<(Range<usize>, bool)> + 'a;
+
+/// Check if a word ends a sentence
+fn is_sentence_end(word: &str) -> bool {
+    matches!(word.trim(), "。" | "." | "!" | "?" | "！" | "？")
+}
+
+/// Split parts into the prompt body and prohibition clauses
+///
+/// Sentences end at 。 . ! ?, at sentence breaks and around parts that
+/// are not text or nouns (code, examples, roles...), which stay in the
+/// body. `find` splits each sentence into clauses and flags the
+/// prohibitions; other clauses of the sentence stay in the body.
+///
+/// # Returns
+/// (body parts, prohibition clauses in order)
+pub fn split_prohibitions(
+    parts: &[PromptPart],
+    find: &ProhibitionFinder,
+) -> (Vec<PromptPart>, Vec<Vec<PromptPart>>) {
+    let mut body = Vec::new();
+    let mut prohibitions = Vec::new();
+    let mut sentence: Vec<PromptPart> = Vec::new();
+
+    let finish = |sentence: &mut Vec<PromptPart>, body: &mut Vec<PromptPart>, prohibitions: &mut Vec<Vec<PromptPart>>| {
+        // One token per noun or word, remembering the part it came from
+        let mut tokens: Vec<String> = Vec::new();
+        let mut origins: Vec<usize> = Vec::new();
+        for (index, part) in sentence.iter().enumerate() {
+            if part.is_noun {
+                tokens.push(format!("_N:{}", quote_text(&part.text)));
+                origins.push(index);
+            } else {
+                for word in part.text.split(' ').filter(|w| !w.is_empty()) {
+                    tokens.push(word.to_string());
+                    origins.push(index);
+                }
+            }
+        }
+
+        for (range, negated) in find(&tokens) {
+            let mut clause: Vec<PromptPart> = Vec::new();
+            let mut words: Vec<&str> = Vec::new();
+            for i in range {
+                let part = &sentence[origins[i]];
+                if part.is_noun {
+                    if !words.is_empty() {
+                        clause.push(PromptPart { is_noun: false, text: words.join(" "), kind: PartKind::Text });
+                        words.clear();
+                    }
+                    clause.push(part.clone());
+                } else {
+                    words.push(&tokens[i]);
+                }
+            }
+            if !words.is_empty() {
+                clause.push(PromptPart { is_noun: false, text: words.join(" "), kind: PartKind::Text });
+            }
+
+            if negated {
+                prohibitions.push(clause);
+            } else {
+                body.append(&mut clause);
+            }
+        }
+        sentence.clear();
+    };
+
+    for part in flatten_conditions(parts).iter() {
+        if part.kind != PartKind::Text {
+            finish(&mut sentence, &mut body, &mut prohibitions);
+            body.push(part.clone());
+            continue;
+        }
+        if part.is_noun {
+            sentence.push(part.clone());
+            continue;
+        }
+
+        // Two text parts in a row are separated by a sentence break
+        if sentence.last().is_some_and(|p| !p.is_noun) {
+            finish(&mut sentence, &mut body, &mut prohibitions);
+        }
+        let text_part = |words: &[&str]| PromptPart {
+            is_noun: false,
+            text: words.join(" "),
+            kind: PartKind::Text,
+        };
+        let mut words: Vec<&str> = Vec::new();
+        for word in part.text.split(' ') {
+            words.push(word);
+            if is_sentence_end(word) {
+                sentence.push(text_part(&words));
+                words.clear();
+                finish(&mut sentence, &mut body, &mut prohibitions);
+            }
+        }
+        if !words.is_empty() {
+            sentence.push(text_part(&words));
+        }
+    }
+    finish(&mut sentence, &mut body, &mut prohibitions);
+
+    (body, prohibitions)
+}
+
+/// Generate formatted prompt with prohibitions collected into their own section
+///
+/// # Arguments
+/// * `parts` - Vector of PromptPart instances
+/// * `locale` - Locale code ("ja", "en", "fr") of the section headers
+/// * `glossary` - Project glossary
+/// * `find` - Clause splitter (see `ProhibitionFinder`)
+///
+/// # Returns
+/// Formatted prompt string: body, prohibitions ("Do not:"), definitions and constraints
+pub fn generate_prompt_with_prohibitions(
+    parts: &[PromptPart],
+    locale: &str,
+    glossary: &Glossary,
+    find: &ProhibitionFinder,
+) -> String {
+    let parts = flatten_conditions(parts);
+    let (body, prohibitions) = split_prohibitions(&parts, find);
+
+    let mut sections = render_prohibitions(&prohibitions, locale);
+    sections.push_str(&render_definitions(&parts, glossary, locale));
+    render_prompt(&body, locale, &sections)
+}
+
+/// Render the prohibitions section (empty if there are none)
+fn render_prohibitions(sentences: &[Vec<PromptPart>], locale: &str) -> String {
+    if sentences.is_empty() {
+        return String::new();
+    }
+
+    let header = match locale {
+        "ja" => "禁止事項:",
+        "fr" => "Interdictions :",
+        _ => "Do not:",
+    };
+
+    let mut section = format!("{}\n", header);
+    for sentence in sentences {
+        let text = generate_prompt_with_locale(sentence, locale);
+        section.push_str(&format!("- {}\n", text.trim().replace('\n', " ")));
+    }
+    section
+}
+
+/// A chat message (OpenAI/Anthropic style `{role, content}`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// Generate chat messages from parts
+///
+/// Each section is rendered with `generate_prompt`. Consecutive sections
+/// with the same role are merged into one message. Examples become their
+/// own user (input) and assistant (output) turns.
+///
+/// # Arguments
+/// * `parts` - Vector of PromptPart instances
+/// * `locale` - Locale code ("ja", "en", "fr") of constraints sections
+///
+/// # Returns
+/// Messages in section order
+pub fn generate_messages(parts: &[PromptPart], locale: &str) -> Vec<ChatMessage> {
+    let mut messages: Vec<ChatMessage> = Vec::new();
+    // Example turns are never merged with the text around them
+    let mut after_example = false;
+
+    let push_text = |messages: &mut Vec<ChatMessage>, role: &str, parts: &[PromptPart], after_example: &mut bool| {
+        let content = generate_prompt_with_locale(parts, locale).trim_end().to_string();
+        if content.is_empty() {
+            return;
+        }
+        match messages.last_mut() {
+            Some(last) if last.role == role && !*after_example => {
+                last.content.push('\n');
+                last.content.push_str(&content);
+            }
+            _ => messages.push(ChatMessage {
+                role: role.to_string(),
+                content,
+            }),
+        }
+        *after_example = false;
+    };
+
+    for section in split_sections(&flatten_conditions(parts)) {
+        let mut text_parts: Vec<PromptPart> = Vec::new();
+
+        for part in &section.parts {
+            if let PartKind::Example { input, output } = &part.kind {
+                push_text(&mut messages, &section.role, &text_parts, &mut after_example);
+                text_parts.clear();
+                messages.push(ChatMessage { role: "user".to_string(), content: input.clone() });
+                messages.push(ChatMessage { role: "assistant".to_string(), content: output.clone() });
+                after_example = true;
+            } else {
+                text_parts.push(part.clone());
+            }
+        }
+
+        push_text(&mut messages, &section.role, &text_parts, &mut after_example);
+    }
+
+    messages
+}
+
+/// Backtick fence longer than any backtick run in the content (at least 3)
+fn code_fence(content: &str) -> String {
+    let longest = content
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    "`".repeat((longest + 1).max(3))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,14 +1153,17 @@ mod tests {
             PromptPart {
                 is_noun: true,
                 text: "テーブルブロック機能".to_string(),
+                kind: PartKind::Text,
             },
             PromptPart {
                 is_noun: false,
                 text: "データベーステーブルを定義します".to_string(),
+                kind: PartKind::Text,
             },
             PromptPart {
                 is_noun: true,
                 text: "対象ユーザー".to_string(),
+                kind: PartKind::Text,
             },
         ];
 
@@ -228,6 +1207,7 @@ mod tests {
         let part = PromptPart {
             is_noun: true,
             text: "GUI ブロック ビルダー 機能".to_string(),
+            kind: PartKind::Text,
         };
 
         assert_eq!(part.is_noun, true);
@@ -241,6 +1221,7 @@ mod tests {
         let part = PromptPart {
             is_noun: true,
             text: "テキストフィールド を 変数 に コピーしてください".to_string(),
+            kind: PartKind::Text,
         };
 
         assert_eq!(part.is_noun, true);
@@ -260,6 +1241,14 @@ mod tests {
         assert_eq!(parts[1].text, "データベースのテーブル構造を視覚的に定義する機能です");
     }
 
+    #[test]
+    fn test_modifier_markers_stripped() {
+        let parts = parse_input("_V:summarize the _ADJ:detailed _N:report _ADV:briefly");
+
+        assert_eq!(parts[0].text, "summarize the detailed");
+        assert_eq!(parts[2].text, "briefly");
+    }
+
     // Edge Case Tests
 
     #[test]
@@ -288,6 +1277,433 @@ mod tests {
         assert_eq!(parts[1].text, "Order");
     }
 
+    #[test]
+    fn test_quoted_noun_with_spaces() {
+        let input = "_N:\"New York\" に _N:\"C:\\Program Files\" を 送信して";
+        let parts = parse_input(input);
+
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[0].text, "New York");
+        assert_eq!(parts[0].is_noun, true);
+        assert_eq!(parts[2].text, "C:\\Program Files");
+        assert_eq!(parts[3].text, "を 送信して");
+    }
+
+    #[test]
+    fn test_escaped_noun_marker_is_text() {
+        let parts = parse_input("\\_N:foo は マーカーです");
+
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].is_noun, false);
+        assert_eq!(parts[0].text, "_N:foo は マーカーです");
+    }
+
+    #[test]
+    fn test_full_width_space_sentences() {
+        let parts = parse_input("これは\u{3000}説明\u{3000}\u{3000}次の文");
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].text, "これは 説明");
+        assert_eq!(parts[1].text, "次の文");
+    }
+
+    #[test]
+    fn test_code_block_preserves_whitespace() {
+        let input = "_N:Config を 説明して\n_CODE:json\n{\n    \"id\":   1\n}\n_END";
+        let parts = parse_input(input);
+
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[2].kind, PartKind::Code { lang: "json".to_string() });
+        assert_eq!(parts[2].text, "{\n    \"id\":   1\n}");
+    }
+
+    #[test]
+    fn test_generate_prompt_code_block() {
+        let parts = parse_input("_N:Script を 修正して\n_CODE:sh\n  echo  hi\n_END\nお願いします");
+        let prompt = generate_prompt(&parts);
+
+        assert_eq!(prompt, "Script (NOUN) を 修正して\n```sh\n  echo  hi\n```\nお願いします\n");
+    }
+
+    #[test]
+    fn test_code_fence_longer_than_content_backticks() {
+        let parts = parse_input("_CODE:md\n```rust\nfn x() {}\n```\n_END");
+        let prompt = generate_prompt(&parts);
+
+        assert!(prompt.starts_with("````md\n```rust"));
+        assert!(prompt.ends_with("```\n````\n"));
+    }
+
+    #[test]
+    fn test_file_token_unresolved() {
+        let parts = parse_input("_N:Doc を _FILE:\"notes/a b.md\" で 要約して");
+
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[2].kind, PartKind::File { path: "notes/a b.md".to_string(), content: None });
+        assert_eq!(generate_prompt(&parts), "Doc (NOUN) を notes/a b.md (FILE) で 要約して\n");
+    }
+
+    #[test]
+    fn test_file_token_resolved() {
+        let mut parts = parse_input("_FILE:src/main.rs を 説明して");
+        parts[0].kind = PartKind::File {
+            path: "src/main.rs".to_string(),
+            content: Some("fn main() {}".to_string()),
+        };
+
+        assert_eq!(generate_prompt(&parts), "src/main.rs (FILE)\n```rs\nfn main() {}\n```\nを 説明して\n");
+    }
+
+    #[test]
+    fn test_include_token() {
+        let mut parts = parse_input("_INCLUDE:style.promps _N:Doc を 要約して");
+        assert_eq!(parts[0].kind, PartKind::Include { path: "style.promps".to_string(), content: None });
+        assert_eq!(generate_prompt(&parts), "style.promps (INCLUDE) Doc (NOUN) を 要約して\n");
+
+        parts[0].kind = PartKind::Include {
+            path: "style.promps".to_string(),
+            content: Some("丁寧に 答えて\n".to_string()),
+        };
+        assert_eq!(generate_prompt(&parts), "丁寧に 答えて\nDoc (NOUN) を 要約して\n");
+    }
+
+    #[test]
+    fn test_glossary_definitions_for_used_nouns() {
+        let glossary = Glossary {
+            entries: vec![
+                GlossaryEntry {
+                    term: "注文".to_string(),
+                    definition: "顧客の購入リクエスト".to_string(),
+                    aliases: vec!["Order".to_string()],
+                },
+                GlossaryEntry { term: "在庫".to_string(), definition: "倉庫の商品数".to_string(), aliases: vec![] },
+                GlossaryEntry { term: "顧客".to_string(), definition: "登録ユーザー".to_string(), aliases: vec![] },
+            ],
+            strict: false,
+        };
+        let parts = parse_input("_N:顧客 の _N:order を 要約して _N:注文");
+
+        assert_eq!(
+            generate_prompt_with_glossary(&parts, "ja", &glossary),
+            "顧客 (NOUN) の order (NOUN) を 要約して 注文 (NOUN)\n用語定義:\n- 顧客: 登録ユーザー\n- 注文: 顧客の購入リクエスト\n"
+        );
+    }
+
+    #[test]
+    fn test_glossary_without_used_nouns() {
+        let glossary = Glossary {
+            entries: vec![GlossaryEntry { term: "Stock".to_string(), definition: "items".to_string(), aliases: vec![] }],
+            strict: true,
+        };
+        let parts = parse_input("summarize the _N:report");
+
+        assert_eq!(generate_prompt_with_glossary(&parts, "en", &glossary), "summarize the report (NOUN)\n");
+    }
+
+    #[test]
+    fn test_constraints_after_definitions() {
+        let glossary = Glossary {
+            entries: vec![GlossaryEntry { term: "Order".to_string(), definition: "a purchase".to_string(), aliases: vec![] }],
+            strict: false,
+        };
+        let parts = parse_input("summarize the _N:Order _FMT:json");
+        let expected = "summarize the Order (NOUN)\nDefinitions:\n- Order: a purchase\nConstraints:\n- Format: JSON\n";
+
+        assert_eq!(generate_prompt_with_glossary(&parts, "en", &glossary), expected);
+        assert_eq!(generate_prompt_with_prohibitions(&parts, "en", &glossary, &negated_sentences), expected);
+    }
+
+    #[test]
+    fn test_negation_opener_fr_needs_closer() {
+        let negated: Vec<&str> = "n'utilisez pas le _N:format .".split(' ').collect();
+        let positive: Vec<&str> = "utilisez n'importe quel _N:format . ne".split(' ').collect();
+
+        assert!(is_negation_opener_fr(&negated, 0));
+        assert!(!is_negation_opener_fr(&positive, 1));
+        assert!(is_negation_opener_fr(&positive, 5));
+    }
+
+    /// Whole sentence as one clause, negated if it has "not" or a ja negative request
+    fn negated_sentences(tokens: &[String]) -> Vec<(Range<usize>, bool)> {
+        let negated = tokens.iter().any(|t| t == "not" || t.ends_with("ないでください"));
+        vec![(0..tokens.len(), negated)]
+    }
+
+    #[test]
+    fn test_prohibitions_section() {
+        let parts = parse_input("summarize the _N:report . do not translate the _N:title . keep it short .");
+        let output = generate_prompt_with_prohibitions(&parts, "en", &Glossary::default(), &negated_sentences);
+
+        assert_eq!(
+            output,
+            "summarize the report (NOUN) . keep it short .\nDo not:\n- do not translate the title (NOUN) .\n"
+        );
+    }
+
+    #[test]
+    fn test_prohibitions_section_ja_with_definitions() {
+        let glossary = Glossary {
+            entries: vec![GlossaryEntry { term: "個人情報".to_string(), definition: "氏名や住所".to_string(), aliases: vec![] }],
+            strict: false,
+        };
+        let parts = parse_input("_N:文書 を 要約して 。\n_N:個人情報 を 含めないでください 。");
+        let output = generate_prompt_with_prohibitions(&parts, "ja", &glossary, &negated_sentences);
+
+        assert_eq!(
+            output,
+            "文書 (NOUN) を 要約して 。\n禁止事項:\n- 個人情報 (NOUN) を 含めないでください 。\n用語定義:\n- 個人情報: 氏名や住所\n"
+        );
+    }
+
+    #[test]
+    fn test_no_prohibitions_same_as_plain_prompt() {
+        let parts = parse_input("_V:summarize the _N:report");
+        assert_eq!(
+            generate_prompt_with_prohibitions(&parts, "en", &Glossary::default(), &negated_sentences),
+            generate_prompt_with_locale(&parts, "en")
+        );
+    }
+
+    #[test]
+    fn test_role_markers_split_sections() {
+        let parts = parse_input("_ROLE:system あなたは 翻訳者 です _ROLE:User _N:文書 を 翻訳して");
+        let sections = split_sections(&parts);
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].role, "system");
+        assert_eq!(sections[0].parts[0].text, "あなたは 翻訳者 です");
+        assert_eq!(sections[1].role, "user");
+        assert_eq!(sections[1].parts.len(), 2);
+    }
+
+    #[test]
+    fn test_generate_messages() {
+        let parts = parse_input("前置き _ROLE:system 簡潔に 答えて _ROLE:assistant はい _ROLE:assistant 了解");
+        let messages = generate_messages(&parts, "en");
+
+        assert_eq!(
+            messages,
+            vec![
+                ChatMessage { role: "user".to_string(), content: "前置き".to_string() },
+                ChatMessage { role: "system".to_string(), content: "簡潔に 答えて".to_string() },
+                ChatMessage { role: "assistant".to_string(), content: "はい\n了解".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_role_is_text() {
+        let parts = parse_input("_ROLE:narrator こんにちは");
+
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].text, "_ROLE:narrator こんにちは");
+        assert_eq!(generate_messages(&parts, "en")[0].role, "user");
+    }
+
+    #[test]
+    fn test_generate_prompt_role_headers() {
+        let parts = parse_input("_ROLE:system 丁寧に _ROLE:user _N:Doc を 要約して");
+        assert_eq!(generate_prompt(&parts), "[system]\n丁寧に\n[user]\nDoc (NOUN) を 要約して\n");
+    }
+
+    #[test]
+    fn test_parse_examples() {
+        let parts = parse_input("_IN:\"good morning\" _OUT:おはよう _IN:hello _OUT:\"\"");
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(
+            parts[0].kind,
+            PartKind::Example { input: "good morning".to_string(), output: "おはよう".to_string() }
+        );
+        assert_eq!(parts[1].kind, PartKind::Example { input: "hello".to_string(), output: String::new() });
+    }
+
+    #[test]
+    fn test_unbalanced_example_output() {
+        let parts = parse_input("_IN:a 説明 _OUT:b");
+
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].kind, PartKind::Example { input: "a".to_string(), output: String::new() });
+        assert_eq!(parts[2].kind, PartKind::Example { input: String::new(), output: "b".to_string() });
+    }
+
+    #[test]
+    fn test_generate_prompt_numbered_examples() {
+        let parts = parse_input("_N:英語 に 翻訳して _IN:猫 _OUT:cat _IN:犬 _OUT:dog");
+
+        assert_eq!(
+            generate_prompt(&parts),
+            "英語 (NOUN) に 翻訳して\nExample 1:\nInput: 猫\nOutput: cat\nExample 2:\nInput: 犬\nOutput: dog\n"
+        );
+        assert!(generate_prompt_with_locale(&parts, "ja").ends_with("例 2:\n入力: 犬\n出力: dog\n"));
+        assert!(generate_prompt_with_locale(&parts, "fr").ends_with("Exemple 2 :\nEntrée : 犬\nSortie : dog\n"));
+    }
+
+    #[test]
+    fn test_generate_messages_example_turns() {
+        let parts = parse_input("_ROLE:system 英語 に 翻訳して _IN:猫 _OUT:cat _ROLE:user 犬");
+        let messages = generate_messages(&parts, "en");
+        let got: Vec<(&str, &str)> = messages.iter().map(|m| (m.role.as_str(), m.content.as_str())).collect();
+
+        assert_eq!(
+            got,
+            vec![
+                ("system", "英語 に 翻訳して"),
+                ("user", "猫"),
+                ("assistant", "cat"),
+                ("user", "犬"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_constraints_rendered_at_end() {
+        let parts = parse_input("_FMT:json _N:Doc を _MAXWORDS:200 要約して _LANG:en _TONE:formal");
+
+        assert_eq!(
+            generate_prompt(&parts),
+            "Doc (NOUN) を 要約して\nConstraints:\n- Format: JSON\n- Length: at most 200 words\n- Language: English\n- Tone: formal\n"
+        );
+    }
+
+    #[test]
+    fn test_constraints_localized() {
+        let parts = parse_input("_N:Doc を 要約して _LANG:fr _MAXWORDS:50");
+
+        assert_eq!(
+            generate_prompt_with_locale(&parts, "ja"),
+            "Doc (NOUN) を 要約して\n制約:\n- 長さ: 50語以内\n- 言語: フランス語\n"
+        );
+        assert!(generate_prompt_with_locale(&parts, "fr").ends_with("Contraintes :\n- Longueur : 50 mots maximum\n- Langue : français\n"));
+    }
+
+    #[test]
+    fn test_repeated_constraint_last_wins() {
+        let parts = parse_input("_FMT:json _FMT:markdown");
+        assert_eq!(generate_prompt(&parts), "Constraints:\n- Format: Markdown\n");
+    }
+
+    #[test]
+    fn test_parse_conditional_tree() {
+        let parts = parse_input("_N:Doc を 要約して _IF:cite 出典 を 含めて _ELSE 簡潔に _ENDIF");
+
+        assert_eq!(parts.len(), 3);
+        match &parts[2].kind {
+            PartKind::Conditional { condition, then_parts, else_parts } => {
+                assert_eq!(condition, &Condition { variable: "cite".to_string(), negated: false });
+                assert_eq!(then_parts[0].text, "出典 を 含めて");
+                assert_eq!(else_parts[0].text, "簡潔に");
+            }
+            other => panic!("expected conditional, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_conditions() {
+        let parts = parse_input("A _IF:x B _IF:!y C _ENDIF _ELSE D _ENDIF E");
+        let render = |x: bool, y: bool| {
+            let variables = HashMap::from([("x".to_string(), x), ("y".to_string(), y)]);
+            generate_prompt(&evaluate_conditions(&parts, &variables))
+        };
+
+        assert_eq!(render(true, false), "A B C E\n");
+        assert_eq!(render(true, true), "A B E\n");
+        assert_eq!(render(false, false), "A D E\n");
+    }
+
+    #[test]
+    fn test_unevaluated_conditions_use_else() {
+        let parts = parse_input("A _IF:x B _ELSE C _ENDIF");
+        assert_eq!(generate_prompt(&parts), "A C\n");
+    }
+
+    #[test]
+    fn test_unclosed_and_stray_conditions() {
+        let unclosed = parse_input("A _IF:x B");
+        let variables = HashMap::from([("x".to_string(), true)]);
+        assert_eq!(generate_prompt(&evaluate_conditions(&unclosed, &variables)), "A B\n");
+
+        let stray = parse_input("A _ENDIF \\_ELSE");
+        assert_eq!(generate_prompt(&stray), "A _ENDIF _ELSE\n");
+    }
+
+    #[test]
+    fn test_list_bullets_keep_noun_annotations() {
+        let parts = parse_input("_N:文書 から _LIST _N:名前 と _N:住所 と 電話 _N:番号 _ENDLIST を 抽出して");
+
+        assert_eq!(
+            generate_prompt(&parts),
+            "文書 (NOUN) から\n- 名前 (NOUN)\n- 住所 (NOUN)\n- 電話 番号 (NOUN)\nを 抽出して\n"
+        );
+    }
+
+    #[test]
+    fn test_list_numbered_items_on_lines() {
+        let parts = parse_input("_V:summarize _LIST:numbered\nthe _N:report\nthe _N:notes\n_ENDLIST");
+
+        assert_eq!(generate_prompt(&parts), "summarize\n1. the report (NOUN)\n2. the notes (NOUN)\n");
+    }
+
+    #[test]
+    fn test_list_without_separators() {
+        let parts = parse_input("_LIST 重要な _N:A _N:B _ENDLIST");
+        match &parts[0].kind {
+            PartKind::List { numbered, items } => {
+                assert!(!numbered);
+                assert_eq!(items.len(), 2);
+                assert_eq!(items[0][0].text, "重要な");
+                assert_eq!(items[1][0].text, "B");
+            }
+            other => panic!("expected list, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_list_unclosed_stray_and_conditional() {
+        let unclosed = parse_input("_LIST _N:A , _N:B");
+        assert_eq!(generate_prompt(&unclosed), "- A (NOUN)\n- B (NOUN)\n");
+
+        let stray = parse_input("A _ENDLIST");
+        assert_eq!(generate_prompt(&stray), "A _ENDLIST\n");
+
+        let conditional = parse_input("_LIST _N:A , _IF:x _N:B _ENDIF _ENDLIST");
+        assert_eq!(generate_prompt(&conditional), "- A (NOUN)\n");
+        let variables = HashMap::from([("x".to_string(), true)]);
+        assert_eq!(
+            generate_prompt(&evaluate_conditions(&conditional, &variables)),
+            "- A (NOUN)\n- B (NOUN)\n"
+        );
+    }
+
+    #[test]
+    fn test_list_crossing_condition() {
+        let variables = HashMap::from([("x".to_string(), false)]);
+
+        // _ENDLIST closes the _IF: opened inside the list; the _ENDIF is stray
+        let parts = parse_input("_N:Z を _LIST _IF:x _N:A _ENDLIST _N:B _ENDIF");
+        let prompt = generate_prompt(&evaluate_conditions(&parts, &variables));
+        assert_eq!(prompt, "Z (NOUN) を\nB (NOUN) _ENDIF\n");
+
+        // _ENDIF closes the list opened inside the condition
+        let parts = parse_input("_IF:x _LIST _N:A _ENDIF _N:B _ENDLIST");
+        let prompt = generate_prompt(&evaluate_conditions(&parts, &variables));
+        assert_eq!(prompt, "B (NOUN) _ENDLIST\n");
+    }
+
+    #[test]
+    fn test_list_nouns_in_definitions() {
+        let glossary = Glossary {
+            entries: vec![GlossaryEntry { term: "SLA".to_string(), definition: "service level".to_string(), aliases: vec![] }],
+            strict: false,
+        };
+        let parts = parse_input("_V:check _LIST _N:SLA and _N:uptime _ENDLIST");
+
+        assert_eq!(
+            generate_prompt_with_glossary(&parts, "en", &glossary),
+            "check\n- SLA (NOUN)\n- uptime (NOUN)\nDefinitions:\n- SLA: service level\n"
+        );
+    }
+
     #[test]
     fn test_very_long_input() {
         // Test with 10,000+ characters (performance baseline)
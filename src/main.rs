@@ -6,12 +6,21 @@ mod modules;
 
 use commands::{
     generate_prompt_from_text,
+    generate_prompt_for_project,
+    generate_chat_messages,
     greet,
     validate_dsl_sequence,
     get_patterns,
+    get_pattern_load_report,
     analyze_dsl_patterns,
+    translate_dsl,
+    import_dsl_to_workspace,
+    segment_text,
+    suggest_next,
+    instantiate_pattern,
     save_project,
     load_project,
+    get_project_dsl,
     create_new_project,
     update_project_timestamp,
     show_open_dialog,
@@ -26,12 +35,21 @@ fn main() {
         .plugin(tauri_plugin_fs::init())
         .invoke_handler(tauri::generate_handler![
             generate_prompt_from_text,
+            generate_prompt_for_project,
+            generate_chat_messages,
             greet,
             validate_dsl_sequence,
             get_patterns,
+            get_pattern_load_report,
             analyze_dsl_patterns,
+            translate_dsl,
+            import_dsl_to_workspace,
+            segment_text,
+            suggest_next,
+            instantiate_pattern,
             save_project,
             load_project,
+            get_project_dsl,
             create_new_project,
             update_project_timestamp,
             show_open_dialog,
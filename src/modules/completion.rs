@@ -0,0 +1,402 @@
+/// Promps - Next Block Completion Module
+///
+/// This module suggests block types that can follow the current input.
+/// Suggestions combine three sources:
+/// 1. Grammar state (what the last token allows, e.g. particle after noun)
+/// 2. Partially matched pattern templates (the next slot of the pattern)
+/// 3. How often the user places each block in their own projects
+
+use promps::tokenizer::split_tokens;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::validation::{
+    analyze_patterns_with_templates, block_types, block_word, PatternTemplate, TokenType,
+};
+
+// ============================================================================
+// Suggestion Types
+// ============================================================================
+
+/// Where a suggestion came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SuggestionSource {
+    /// Allowed by the grammar state after the last token
+    Grammar,
+    /// Next slot of a partially matched pattern
+    Pattern,
+    /// Frequently used in the user's projects
+    Frequency,
+}
+
+/// A suggested next block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    /// Blockly block type (e.g. "promps_particle_wo")
+    pub block_type: String,
+    /// Output text in the locale (None for text-input blocks)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Ranking score (higher is better)
+    pub score: f64,
+    /// Sources that contributed to the score
+    pub sources: Vec<SuggestionSource>,
+}
+
+/// Block usage counts by block type
+pub type BlockUsage = HashMap<String, usize>;
+
+// ============================================================================
+// Block Categories
+// ============================================================================
+
+/// Grammatical category of a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Noun,
+    Particle,
+    Verb,
+    Article,
+    Please,
+    SentenceEnd,
+    Touten,
+    Other,
+}
+
+/// Category of a block type
+fn category_of(block_type: &str) -> Option<Category> {
+    match block_type {
+        "promps_noun" => Some(Category::Noun),
+        "promps_other" => Some(Category::Other),
+        "promps_article_please" => Some(Category::Please),
+        "promps_punct_kuten" => Some(Category::SentenceEnd),
+        "promps_punct_touten" => Some(Category::Touten),
+        bt if bt.starts_with("promps_particle_") => Some(Category::Particle),
+        bt if bt.starts_with("promps_verb_") => Some(Category::Verb),
+        bt if bt.starts_with("promps_article_") => Some(Category::Article),
+        _ => None,
+    }
+}
+
+/// Block types of a category available in a locale (palette order)
+fn blocks_in(category: Category, locale: &str) -> Vec<&'static str> {
+    let mut types: Vec<&'static str> = block_types(locale)
+        .into_iter()
+        .filter(|bt| category_of(bt) == Some(category))
+        .collect();
+
+    match category {
+        Category::Noun => types.push("promps_noun"),
+        Category::Other => types.push("promps_other"),
+        Category::Verb => types.push("promps_verb_custom"),
+        _ => {}
+    }
+
+    types
+}
+
+// ============================================================================
+// Grammar State
+// ============================================================================
+
+/// Check if a token ends a sentence
+fn is_sentence_end(token: &str) -> bool {
+    matches!(token, "。" | "." | "！" | "？" | "!" | "?")
+}
+
+/// Categories allowed after the last token, most likely first
+fn next_categories(tokens: &[&str], locale: &str) -> Vec<Category> {
+    use Category::*;
+
+    let last = match tokens.last() {
+        Some(last) if !is_sentence_end(last) => *last,
+        // Start of a sentence
+        _ => {
+            return match locale {
+                "en" | "fr" => vec![Verb, Please, Noun],
+                _ => vec![Noun, Other, Verb],
+            };
+        }
+    };
+
+    match locale {
+        "en" | "fr" => {
+            let token_type = if locale == "fr" {
+                TokenType::classify_fr(last)
+            } else {
+                TokenType::classify_en(last)
+            };
+            match token_type {
+                TokenType::Please | TokenType::Negation => vec![Verb],
+                TokenType::Verb => vec![Article, Noun],
+                TokenType::Article | TokenType::Adjective => vec![Noun],
+                // "and" may join actions or nouns; "then" starts a new action
+                TokenType::Conjunction => vec![Verb, Article, Noun],
+                TokenType::Noun => vec![Particle, SentenceEnd, Touten],
+                TokenType::Preposition => vec![Article, Noun],
+                TokenType::Punctuation => vec![Verb, Noun, Article],
+                _ => vec![Noun, Article, Particle],
+            }
+        }
+        _ => match TokenType::classify(last) {
+            TokenType::Noun => vec![Particle, Other, Touten],
+            // を is followed by the verb; other particles usually by another noun
+            TokenType::Particle if last == "を" => vec![Verb, Noun],
+            TokenType::Particle => vec![Noun, Verb],
+            TokenType::Verb => vec![SentenceEnd, Touten],
+            TokenType::Adverb => vec![Verb],
+            TokenType::Punctuation | TokenType::Conjunction => vec![Noun, Verb],
+            _ => vec![Noun, Verb, Touten],
+        },
+    }
+}
+
+// ============================================================================
+// Usage Frequency
+// ============================================================================
+
+/// Count block types in a Blockly workspace (serialized JSON)
+///
+/// Every object with a `"type": "promps_*"` field is counted, so blocks
+/// nested in `next`/`inputs` are included.
+pub fn count_block_usage(workspace: &serde_json::Value, usage: &mut BlockUsage) {
+    match workspace {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(block_type)) = map.get("type") {
+                if block_type.starts_with("promps_") {
+                    *usage.entry(block_type.clone()).or_insert(0) += 1;
+                }
+            }
+            for value in map.values() {
+                count_block_usage(value, usage);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for value in items {
+                count_block_usage(value, usage);
+            }
+        }
+        _ => {}
+    }
+}
+
+// ============================================================================
+// Suggestion
+// ============================================================================
+
+/// Weight of the first grammar candidate (later candidates get slightly less)
+const GRAMMAR_WEIGHT: f64 = 1.0;
+/// Weight of the most frequently used block type
+const FREQUENCY_WEIGHT: f64 = 0.5;
+
+/// Next slot of a partially matched pattern: first of the trailing missing slots
+fn next_pattern_slot(missing_slots: &[usize], block_count: usize) -> Option<usize> {
+    let mut slots = missing_slots.to_vec();
+    slots.sort_unstable();
+    slots.dedup();
+
+    let mut next = None;
+    let mut expected = block_count;
+    for &slot in slots.iter().rev() {
+        if slot + 1 != expected {
+            break;
+        }
+        next = Some(slot);
+        expected = slot;
+    }
+    next
+}
+
+/// Suggest block types that can follow the input
+///
+/// # Arguments
+/// * `input` - Space-delimited DSL tokens up to the cursor
+/// * `locale` - Locale code ("ja", "en", "fr")
+/// * `patterns` - Pattern templates to complete
+/// * `usage` - Block usage counts from the user's projects
+///
+/// # Returns
+/// Suggestions sorted by score (highest first)
+pub fn suggest_next(
+    input: &str,
+    locale: &str,
+    patterns: &[PatternTemplate],
+    usage: &BlockUsage,
+) -> Vec<Suggestion> {
+    let owned_tokens = split_tokens(input);
+    let tokens: Vec<&str> = owned_tokens.iter().map(|t| t.as_str()).collect();
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+
+    fn add(suggestions: &mut Vec<Suggestion>, block_type: &str, locale: &str, score: f64, source: SuggestionSource) {
+        if let Some(existing) = suggestions.iter_mut().find(|s| s.block_type == block_type) {
+            existing.score += score;
+            if !existing.sources.contains(&source) {
+                existing.sources.push(source);
+            }
+        } else {
+            suggestions.push(Suggestion {
+                block_type: block_type.to_string(),
+                text: block_word(block_type, locale).map(|w| w.to_string()),
+                score,
+                sources: vec![source],
+            });
+        }
+    }
+
+    // 1. Grammar state
+    for (rank, category) in next_categories(&tokens, locale).into_iter().enumerate() {
+        let weight = GRAMMAR_WEIGHT - 0.1 * rank as f64;
+        for block_type in blocks_in(category, locale) {
+            add(&mut suggestions, block_type, locale, weight, SuggestionSource::Grammar);
+        }
+    }
+
+    // 2. Partially matched patterns ending at the cursor
+    if !tokens.is_empty() {
+        for result in analyze_patterns_with_templates(input, locale, patterns) {
+            let reaches_cursor = result.matched_range.map(|r| r.end == tokens.len()).unwrap_or(false);
+            if result.is_complete || !reaches_cursor {
+                continue;
+            }
+            let Some(pattern) = patterns.iter().find(|p| p.id == result.pattern_id) else { continue };
+            let Some(slot) = next_pattern_slot(&result.missing_slots, pattern.blocks.len()) else { continue };
+            let block = &pattern.blocks[slot];
+
+            if block.is_placeholder {
+                // Placeholder slots accept any block of the same category
+                if let Some(category) = category_of(&block.block_type) {
+                    for block_type in blocks_in(category, locale) {
+                        add(&mut suggestions, block_type, locale, result.match_score, SuggestionSource::Pattern);
+                    }
+                }
+            } else {
+                add(&mut suggestions, &block.block_type, locale, result.match_score, SuggestionSource::Pattern);
+            }
+        }
+    }
+
+    // 3. Usage frequency (only boosts candidates from grammar or patterns)
+    let max_count = usage.values().copied().max().unwrap_or(0);
+    if max_count > 0 {
+        for suggestion in suggestions.iter_mut() {
+            let count = usage.get(&suggestion.block_type).copied().unwrap_or(0);
+            if count > 0 {
+                suggestion.score += FREQUENCY_WEIGHT * count as f64 / max_count as f64;
+                suggestion.sources.push(SuggestionSource::Frequency);
+            }
+        }
+    }
+
+    // Stable sort keeps palette order on ties
+    suggestions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    suggestions
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::validation::get_pattern_templates_by_locale;
+
+    fn suggest(input: &str, locale: &str) -> Vec<Suggestion> {
+        suggest_next(input, locale, &get_pattern_templates_by_locale(locale), &BlockUsage::new())
+    }
+
+    fn position(suggestions: &[Suggestion], block_type: &str) -> usize {
+        suggestions.iter().position(|s| s.block_type == block_type).unwrap()
+    }
+
+    #[test]
+    fn test_suggest_empty_input_starts_with_noun() {
+        let suggestions = suggest("", "ja");
+        assert_eq!(suggestions[0].block_type, "promps_noun");
+    }
+
+    #[test]
+    fn test_suggest_particle_after_noun() {
+        let suggestions = suggest("_N:Doc", "ja");
+        assert_eq!(category_of(&suggestions[0].block_type), Some(Category::Particle));
+        assert!(!suggestions.iter().any(|s| s.block_type.starts_with("promps_verb_")));
+    }
+
+    #[test]
+    fn test_suggest_verb_after_wo() {
+        let suggestions = suggest("_N:Doc を", "ja");
+        assert_eq!(category_of(&suggestions[0].block_type), Some(Category::Verb));
+        assert!(suggestions[0].sources.contains(&SuggestionSource::Pattern));
+        assert!(position(&suggestions, "promps_verb_analyze") < position(&suggestions, "promps_noun"));
+    }
+
+    #[test]
+    fn test_suggest_pattern_fixed_slot() {
+        // sov_basic: 名詞 が 名詞 を 動詞 -> を follows the second noun
+        let suggestions = suggest("_N:User が _N:Doc", "ja");
+        let wo = suggestions.iter().find(|s| s.block_type == "promps_particle_wo").unwrap();
+        assert!(wo.sources.contains(&SuggestionSource::Pattern));
+        assert_eq!(suggestions[0].block_type, "promps_particle_wo");
+    }
+
+    #[test]
+    fn test_suggest_en_after_verb() {
+        let suggestions = suggest("analyze", "en");
+        let top = category_of(&suggestions[0].block_type);
+        assert!(top == Some(Category::Article) || top == Some(Category::Noun));
+        assert_eq!(suggestions.iter().find(|s| s.block_type == "promps_article_the").unwrap().text.as_deref(), Some("the"));
+    }
+
+    #[test]
+    fn test_suggest_en_skips_empty_particles() {
+        let suggestions = suggest("analyze _N:doc", "en");
+        assert!(!suggestions.iter().any(|s| s.block_type == "promps_particle_ga"));
+        assert!(suggestions.iter().any(|s| s.block_type == "promps_particle_ni"));
+    }
+
+    #[test]
+    fn test_suggest_frequency_breaks_ties() {
+        let mut usage = BlockUsage::new();
+        usage.insert("promps_particle_de".to_string(), 10);
+
+        let suggestions = suggest_next("_N:Doc", "ja", &[], &usage);
+        assert_eq!(suggestions[0].block_type, "promps_particle_de");
+        assert!(suggestions[0].sources.contains(&SuggestionSource::Frequency));
+    }
+
+    #[test]
+    fn test_count_block_usage_nested() {
+        let workspace = serde_json::json!({
+            "blocks": {"blocks": [{
+                "type": "promps_noun",
+                "fields": {"TEXT": "Doc"},
+                "next": {"block": {
+                    "type": "promps_particle_wo",
+                    "next": {"block": {"type": "promps_noun"}}
+                }}
+            }]}
+        });
+        let mut usage = BlockUsage::new();
+        count_block_usage(&workspace, &mut usage);
+
+        assert_eq!(usage.get("promps_noun"), Some(&2));
+        assert_eq!(usage.get("promps_particle_wo"), Some(&1));
+    }
+
+    #[test]
+    fn test_next_pattern_slot() {
+        assert_eq!(next_pattern_slot(&[0, 1, 4], 5), Some(4));
+        assert_eq!(next_pattern_slot(&[2, 3, 4], 5), Some(2));
+        assert_eq!(next_pattern_slot(&[1], 5), None);
+    }
+
+    #[test]
+    fn test_suggestion_serialization() {
+        let suggestions = suggest("_N:Doc を", "ja");
+        let json = serde_json::to_string(&suggestions[0]).unwrap();
+        assert!(json.contains("\"blockType\":"));
+        assert!(json.contains("\"sources\":[\"grammar\",\"pattern\"]"));
+    }
+}
@@ -0,0 +1,377 @@
+/// Promps File Inclusion
+///
+/// Resolves `_FILE:path` tokens at generation time. Paths are read from
+/// the directory of the open project file (the same `.promps` path used by
+/// `load_project`) and must stay inside it; the content is inlined into the
+/// prompt as a labeled fenced block.
+///
+/// Files larger than `MAX_FILE_SIZE` or not valid UTF-8 are not inlined;
+/// `validate_file_includes` reports them as validation errors.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use promps::tokenizer::tokenize;
+use promps::{PartKind, PromptPart};
+
+use super::validation::{Severity, ValidationError, ValidationErrorCode};
+
+/// Maximum size of an included file (256 KiB)
+pub const MAX_FILE_SIZE: u64 = 256 * 1024;
+
+/// Reason an included file could not be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileIncludeError {
+    /// The project has not been saved yet
+    NoBaseDirectory,
+    /// File does not exist (or is not a regular file)
+    NotFound,
+    /// File is outside the project directory (absolute path or `../`)
+    OutsideProject,
+    /// File exceeds `MAX_FILE_SIZE`
+    TooLarge { size: u64 },
+    /// File is not valid UTF-8 text
+    NotUtf8,
+    /// Other I/O error
+    Unreadable(String),
+}
+
+impl FileIncludeError {
+    /// Validation error code for this error
+    pub fn code(&self) -> ValidationErrorCode {
+        match self {
+            FileIncludeError::NoBaseDirectory
+            | FileIncludeError::NotFound
+            | FileIncludeError::Unreadable(_) => ValidationErrorCode::FileNotFound,
+            FileIncludeError::TooLarge { .. } => ValidationErrorCode::FileTooLarge,
+            FileIncludeError::NotUtf8 => ValidationErrorCode::FileNotUtf8,
+            FileIncludeError::OutsideProject => ValidationErrorCode::FileOutsideProject,
+        }
+    }
+
+    /// Localized message and suggestion
+    fn message(&self, path: &str, locale: &str) -> (String, String) {
+        let limit_kb = MAX_FILE_SIZE / 1024;
+        match (self, locale) {
+            (FileIncludeError::NoBaseDirectory, "en") => (
+                format!("Cannot include '{}' in an unsaved project", path),
+                "Save the project first".to_string(),
+            ),
+            (FileIncludeError::NoBaseDirectory, "fr") => (
+                format!("Impossible d'inclure '{}' dans un projet non enregistré", path),
+                "Enregistrez d'abord le projet".to_string(),
+            ),
+            (FileIncludeError::NoBaseDirectory, _) => (
+                format!("未保存のプロジェクトでは「{}」を挿入できません", path),
+                "先にプロジェクトを保存してください".to_string(),
+            ),
+            (FileIncludeError::OutsideProject, "en") => (
+                format!("'{}' is outside the project directory", path),
+                "Move the file into the project directory".to_string(),
+            ),
+            (FileIncludeError::OutsideProject, "fr") => (
+                format!("'{}' est en dehors du dossier du projet", path),
+                "Déplacez le fichier dans le dossier du projet".to_string(),
+            ),
+            (FileIncludeError::OutsideProject, _) => (
+                format!("「{}」はプロジェクトのディレクトリの外にあります", path),
+                "ファイルをプロジェクトのディレクトリ内に移動してください".to_string(),
+            ),
+            (FileIncludeError::NotFound, "en") => (
+                format!("File '{}' not found", path),
+                "Check the path (relative to the project file)".to_string(),
+            ),
+            (FileIncludeError::NotFound, "fr") => (
+                format!("Fichier '{}' introuvable", path),
+                "Vérifiez le chemin (relatif au fichier du projet)".to_string(),
+            ),
+            (FileIncludeError::NotFound, _) => (
+                format!("ファイル「{}」が見つかりません", path),
+                "パスを確認してください（プロジェクトファイルからの相対パス）".to_string(),
+            ),
+            (FileIncludeError::TooLarge { size }, "en") => (
+                format!("File '{}' is too large ({} KB, limit {} KB)", path, size / 1024, limit_kb),
+                "Include a smaller file or an excerpt".to_string(),
+            ),
+            (FileIncludeError::TooLarge { size }, "fr") => (
+                format!("Le fichier '{}' est trop volumineux ({} Ko, limite {} Ko)", path, size / 1024, limit_kb),
+                "Incluez un fichier plus petit ou un extrait".to_string(),
+            ),
+            (FileIncludeError::TooLarge { size }, _) => (
+                format!("ファイル「{}」が大きすぎます（{} KB、上限 {} KB）", path, size / 1024, limit_kb),
+                "より小さいファイルか抜粋を使用してください".to_string(),
+            ),
+            (FileIncludeError::NotUtf8, "en") => (
+                format!("File '{}' is not UTF-8 text", path),
+                "Only UTF-8 text files can be included".to_string(),
+            ),
+            (FileIncludeError::NotUtf8, "fr") => (
+                format!("Le fichier '{}' n'est pas un texte UTF-8", path),
+                "Seuls les fichiers texte UTF-8 peuvent être inclus".to_string(),
+            ),
+            (FileIncludeError::NotUtf8, _) => (
+                format!("ファイル「{}」はUTF-8テキストではありません", path),
+                "UTF-8のテキストファイルのみ挿入できます".to_string(),
+            ),
+            (FileIncludeError::Unreadable(e), "en") => (
+                format!("Failed to read file '{}': {}", path, e),
+                "Check the file permissions".to_string(),
+            ),
+            (FileIncludeError::Unreadable(e), "fr") => (
+                format!("Échec de la lecture du fichier '{}' : {}", path, e),
+                "Vérifiez les permissions du fichier".to_string(),
+            ),
+            (FileIncludeError::Unreadable(e), _) => (
+                format!("ファイル「{}」を読み込めません: {}", path, e),
+                "ファイルの権限を確認してください".to_string(),
+            ),
+        }
+    }
+}
+
+/// Directory that relative `_FILE:` paths are resolved against
+///
+/// # Arguments
+/// * `project_path` - Path of the `.promps` project file, if saved
+pub fn project_base_dir(project_path: Option<&str>) -> Option<PathBuf> {
+    project_path
+        .map(Path::new)
+        .and_then(|p| p.parent())
+        .map(|p| p.to_path_buf())
+}
+
+/// Resolve an included path relative to `base_dir`
+///
+/// The canonical path must be inside `root` (the top-level project
+/// directory), so absolute paths and `../` cannot leave the project.
+pub fn resolve_include_path(
+    path: &str,
+    base_dir: Option<&Path>,
+    root: Option<&Path>,
+) -> Result<PathBuf, FileIncludeError> {
+    let (base_dir, root) = base_dir.zip(root).ok_or(FileIncludeError::NoBaseDirectory)?;
+    let root = fs::canonicalize(root).map_err(|_| FileIncludeError::NotFound)?;
+    let full_path = fs::canonicalize(base_dir.join(path)).map_err(|_| FileIncludeError::NotFound)?;
+    if !full_path.starts_with(&root) {
+        return Err(FileIncludeError::OutsideProject);
+    }
+    Ok(full_path)
+}
+
+/// Read an included file with size and UTF-8 checks
+///
+/// # Arguments
+/// * `path` - Path as written in the `_FILE:` token
+/// * `base_dir` - Project directory (see `project_base_dir`)
+///
+/// # Returns
+/// File content, or the reason it cannot be included
+pub fn read_included_file(path: &str, base_dir: Option<&Path>) -> Result<String, FileIncludeError> {
+    read_included_file_within(path, base_dir, base_dir)
+}
+
+/// Read an included file of a project that may be nested below `root`
+fn read_included_file_within(
+    path: &str,
+    base_dir: Option<&Path>,
+    root: Option<&Path>,
+) -> Result<String, FileIncludeError> {
+    let full_path = resolve_include_path(path, base_dir, root)?;
+
+    let metadata = fs::metadata(&full_path).map_err(|_| FileIncludeError::NotFound)?;
+    if !metadata.is_file() {
+        return Err(FileIncludeError::NotFound);
+    }
+    if metadata.len() > MAX_FILE_SIZE {
+        return Err(FileIncludeError::TooLarge { size: metadata.len() });
+    }
+
+    let bytes = fs::read(&full_path).map_err(|e| FileIncludeError::Unreadable(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|_| FileIncludeError::NotUtf8)
+}
+
+/// Fill in the content of `_FILE:` parts
+///
+/// Parts whose file cannot be read stay unresolved (rendered as a label).
+///
+/// # Returns
+/// Number of files that could not be included
+pub fn resolve_file_parts(parts: &mut [PromptPart], base_dir: Option<&Path>) -> usize {
+    resolve_file_parts_within(parts, base_dir, base_dir)
+}
+
+/// Fill in the content of `_FILE:` parts of an included project
+///
+/// Paths are relative to the included project's directory (`base_dir`) but
+/// must stay inside the top-level project directory (`root`).
+pub fn resolve_file_parts_within(
+    parts: &mut [PromptPart],
+    base_dir: Option<&Path>,
+    root: Option<&Path>,
+) -> usize {
+    let mut failed = 0;
+
+    for part in parts.iter_mut() {
+        if let PartKind::File { path, content } = &mut part.kind {
+            match read_included_file_within(path, base_dir, root) {
+                Ok(text) => *content = Some(text),
+                Err(_) => failed += 1,
+            }
+        }
+    }
+
+    failed
+}
+
+/// Check every `_FILE:` token in the input
+///
+/// # Arguments
+/// * `input` - DSL text
+/// * `base_dir` - Project directory (see `project_base_dir`)
+/// * `locale` - Locale code for messages ("ja", "en", "fr")
+///
+/// # Returns
+/// One error per file that cannot be included (position = token index)
+pub fn validate_file_includes(input: &str, base_dir: Option<&Path>, locale: &str) -> Vec<ValidationError> {
+    tokenize(input)
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| token.marker.as_deref() == Some("FILE"))
+        .filter_map(|(i, token)| {
+            let error = read_included_file(&token.text, base_dir).err()?;
+            let (message, suggestion) = error.message(&token.text, locale);
+            Some(ValidationError::new(error.code(), message, i, Severity::Error, Some(suggestion)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use promps::{generate_prompt, parse_input};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("promps_test_files_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_project_base_dir() {
+        assert_eq!(
+            project_base_dir(Some("/home/user/prompts/review.promps")),
+            Some(PathBuf::from("/home/user/prompts"))
+        );
+        assert_eq!(project_base_dir(None), None);
+    }
+
+    #[test]
+    fn test_relative_path_without_project() {
+        assert_eq!(read_included_file("notes.md", None), Err(FileIncludeError::NoBaseDirectory));
+    }
+
+    #[test]
+    fn test_paths_outside_project_rejected() {
+        let dir = temp_dir("outside_project");
+        let outside = temp_dir("outside_target");
+        fs::write(outside.join("secret.txt"), "secret").unwrap();
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(dir.join("docs/notes.md"), "notes").unwrap();
+
+        let absolute = outside.join("secret.txt").to_string_lossy().to_string();
+        let relative = "../promps_test_files_outside_target/secret.txt";
+        assert_eq!(read_included_file(&absolute, Some(&dir)), Err(FileIncludeError::OutsideProject));
+        assert_eq!(read_included_file(relative, Some(&dir)), Err(FileIncludeError::OutsideProject));
+        assert_eq!(read_included_file("docs/../docs/notes.md", Some(&dir)), Ok("notes".to_string()));
+
+        // Nested project: ../ is fine while it stays inside the root
+        let nested = dir.join("docs");
+        assert_eq!(
+            read_included_file_within("../docs/notes.md", Some(&nested), Some(&dir)),
+            Ok("notes".to_string())
+        );
+
+        let errors = validate_file_includes(&format!("_FILE:\"{}\"", absolute), Some(&dir), "en");
+        assert_eq!(errors[0].code, ValidationErrorCode::FileOutsideProject);
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&outside);
+    }
+
+    #[test]
+    fn test_read_relative_to_project() {
+        let dir = temp_dir("relative");
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {\n    run();\n}").unwrap();
+
+        let content = read_included_file("src/main.rs", Some(&dir)).unwrap();
+        assert_eq!(content, "fn main() {\n    run();\n}");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_missing_and_directory_not_found() {
+        let dir = temp_dir("missing");
+
+        assert_eq!(read_included_file("nope.txt", Some(&dir)), Err(FileIncludeError::NotFound));
+        assert_eq!(read_included_file(".", Some(&dir)), Err(FileIncludeError::NotFound));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let dir = temp_dir("large");
+        fs::write(dir.join("big.log"), vec![b'a'; MAX_FILE_SIZE as usize + 1]).unwrap();
+
+        assert_eq!(
+            read_included_file("big.log", Some(&dir)),
+            Err(FileIncludeError::TooLarge { size: MAX_FILE_SIZE + 1 })
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_not_utf8() {
+        let dir = temp_dir("binary");
+        fs::write(dir.join("image.png"), [0x89, 0x50, 0x4e, 0x47, 0xff, 0xfe]).unwrap();
+
+        assert_eq!(read_included_file("image.png", Some(&dir)), Err(FileIncludeError::NotUtf8));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolve_file_parts_inlines_content() {
+        let dir = temp_dir("resolve");
+        fs::write(dir.join("data.json"), "{\n  \"id\": 1\n}").unwrap();
+
+        let mut parts = parse_input("_FILE:data.json を _FILE:missing.txt と 比較して");
+        let failed = resolve_file_parts(&mut parts, Some(&dir));
+        let prompt = generate_prompt(&parts);
+
+        assert_eq!(failed, 1);
+        assert!(prompt.starts_with("data.json (FILE)\n```json\n{\n  \"id\": 1\n}\n```\n"));
+        assert!(prompt.contains("missing.txt (FILE) と 比較して"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_validate_file_includes() {
+        let dir = temp_dir("validate");
+        fs::write(dir.join("ok.txt"), "ok").unwrap();
+
+        let errors = validate_file_includes("_FILE:ok.txt と _FILE:gone.txt を 比較して", Some(&dir), "en");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ValidationErrorCode::FileNotFound);
+        assert_eq!(errors[0].position, 2);
+        assert!(errors[0].message.contains("gone.txt"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
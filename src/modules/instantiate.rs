@@ -0,0 +1,257 @@
+/// Promps - Pattern Instantiation Module
+///
+/// This module turns a pattern template plus slot values into DSL text,
+/// producing the same output the Blockly blocks would generate.
+/// The result is validated so scripts and the CLI can scaffold prompts
+/// without the block editor.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use promps::tokenizer::quote_text;
+
+use super::validation::{
+    block_word, validate_sequence_with_locale, PatternBlock, PatternTemplate, ValidationResult,
+};
+
+// ============================================================================
+// Result Types
+// ============================================================================
+
+/// A required slot that has no value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnfilledSlot {
+    /// Block index in the template (0-indexed)
+    pub index: usize,
+    /// Slot label (e.g. "目的語", "Object")
+    pub label: String,
+    /// Block type of the slot
+    pub block_type: String,
+}
+
+/// Result of instantiating a pattern template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstantiationResult {
+    /// Generated DSL (unfilled slots are left out)
+    pub dsl: String,
+    /// Required slots without a value
+    pub unfilled_slots: Vec<UnfilledSlot>,
+    /// Grammar validation of the generated DSL
+    pub validation: ValidationResult,
+    /// All required slots filled and the DSL has no grammar errors
+    pub complete: bool,
+}
+
+// ============================================================================
+// Instantiation
+// ============================================================================
+
+/// Look up the value for a slot by label, then by block index
+fn slot_value<'a>(values: &'a HashMap<String, String>, index: usize, block: &PatternBlock) -> Option<&'a str> {
+    values
+        .get(&block.label)
+        .or_else(|| values.get(&index.to_string()))
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+}
+
+/// DSL text of a single block, or None if the slot is unfilled
+///
+/// Returns `Some("")` for blocks with no output in the locale (e.g. が in English).
+fn block_dsl(block: &PatternBlock, value: Option<&str>, locale: &str) -> Option<String> {
+    let value = value.or(block.default_value.as_deref());
+
+    match block.block_type.as_str() {
+        "promps_noun" => value.map(|v| format!("_N:{}", quote_text(v))),
+        "promps_verb_custom" => value.map(|v| format!("_V:{}", quote_text(v))),
+        "promps_other" => value.map(|v| v.to_string()),
+        // Modifier slots are optional: unfilled ones are left out
+        "promps_adjective" => Some(value.map(|v| format!("_ADJ:{}", quote_text(v))).unwrap_or_default()),
+        "promps_adverb" => Some(value.map(|v| format!("_ADV:{}", quote_text(v))).unwrap_or_default()),
+        block_type if block_type.starts_with("promps_verb_") => {
+            // A verb slot accepts free text or another verb block type
+            let word = match value {
+                Some(v) if v.starts_with("promps_verb_") => block_word(v, locale).unwrap_or(v).to_string(),
+                Some(v) => quote_text(v),
+                None => block_word(block_type, locale).unwrap_or("").to_string(),
+            };
+            Some(format!("_V:{}", word))
+        }
+        block_type => Some(block_word(block_type, locale).unwrap_or("").to_string()),
+    }
+}
+
+/// Instantiate a pattern template with slot values
+///
+/// # Arguments
+/// * `pattern` - Template to instantiate
+/// * `locale` - Locale code ("ja", "en", "fr") for fixed block output
+/// * `values` - Slot values keyed by block label or block index ("0", "1", ...)
+///
+/// # Returns
+/// InstantiationResult with DSL, unfilled slots, validation and completeness
+pub fn instantiate_template(
+    pattern: &PatternTemplate,
+    locale: &str,
+    values: &HashMap<String, String>,
+) -> InstantiationResult {
+    let mut words = Vec::new();
+    let mut unfilled_slots = Vec::new();
+
+    for (index, block) in pattern.blocks.iter().enumerate() {
+        match block_dsl(block, slot_value(values, index, block), locale) {
+            Some(text) if !text.is_empty() => words.push(text),
+            Some(_) => {}
+            None => unfilled_slots.push(UnfilledSlot {
+                index,
+                label: block.label.clone(),
+                block_type: block.block_type.clone(),
+            }),
+        }
+    }
+
+    let dsl = words.join(" ");
+    let validation = validate_sequence_with_locale(&dsl, locale);
+
+    InstantiationResult {
+        complete: unfilled_slots.is_empty() && validation.is_valid,
+        dsl,
+        unfilled_slots,
+        validation,
+    }
+}
+
+/// Instantiate a pattern template by id
+///
+/// # Returns
+/// InstantiationResult, or an error if no template has the id
+pub fn instantiate_pattern(
+    pattern_id: &str,
+    locale: &str,
+    values: &HashMap<String, String>,
+    patterns: &[PatternTemplate],
+) -> Result<InstantiationResult, String> {
+    let pattern = patterns
+        .iter()
+        .find(|p| p.id == pattern_id)
+        .ok_or_else(|| format!("Pattern not found: {}", pattern_id))?;
+
+    Ok(instantiate_template(pattern, locale, values))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::validation::get_pattern_templates_by_locale;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn instantiate(id: &str, locale: &str, pairs: &[(&str, &str)]) -> InstantiationResult {
+        instantiate_pattern(id, locale, &values(pairs), &get_pattern_templates_by_locale(locale)).unwrap()
+    }
+
+    #[test]
+    fn test_instantiate_sov_basic() {
+        let result = instantiate("sov_basic", "ja", &[("主語", "ユーザー"), ("目的語", "ドキュメント")]);
+
+        assert_eq!(result.dsl, "_N:ユーザー が _N:ドキュメント を _V:分析して");
+        assert!(result.unfilled_slots.is_empty());
+        assert!(result.complete);
+        assert_eq!(serde_json::to_value(&result).unwrap()["complete"], true);
+    }
+
+    #[test]
+    fn test_instantiate_reports_unfilled_slots() {
+        let result = instantiate("sov_basic", "ja", &[("主語", "ユーザー")]);
+
+        assert_eq!(result.unfilled_slots.len(), 1);
+        assert_eq!(result.unfilled_slots[0].index, 2);
+        assert_eq!(result.unfilled_slots[0].label, "目的語");
+        assert!(!result.complete);
+    }
+
+    #[test]
+    fn test_instantiate_by_index() {
+        let result = instantiate("ov_simple", "ja", &[("0", "レポート")]);
+        assert_eq!(result.dsl, "_N:レポート を _V:要約して");
+    }
+
+    #[test]
+    fn test_instantiate_verb_override() {
+        let by_text = instantiate("ov_simple", "ja", &[("目的語", "文書"), ("動詞", "整理して")]);
+        let by_type = instantiate("ov_simple", "ja", &[("目的語", "文書"), ("動詞", "promps_verb_translate")]);
+
+        assert_eq!(by_text.dsl, "_N:文書 を _V:整理して");
+        assert_eq!(by_type.dsl, "_N:文書 を _V:翻訳して");
+    }
+
+    #[test]
+    fn test_instantiate_quotes_values_with_spaces() {
+        let result = instantiate("svo_basic", "en", &[("Action", "write down"), ("Object", "New York")]);
+
+        assert_eq!(result.dsl, "_V:\"write down\" _N:\"New York\"");
+        assert!(promps::parse_input(&result.dsl).iter().any(|p| p.text == "New York"));
+    }
+
+    #[test]
+    fn test_instantiate_default_value() {
+        // topic_about uses a promps_other block with default "について"
+        let result = instantiate("topic_about", "ja", &[("0", "データ")]);
+        assert!(result.dsl.starts_with("_N:データ について _V:"));
+    }
+
+    #[test]
+    fn test_instantiate_en_locale_words() {
+        let result = instantiate("svo_article", "en", &[("Object", "report")]);
+
+        assert_eq!(result.dsl, "_V:summarize the _N:report");
+        assert!(result.validation.is_valid);
+    }
+
+    #[test]
+    fn test_instantiate_optional_modifier_slot() {
+        let pattern = PatternTemplate::new(
+            "detailed",
+            "Detailed",
+            "",
+            "V the [Adj] N",
+            "",
+            vec![
+                PatternBlock::fixed("promps_verb_summarize", "Action"),
+                PatternBlock::fixed("promps_article_the", "the"),
+                PatternBlock::placeholder("promps_adjective", "Adjective"),
+                PatternBlock::placeholder("promps_noun", "Object"),
+            ],
+        );
+
+        let filled = instantiate_template(&pattern, "en", &values(&[("Adjective", "brief"), ("Object", "report")]));
+        let unfilled = instantiate_template(&pattern, "en", &values(&[("Object", "report")]));
+
+        assert_eq!(filled.dsl, "_V:summarize the _ADJ:brief _N:report");
+        assert_eq!(unfilled.dsl, "_V:summarize the _N:report");
+        assert!(unfilled.complete);
+    }
+
+    #[test]
+    fn test_instantiate_unknown_pattern() {
+        let result = instantiate_pattern("missing", "ja", &HashMap::new(), &get_pattern_templates_by_locale("ja"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_instantiation_result_serialization() {
+        let result = instantiate("ov_simple", "ja", &[]);
+        let json = serde_json::to_string(&result).unwrap();
+
+        assert!(json.contains("\"unfilledSlots\":"));
+        assert!(json.contains("\"validation\":"));
+    }
+}
@@ -0,0 +1,214 @@
+/// Promps Locale Detection
+///
+/// Guesses the locale of a DSL sentence from its script and lexicon:
+/// kana / kanji words vote for "ja"; Latin words vote for "fr" when they
+/// carry French diacritics or elisions or are French function words or
+/// verbs, and for "en" when they are English ones. Unknown Latin words
+/// are a weak vote for "en". Nouns only count when a sentence has no
+/// other words (names are often written in another language).
+
+use std::ops::Range;
+
+use promps::tokenizer::{tokenize, Token};
+use promps::{is_keyword_token, is_list_start, token_role};
+
+use super::normalize::normalize_width;
+use super::validation::TokenType;
+
+/// Locale argument value that requests detection
+pub const AUTO_LOCALE: &str = "auto";
+
+/// Locale used when nothing can be detected
+pub const DEFAULT_LOCALE: &str = "ja";
+
+/// Detectable locales, in tie-break order
+const LOCALES: [&str; 3] = ["ja", "en", "fr"];
+
+/// Characters that only occur in French (among the supported locales)
+const FRENCH_DIACRITICS: &str = "àâäçéèêëîïôöùûüÿœæ";
+
+/// Weight of a Latin word that neither lexicon knows
+const UNKNOWN_LATIN_WEIGHT: f32 = 0.25;
+
+/// Check if a character is Japanese script (kana, kanji, CJK punctuation)
+fn is_japanese_char(c: char) -> bool {
+    matches!(c,
+        '\u{3001}'..='\u{3002}'    // 、。
+        | '\u{3005}'               // 々
+        | '\u{3040}'..='\u{30FF}'  // hiragana, katakana
+        | '\u{3400}'..='\u{4DBF}'  // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'  // CJK unified ideographs
+    )
+}
+
+/// Check if a classified word is a lexicon word (not a noun, punctuation or unknown)
+fn is_lexicon_word(token_type: TokenType) -> bool {
+    !matches!(token_type, TokenType::Other | TokenType::Noun | TokenType::Punctuation)
+}
+
+/// Votes of a single word, in `LOCALES` order
+fn word_votes(word: &str) -> [f32; 3] {
+    let word = normalize_width(word).to_lowercase().replace('’', "'");
+
+    if word.chars().any(is_japanese_char) {
+        return [1.0, 0.0, 0.0];
+    }
+    if !word.chars().any(char::is_alphabetic) {
+        return [0.0; 3];
+    }
+
+    let elided = ["l'", "d'", "j'", "qu'", "n'", "s'", "c'"]
+        .iter()
+        .any(|prefix| word.starts_with(prefix) && word.len() > prefix.len());
+    if elided || word.chars().any(|c| FRENCH_DIACRITICS.contains(c)) {
+        return [0.0, 0.0, 1.0];
+    }
+
+    match (
+        is_lexicon_word(TokenType::classify_en(&word)),
+        is_lexicon_word(TokenType::classify_fr(&word)),
+    ) {
+        (true, false) => [0.0, 1.0, 0.0],
+        (false, true) => [0.0, 0.0, 1.0],
+        (true, true) => [0.0; 3],
+        (false, false) => [0.0, UNKNOWN_LATIN_WEIGHT, 0.0],
+    }
+}
+
+/// Detect the locale of a token sequence
+///
+/// # Returns
+/// "ja", "en" or "fr", or None if no token gives a hint
+pub fn detect_locale(tokens: &[Token]) -> Option<&'static str> {
+    let mut words = [0.0f32; 3];
+    let mut nouns = [0.0f32; 3];
+
+    for token in tokens {
+        let votes = match token.marker.as_deref() {
+            None if !token.text.starts_with('_') || token.escaped => &mut words,
+            Some("V" | "ADJ" | "ADV") => &mut words,
+            Some("N") => &mut nouns,
+            _ => continue,
+        };
+        for (total, vote) in votes.iter_mut().zip(word_votes(&token.text)) {
+            *total += vote;
+        }
+    }
+
+    let scores = if words.iter().any(|&s| s > 0.0) { words } else { nouns };
+    let (best, score) = LOCALES
+        .iter()
+        .zip(scores)
+        .fold((None, 0.0), |(best, max), (&locale, score)| {
+            if score > max { (Some(locale), score) } else { (best, max) }
+        });
+    best.filter(|_| score > 0.0)
+}
+
+/// Detect the locale of a whole DSL input
+///
+/// # Returns
+/// Detected locale, or `DEFAULT_LOCALE`
+pub fn detect_input_locale(input: &str) -> &'static str {
+    detect_locale(&tokenize(input)).unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Check if a token ends a sentence (。 . ! ? and their width variants)
+fn is_terminal(token: &Token) -> bool {
+    token.marker.is_none() && matches!(normalize_width(&token.text).as_str(), "。" | "." | "!" | "?")
+}
+
+/// Split tokens into sentences
+///
+/// Sentences end at a sentence break (blank line or double space), after
+/// terminal punctuation and at chat role markers (which belong to no sentence).
+/// A `_LIST` ... `_ENDLIST` section stays in one sentence, whatever its items contain.
+pub fn sentence_ranges(tokens: &[Token]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut in_list = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if in_list {
+            if is_keyword_token(token, "_ENDLIST") {
+                in_list = false;
+                continue;
+            }
+            // Role markers also end an open list
+            if token_role(token).is_none() {
+                continue;
+            }
+            in_list = false;
+        } else if is_list_start(token) {
+            in_list = true;
+        }
+
+        if token.sentence_break && i > start {
+            ranges.push(start..i);
+            start = i;
+        }
+        if token_role(token).is_some() {
+            if i > start {
+                ranges.push(start..i);
+            }
+            start = i + 1;
+        } else if is_terminal(token) {
+            ranges.push(start..i + 1);
+            start = i + 1;
+        }
+    }
+    if start < tokens.len() {
+        ranges.push(start..tokens.len());
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(input: &str) -> Option<&'static str> {
+        detect_locale(&tokenize(input))
+    }
+
+    #[test]
+    fn test_detect_by_script_and_lexicon() {
+        assert_eq!(detect("_N:Document を 分析して"), Some("ja"));
+        assert_eq!(detect("analyze the _N:document"), Some("en"));
+        assert_eq!(detect("analyser le _N:document"), Some("fr"));
+        assert_eq!(detect("résumer _N:rapport"), Some("fr"));
+        assert_eq!(detect("explique l'erreur"), Some("fr"));
+        assert_eq!(detect("explique l’erreur"), Some("fr"));
+    }
+
+    #[test]
+    fn test_nouns_only_as_fallback() {
+        // English names in a Japanese sentence
+        assert_eq!(detect("_N:User が _N:Order を 確認して"), Some("ja"));
+        assert_eq!(detect("_N:ユーザー"), Some("ja"));
+        assert_eq!(detect("_N:Order"), Some("en"));
+        assert_eq!(detect("! ?"), None);
+        assert_eq!(detect(""), None);
+    }
+
+    #[test]
+    fn test_sentence_ranges() {
+        let tokens = tokenize("_N:文書 を 要約して 。 then translate it .\n_ROLE:user résumer _N:x");
+        let ranges = sentence_ranges(&tokens);
+
+        assert_eq!(ranges, vec![0..4, 4..8, 9..11]);
+    }
+
+    #[test]
+    fn test_detect_input_locale_default() {
+        assert_eq!(detect_input_locale(""), "ja");
+        assert_eq!(detect_input_locale("please summarize the _N:report"), "en");
+    }
+
+    #[test]
+    fn test_sentence_ranges_keep_lists() {
+        let tokens = tokenize("_V:compare _LIST\nthe _N:report .\nthe _N:notes\n_ENDLIST .\n_V:summarize");
+        assert_eq!(sentence_ranges(&tokens), vec![0..9, 9..10]);
+    }
+}
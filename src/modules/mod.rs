@@ -8,3 +8,39 @@
 
 // Phase 5: Grammar Validation
 pub mod validation;
+
+// Pattern structure mini-language (N が N を V, [..], (..)+, (a|b))
+pub mod pattern_lang;
+
+// Cross-locale DSL translation (SOV <-> SVO)
+pub mod translation;
+
+// User-defined pattern templates (JSON/TOML files)
+pub mod pattern_loader;
+
+// Next block suggestions (grammar state + patterns + usage)
+pub mod completion;
+
+// Pattern template instantiation (template + slot values -> DSL)
+pub mod instantiate;
+
+// Blockly workspace JSON <-> DSL (headless generation and import)
+pub mod workspace;
+
+// Plain text -> DSL segmentation (no _N: markers / no spaces)
+pub mod segmentation;
+
+// _FILE:path inclusion (project-relative, size and UTF-8 checks)
+pub mod file_include;
+
+// _INCLUDE:path.promps sub-prompts (recursive, cycle checks, confined to project dir)
+pub mod project_include;
+
+// Near-duplicate noun spellings (normalization, edit distance, glossary aliases)
+pub mod noun_consistency;
+
+// NFKC-style token normalization before classification (width, kana, locale punctuation)
+pub mod normalize;
+
+// Per-sentence locale detection (script and lexicon based)
+pub mod locale_detect;
@@ -0,0 +1,165 @@
+/// Promps Token Normalization
+///
+/// NFKC-style folding of IME variations before token classification:
+/// full-width ASCII (`！`, `Ｏｒｄｅｒ`), half-width katakana (`ﾕｰｻﾞｰ`),
+/// half-width CJK punctuation and a few compatibility characters.
+///
+/// Only classification uses the normalized form; the original text is
+/// kept for the generated prompt.
+///
+/// Locale-aware exceptions: in "ja", `，` / `．` tokens (academic style) are
+/// the touten / kuten `、` / `。`; in "en" / "fr", `、` / `。` tokens left
+/// over from a Japanese IME are `,` / `.`.
+
+/// Half-width katakana U+FF66..=U+FF9D and their full-width forms
+const HALF_WIDTH_KATAKANA: &str = "ｦｧｨｩｪｫｬｭｮｯｰｱｲｳｴｵｶｷｸｹｺｻｼｽｾｿﾀﾁﾂﾃﾄﾅﾆﾇﾈﾉﾊﾋﾌﾍﾎﾏﾐﾑﾒﾓﾔﾕﾖﾗﾘﾙﾚﾛﾜﾝ";
+const FULL_WIDTH_KATAKANA: &str = "ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
+
+/// Half-width voiced sound marks
+const HALF_WIDTH_DAKUTEN: char = 'ﾞ';
+const HALF_WIDTH_HANDAKUTEN: char = 'ﾟ';
+
+/// Fold a single character (None = no compatibility mapping)
+fn fold_char(c: char) -> Option<&'static str> {
+    match c {
+        '\u{3000}' | '\u{00A0}' => Some(" "),
+        '｡' => Some("。"),
+        '｢' => Some("「"),
+        '｣' => Some("」"),
+        '､' => Some("、"),
+        '･' => Some("・"),
+        'ﬀ' => Some("ff"),
+        'ﬁ' => Some("fi"),
+        'ﬂ' => Some("fl"),
+        _ => None,
+    }
+}
+
+/// Full-width katakana for a half-width one
+fn widen_katakana(c: char) -> Option<char> {
+    HALF_WIDTH_KATAKANA
+        .chars()
+        .position(|h| h == c)
+        .and_then(|i| FULL_WIDTH_KATAKANA.chars().nth(i))
+}
+
+/// Compose a katakana with a following voiced sound mark (`カ` + `ﾞ` = `ガ`)
+fn compose_voiced(base: char, mark: char) -> Option<char> {
+    const VOICEABLE: &str = "カキクケコサシスセソタチツテトハヒフヘホ";
+    const SEMI_VOICEABLE: &str = "ハヒフヘホ";
+
+    match mark {
+        HALF_WIDTH_DAKUTEN if base == 'ウ' => Some('ヴ'),
+        HALF_WIDTH_DAKUTEN if VOICEABLE.contains(base) => char::from_u32(base as u32 + 1),
+        HALF_WIDTH_HANDAKUTEN if SEMI_VOICEABLE.contains(base) => char::from_u32(base as u32 + 2),
+        _ => None,
+    }
+}
+
+/// Locale-independent compatibility folding
+///
+/// # Returns
+/// Text with full-width ASCII, half-width katakana and compatibility
+/// characters replaced by their canonical forms
+pub fn normalize_width(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        // Full-width ASCII (U+FF01..=U+FF5E)
+        if ('\u{FF01}'..='\u{FF5E}').contains(&c) {
+            result.extend(char::from_u32(c as u32 - 0xFEE0));
+            continue;
+        }
+
+        if let Some(full) = widen_katakana(c) {
+            result.push(full);
+            continue;
+        }
+
+        if c == HALF_WIDTH_DAKUTEN || c == HALF_WIDTH_HANDAKUTEN {
+            match result.chars().last().and_then(|base| compose_voiced(base, c)) {
+                Some(voiced) => {
+                    result.pop();
+                    result.push(voiced);
+                }
+                None => result.push(if c == HALF_WIDTH_DAKUTEN { '゛' } else { '゜' }),
+            }
+            continue;
+        }
+
+        match fold_char(c) {
+            Some(folded) => result.push_str(folded),
+            None => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Normalize a token for classification in the given locale
+///
+/// # Arguments
+/// * `token` - Token text (without marker)
+/// * `locale` - Locale code ("ja", "en", "fr")
+pub fn normalize_token(token: &str, locale: &str) -> String {
+    let western = matches!(locale, "en" | "fr");
+
+    // Full-width comma / period are touten / kuten in Japanese (academic style);
+    // half-width `,` stays a comma (it has its own block)
+    match token {
+        "，" if !western => return "、".to_string(),
+        "．" if !western => return "。".to_string(),
+        _ => {}
+    }
+
+    // Punctuation tokens only (`file.txt` keeps its period)
+    let normalized = normalize_width(token);
+    match normalized.as_str() {
+        "、" if western => ",".to_string(),
+        "。" if western => ".".to_string(),
+        _ => normalized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_width_ascii() {
+        assert_eq!(normalize_width("Ｏｒｄｅｒ＿ＩＤ１２"), "Order_ID12");
+        assert_eq!(normalize_width("！？，．"), "!?,.");
+    }
+
+    #[test]
+    fn test_half_width_katakana() {
+        assert_eq!(normalize_width("ﾕｰｻﾞｰ"), "ユーザー");
+        assert_eq!(normalize_width("ﾃﾞｰﾀﾍﾞｰｽ"), "データベース");
+        assert_eq!(normalize_width("ﾊﾟｽﾜｰﾄﾞ"), "パスワード");
+        assert_eq!(normalize_width("ｳﾞｧｲｵﾘﾝ"), "ヴァイオリン");
+    }
+
+    #[test]
+    fn test_stray_voiced_marks() {
+        assert_eq!(normalize_width("ﾞ"), "゛");
+        assert_eq!(normalize_width("ｱﾟ"), "ア゜");
+    }
+
+    #[test]
+    fn test_half_width_punctuation_and_compat() {
+        assert_eq!(normalize_width("｢ﾃｽﾄ｣､｡"), "「テスト」、。");
+        assert_eq!(normalize_width("ﬁle\u{3000}x"), "file x");
+    }
+
+    #[test]
+    fn test_locale_exceptions() {
+        assert_eq!(normalize_token("，", "ja"), "、");
+        assert_eq!(normalize_token("．", "ja"), "。");
+        assert_eq!(normalize_token("。", "en"), ".");
+        assert_eq!(normalize_token("、", "fr"), ",");
+        assert_eq!(normalize_token("！", "ja"), "!");
+        assert_eq!(normalize_token("ｆｉｌｅ．ｔｘｔ", "ja"), "file.txt");
+        assert_eq!(normalize_token(",", "ja"), ",");
+        assert_eq!(normalize_token("｡", "en"), ".");
+    }
+}
@@ -0,0 +1,275 @@
+/// Promps Noun Consistency
+///
+/// Finds nouns that are likely spellings of the same entity
+/// (`ユーザー` / `ユーザ`, `Order` / `Orders`, `データベース` / `DB` via
+/// glossary aliases) and suggests replacing them with one canonical
+/// spelling.
+///
+/// Two spellings are grouped when their normalized forms are equal
+/// (case, trailing long vowel mark, plural), when they name the same
+/// glossary entry, or when their normalized edit distance is small.
+
+use promps::parse_input;
+use promps::tokenizer::tokenize;
+use promps::Glossary;
+
+use super::normalize::normalize_width;
+use super::validation::{AutoFixAction, AutoFixActionType, Severity, ValidationError, ValidationErrorCode};
+
+/// Edit distance relative to the longer spelling must stay below this
+const MAX_DISTANCE_RATIO: f64 = 0.2;
+
+/// Minimum length (characters) for edit-distance matching
+/// (shorter nouns such as `Table` / `Cable` or `Sender` / `Render` differ
+/// by design)
+const MIN_FUZZY_LENGTH: usize = 7;
+
+/// Normalize a noun for comparison
+///
+/// Folds width variants (`ﾕｰｻﾞｰ`, `Ｏｒｄｅｒ`), lowercases, drops a trailing
+/// katakana long vowel mark (`ユーザー` → `ユーザ`) and strips an English
+/// plural ending from the last word.
+pub fn normalize_noun(noun: &str) -> String {
+    let lower = normalize_width(noun.trim()).to_lowercase();
+    let without_long_vowel = lower.trim_end_matches('ー');
+    strip_plural(without_long_vowel)
+}
+
+/// Strip an English plural ending (`orders` → `order`, `entries` → `entry`)
+fn strip_plural(word: &str) -> String {
+    if !word.is_ascii() || word.chars().count() < 4 {
+        return word.to_string();
+    }
+    if let Some(stem) = word.strip_suffix("ies") {
+        return format!("{}y", stem);
+    }
+    for suffix in ["sses", "shes", "ches", "xes"] {
+        if word.ends_with(suffix) {
+            return word[..word.len() - 2].to_string();
+        }
+    }
+    match word.strip_suffix('s') {
+        Some(stem) if !stem.ends_with('s') && !stem.ends_with('u') => stem.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+/// Levenshtein distance over characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Check if two spellings likely name the same entity
+fn same_entity(a: &str, b: &str, glossary: &Glossary) -> bool {
+    if let (Some(x), Some(y)) = (glossary.lookup(a), glossary.lookup(b)) {
+        return x == y;
+    }
+
+    let (a, b) = (normalize_noun(a), normalize_noun(b));
+    if a == b {
+        return true;
+    }
+
+    let longest = a.chars().count().max(b.chars().count());
+    if a.chars().count().min(b.chars().count()) < MIN_FUZZY_LENGTH {
+        return false;
+    }
+    (edit_distance(&a, &b) as f64) / (longest as f64) < MAX_DISTANCE_RATIO
+}
+
+/// Canonical spelling of a group: the glossary term if defined, otherwise
+/// the most frequent spelling (first used wins ties)
+fn canonical_spelling<'a>(group: &[(&'a str, usize)], glossary: &'a Glossary) -> &'a str {
+    if let Some(entry) = group.iter().find_map(|(noun, _)| glossary.lookup(noun)) {
+        return &entry.term;
+    }
+    group
+        .iter()
+        .fold(None, |best: Option<(&str, usize)>, &(noun, count)| match best {
+            Some((_, best_count)) if best_count >= count => best,
+            _ => Some((noun, count)),
+        })
+        .map(|(noun, _)| noun)
+        .unwrap_or_default()
+}
+
+/// Check nouns for near-duplicate spellings
+///
+/// # Arguments
+/// * `input` - DSL text
+/// * `glossary` - Project glossary (aliases group spellings, terms are canonical)
+/// * `locale` - Locale code for messages ("ja", "en", "fr")
+///
+/// # Returns
+/// One warning per use of a non-canonical spelling, with a Replace auto-fix
+pub fn validate_noun_consistency(input: &str, glossary: &Glossary, locale: &str) -> Vec<ValidationError> {
+    // Distinct spellings of the noun parts, in order of first use, with counts
+    let mut spellings: Vec<(&str, usize)> = Vec::new();
+    let parts = parse_input(input);
+    for part in parts.iter().filter(|p| p.is_noun) {
+        match spellings.iter_mut().find(|(noun, _)| *noun == part.text) {
+            Some((_, count)) => *count += 1,
+            None => spellings.push((&part.text, 1)),
+        }
+    }
+
+    // Group spellings (each spelling joins the first group it matches)
+    let mut groups: Vec<Vec<(&str, usize)>> = Vec::new();
+    for spelling in spellings {
+        match groups
+            .iter_mut()
+            .find(|group| group.iter().any(|(noun, _)| same_entity(noun, spelling.0, glossary)))
+        {
+            Some(group) => group.push(spelling),
+            None => groups.push(vec![spelling]),
+        }
+    }
+
+    let replacements: Vec<(&str, &str)> = groups
+        .iter()
+        .filter(|group| group.len() > 1 || glossary.lookup(group[0].0).is_some())
+        .flat_map(|group| {
+            let canonical = canonical_spelling(group, glossary);
+            group
+                .iter()
+                .filter(move |(noun, _)| *noun != canonical)
+                .map(move |(noun, _)| (*noun, canonical))
+        })
+        .collect();
+
+    tokenize(input)
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| token.is_noun())
+        .filter_map(|(i, token)| {
+            let (noun, canonical) = replacements.iter().find(|(noun, _)| *noun == token.text)?;
+            let (message, suggestion, label) = match locale {
+                "en" => (
+                    format!("'{}' looks like another spelling of '{}'", noun, canonical),
+                    "Use one spelling for the same entity",
+                    format!("Replace with '{}'", canonical),
+                ),
+                "fr" => (
+                    format!("'{}' semble être une autre orthographe de '{}'", noun, canonical),
+                    "Utilisez une seule orthographe pour la même entité",
+                    format!("Remplacer par '{}'", canonical),
+                ),
+                _ => (
+                    format!("「{}」は「{}」の表記ゆれの可能性があります", noun, canonical),
+                    "同じ対象には同じ表記を使ってください",
+                    format!("「{}」に置換", canonical),
+                ),
+            };
+            Some(ValidationError::with_autofix(
+                ValidationErrorCode::InconsistentNoun,
+                message,
+                i,
+                Severity::Warning,
+                Some(suggestion.to_string()),
+                AutoFixAction {
+                    action_type: AutoFixActionType::Replace,
+                    block_type: "promps_noun".to_string(),
+                    target_position: i,
+                    label,
+                    replacement: Some(canonical.to_string()),
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use promps::GlossaryEntry;
+
+    fn replacements(errors: &[ValidationError]) -> Vec<(usize, String)> {
+        errors
+            .iter()
+            .map(|e| (e.position, e.autofix.as_ref().unwrap().replacement.clone().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_normalize_noun() {
+        assert_eq!(normalize_noun("ユーザー"), "ユーザ");
+        assert_eq!(normalize_noun("ﾕｰｻﾞｰ"), "ユーザ");
+        assert_eq!(normalize_noun("Ｏｒｄｅｒｓ"), "order");
+        assert_eq!(normalize_noun("Orders"), "order");
+        assert_eq!(normalize_noun("Entries"), "entry");
+        assert_eq!(normalize_noun("Boxes"), "box");
+        assert_eq!(normalize_noun("Status"), "status");
+        assert_eq!(normalize_noun("Address"), "address");
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("customer", "custmer"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_long_vowel_variants() {
+        let errors = validate_noun_consistency("_N:ユーザー が _N:ユーザー と _N:ユーザ に 送って", &Glossary::default(), "ja");
+
+        assert_eq!(replacements(&errors), vec![(4, "ユーザー".to_string())]);
+        assert_eq!(errors[0].code, ValidationErrorCode::InconsistentNoun);
+        assert_eq!(errors[0].autofix.as_ref().unwrap().action_type, AutoFixActionType::Replace);
+    }
+
+    #[test]
+    fn test_plural_and_typo_variants() {
+        let errors = validate_noun_consistency(
+            "list the _N:Order and the _N:Orders of the _N:Customer and _N:Custmer",
+            &Glossary::default(),
+            "en",
+        );
+
+        assert_eq!(replacements(&errors), vec![(5, "Order".to_string()), (10, "Customer".to_string())]);
+    }
+
+    #[test]
+    fn test_short_nouns_not_fuzzy_matched() {
+        let errors = validate_noun_consistency("_N:Cat と _N:Car と _N:顧客 と _N:顧問", &Glossary::default(), "ja");
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_different_words_one_letter_apart_not_grouped() {
+        let errors = validate_noun_consistency(
+            "compare the _N:Table and the _N:Cable , the _N:Model and the _N:Modal , the _N:Sender and the _N:Render",
+            &Glossary::default(),
+            "en",
+        );
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_glossary_aliases_and_canonical_term() {
+        let glossary = Glossary {
+            entries: vec![GlossaryEntry {
+                term: "データベース".to_string(),
+                definition: "PostgreSQL".to_string(),
+                aliases: vec!["DB".to_string()],
+            }],
+            strict: false,
+        };
+        let errors = validate_noun_consistency("_N:DB を _N:DB に 移行して", &glossary, "ja");
+
+        assert_eq!(replacements(&errors), vec![(0, "データベース".to_string()), (2, "データベース".to_string())]);
+    }
+}
@@ -0,0 +1,667 @@
+/// Promps - Pattern Mini-language Module
+///
+/// This module parses `PatternTemplate.structure` strings such as
+/// `N が N を V`, `please V [Article] N`, `(N と)+ N を V` or `N (に|へ) V`
+/// and compiles them into a small NFA that the pattern matcher aligns
+/// against the input with edit distance.
+///
+/// # Grammar
+/// ```text
+/// sequence := item*
+/// item     := primary '+'?
+/// primary  := atom | '[' sequence ']' | '(' sequence ('|' sequence)* ')'
+/// atom     := N | V | P | Art | Prep | Adj | Adv | Neg | literal
+/// ```
+/// `[…]` is optional, `(…)+` repeats one or more times, `(a|b)` is an alternative.
+
+use serde::{Deserialize, Serialize};
+
+use super::validation::TokenType;
+
+// ============================================================================
+// Syntax Tree
+// ============================================================================
+
+/// A single slot in a pattern
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind", content = "text")]
+pub enum PatternAtom {
+    /// Any noun (N, 名詞, Noun, Nom)
+    Noun,
+    /// Any verb (V, 動詞, Verb, Verbe)
+    Verb,
+    /// Any particle (P, 助詞, Particle)
+    Particle,
+    /// Any article (Art, Article, 冠詞)
+    Article,
+    /// Any preposition (Prep, Prép, 前置詞)
+    Preposition,
+    /// Any adjective (Adj, 形容詞, Adjective, Adjectif)
+    Adjective,
+    /// Any adverb (Adv, 副詞, Adverb, Adverbe)
+    Adverb,
+    /// Any negation word (Neg, 否定, Negation, Négation)
+    Negation,
+    /// Any conjunction (Conj, 接続詞, Conjunction, Conjonction)
+    Conjunction,
+    /// Exact token (particles, fixed words)
+    Literal(String),
+}
+
+impl PatternAtom {
+    /// Parse a word of the structure string into an atom
+    pub fn from_word(word: &str) -> Self {
+        match word {
+            "N" | "名詞" | "Noun" | "Nom" => PatternAtom::Noun,
+            "V" | "動詞" | "Verb" | "Verbe" => PatternAtom::Verb,
+            "P" | "助詞" | "Particle" => PatternAtom::Particle,
+            "Art" | "Article" | "冠詞" => PatternAtom::Article,
+            "Prep" | "Prép" | "前置詞" => PatternAtom::Preposition,
+            "Adj" | "形容詞" | "Adjective" | "Adjectif" => PatternAtom::Adjective,
+            "Adv" | "副詞" | "Adverb" | "Adverbe" => PatternAtom::Adverb,
+            "Neg" | "否定" | "Negation" | "Négation" => PatternAtom::Negation,
+            "Conj" | "接続詞" | "Conjunction" | "Conjonction" => PatternAtom::Conjunction,
+            _ => PatternAtom::Literal(word.to_string()),
+        }
+    }
+
+    /// Display text used as the slot label
+    pub fn label(&self) -> String {
+        match self {
+            PatternAtom::Noun => "N".to_string(),
+            PatternAtom::Verb => "V".to_string(),
+            PatternAtom::Particle => "P".to_string(),
+            PatternAtom::Article => "Art".to_string(),
+            PatternAtom::Preposition => "Prep".to_string(),
+            PatternAtom::Adjective => "Adj".to_string(),
+            PatternAtom::Adverb => "Adv".to_string(),
+            PatternAtom::Negation => "Neg".to_string(),
+            PatternAtom::Conjunction => "Conj".to_string(),
+            PatternAtom::Literal(text) => text.clone(),
+        }
+    }
+
+    /// Check if a token satisfies this atom
+    pub fn matches(&self, token: &str, locale: &str) -> bool {
+        let token_type = match locale {
+            "en" => TokenType::classify_en(token),
+            "fr" => TokenType::classify_fr(token),
+            _ => TokenType::classify(token),
+        };
+
+        match self {
+            PatternAtom::Noun => token_type == TokenType::Noun,
+            PatternAtom::Verb => token_type == TokenType::Verb,
+            PatternAtom::Particle => token_type == TokenType::Particle,
+            PatternAtom::Article => token_type == TokenType::Article,
+            PatternAtom::Preposition => token_type == TokenType::Preposition,
+            PatternAtom::Adjective => token_type == TokenType::Adjective,
+            PatternAtom::Adverb => token_type == TokenType::Adverb,
+            PatternAtom::Negation => token_type == TokenType::Negation,
+            PatternAtom::Conjunction => token_type == TokenType::Conjunction,
+            PatternAtom::Literal(text) => {
+                // English/French words match case-insensitively
+                if locale == "en" || locale == "fr" {
+                    token.to_lowercase() == text.to_lowercase()
+                } else {
+                    token == text
+                }
+            }
+        }
+    }
+}
+
+/// Pattern expression tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type", content = "value")]
+pub enum PatternExpr {
+    /// A single slot
+    Atom(PatternAtom),
+    /// Items in order
+    Sequence(Vec<PatternExpr>),
+    /// One of the alternatives
+    Alternative(Vec<PatternExpr>),
+    /// Zero or one occurrence
+    Optional(Box<PatternExpr>),
+    /// One or more occurrences
+    Repeat(Box<PatternExpr>),
+}
+
+impl PatternExpr {
+    /// Number of atoms in the expression (each occurrence counted once)
+    pub fn atom_count(&self) -> usize {
+        match self {
+            PatternExpr::Atom(_) => 1,
+            PatternExpr::Sequence(items) | PatternExpr::Alternative(items) => {
+                items.iter().map(|e| e.atom_count()).sum()
+            }
+            PatternExpr::Optional(inner) | PatternExpr::Repeat(inner) => inner.atom_count(),
+        }
+    }
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+/// Check if a structure string uses mini-language operators
+pub fn has_pattern_syntax(structure: &str) -> bool {
+    structure.chars().any(|c| matches!(c, '[' | ']' | '(' | ')' | '|' | '+'))
+}
+
+/// Split a structure string into words and operator symbols
+fn lex(structure: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    for c in structure.chars() {
+        if c.is_whitespace() || matches!(c, '[' | ']' | '(' | ')' | '|' | '+') {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Parse a structure string into a pattern expression
+///
+/// # Arguments
+/// * `structure` - Pattern structure (e.g., "N が N を V", "please V [Art] N")
+///
+/// # Returns
+/// The parsed expression, or an error message for malformed input
+pub fn parse_pattern(structure: &str) -> Result<PatternExpr, String> {
+    let tokens = lex(structure);
+    let mut pos = 0;
+    let expr = parse_sequence(&tokens, &mut pos)?;
+
+    if pos < tokens.len() {
+        return Err(format!("Unexpected '{}' at position {}", tokens[pos], pos + 1));
+    }
+    if expr.atom_count() == 0 {
+        return Err("Pattern is empty".to_string());
+    }
+
+    Ok(expr)
+}
+
+fn parse_sequence(tokens: &[String], pos: &mut usize) -> Result<PatternExpr, String> {
+    let mut items = Vec::new();
+
+    while *pos < tokens.len() {
+        let primary = match tokens[*pos].as_str() {
+            "]" | ")" | "|" => break,
+            "+" => return Err(format!("'+' without a group at position {}", *pos + 1)),
+            "[" => {
+                *pos += 1;
+                let inner = parse_sequence(tokens, pos)?;
+                expect(tokens, pos, "]")?;
+                PatternExpr::Optional(Box::new(inner))
+            }
+            "(" => {
+                *pos += 1;
+                let mut alternatives = vec![parse_sequence(tokens, pos)?];
+                while tokens.get(*pos).map(|t| t == "|").unwrap_or(false) {
+                    *pos += 1;
+                    alternatives.push(parse_sequence(tokens, pos)?);
+                }
+                expect(tokens, pos, ")")?;
+                if alternatives.len() == 1 {
+                    alternatives.pop().unwrap()
+                } else {
+                    PatternExpr::Alternative(alternatives)
+                }
+            }
+            word => {
+                *pos += 1;
+                PatternExpr::Atom(PatternAtom::from_word(word))
+            }
+        };
+
+        if tokens.get(*pos).map(|t| t == "+").unwrap_or(false) {
+            *pos += 1;
+            items.push(PatternExpr::Repeat(Box::new(primary)));
+        } else {
+            items.push(primary);
+        }
+    }
+
+    Ok(if items.len() == 1 {
+        items.pop().unwrap()
+    } else {
+        PatternExpr::Sequence(items)
+    })
+}
+
+fn expect(tokens: &[String], pos: &mut usize, symbol: &str) -> Result<(), String> {
+    match tokens.get(*pos) {
+        Some(t) if t == symbol => {
+            *pos += 1;
+            Ok(())
+        }
+        Some(t) => Err(format!("Expected '{}' but found '{}' at position {}", symbol, t, *pos + 1)),
+        None => Err(format!("Missing '{}' at end of pattern", symbol)),
+    }
+}
+
+// ============================================================================
+// NFA Compilation
+// ============================================================================
+
+/// Transition of the compiled pattern
+#[derive(Debug, Clone)]
+enum Edge {
+    /// Move without consuming a token
+    Epsilon(usize),
+    /// Consume a token for a slot: (target state, slot index)
+    Slot(usize, usize),
+}
+
+/// A pattern compiled into an NFA
+///
+/// Slots are numbered in order of appearance in the structure string.
+#[derive(Debug, Clone)]
+pub struct CompiledPattern {
+    edges: Vec<Vec<Edge>>,
+    start: usize,
+    accept: usize,
+    /// Atom of each slot
+    pub slots: Vec<PatternAtom>,
+}
+
+impl CompiledPattern {
+    /// Compile a pattern expression
+    pub fn compile(expr: &PatternExpr) -> Self {
+        let mut pattern = CompiledPattern {
+            edges: Vec::new(),
+            start: 0,
+            accept: 0,
+            slots: Vec::new(),
+        };
+        pattern.start = pattern.new_state();
+        pattern.accept = pattern.build(expr, pattern.start);
+        pattern
+    }
+
+    fn new_state(&mut self) -> usize {
+        self.edges.push(Vec::new());
+        self.edges.len() - 1
+    }
+
+    /// Build the expression starting at `from`; returns the exit state
+    fn build(&mut self, expr: &PatternExpr, from: usize) -> usize {
+        match expr {
+            PatternExpr::Atom(atom) => {
+                let slot = self.slots.len();
+                self.slots.push(atom.clone());
+                let to = self.new_state();
+                self.edges[from].push(Edge::Slot(to, slot));
+                to
+            }
+            PatternExpr::Sequence(items) => items.iter().fold(from, |state, item| self.build(item, state)),
+            PatternExpr::Alternative(items) => {
+                let exit = self.new_state();
+                for item in items {
+                    let entry = self.new_state();
+                    self.edges[from].push(Edge::Epsilon(entry));
+                    let end = self.build(item, entry);
+                    self.edges[end].push(Edge::Epsilon(exit));
+                }
+                exit
+            }
+            PatternExpr::Optional(inner) => {
+                let end = self.build(inner, from);
+                self.edges[from].push(Edge::Epsilon(end));
+                end
+            }
+            PatternExpr::Repeat(inner) => {
+                let entry = self.new_state();
+                self.edges[from].push(Edge::Epsilon(entry));
+                let end = self.build(inner, entry);
+                let exit = self.new_state();
+                self.edges[end].push(Edge::Epsilon(entry));
+                self.edges[end].push(Edge::Epsilon(exit));
+                exit
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Alignment
+// ============================================================================
+
+/// Edit operation of a pattern alignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignOp {
+    /// Token matches the slot
+    Match,
+    /// Token is in the slot's place but does not match it
+    Substitute,
+    /// Slot has no token
+    Missing,
+    /// Token has no slot (extra token inside the match)
+    Inserted,
+}
+
+/// One step of an alignment: (operation, slot index, token index)
+pub type AlignStep = (AlignOp, Option<usize>, Option<usize>);
+
+/// Alignment of a compiled pattern against a token sequence
+#[derive(Debug, Clone)]
+pub struct Alignment {
+    /// Steps in token order
+    pub ops: Vec<AlignStep>,
+    /// Number of matched slots
+    pub matches: usize,
+    /// Number of extra tokens inside the match
+    pub inserted: usize,
+}
+
+impl Alignment {
+    /// Number of slots on the aligned path (matched, substituted or missing)
+    pub fn slot_count(&self) -> usize {
+        self.ops.iter().filter(|(_, slot, _)| slot.is_some()).count()
+    }
+
+    /// Graded score: matched slots over path slots plus extra tokens
+    pub fn score(&self) -> f64 {
+        let denominator = self.slot_count() + self.inserted;
+        if self.matches == 0 || denominator == 0 {
+            0.0
+        } else {
+            self.matches as f64 / denominator as f64
+        }
+    }
+
+    /// All slots matched with no extra tokens
+    pub fn is_complete(&self) -> bool {
+        self.matches > 0 && self.matches == self.slot_count() && self.inserted == 0
+    }
+}
+
+/// How a DP cell was reached
+#[derive(Debug, Clone, Copy)]
+enum Back {
+    Start,
+    Epsilon(usize),
+    Missing(usize, usize),
+    Match(usize, usize),
+    Substitute(usize, usize),
+    Inserted(usize),
+}
+
+/// Cell cost: (edit cost, -matches, substitutions) - lower is better
+type Cost = (usize, isize, usize);
+
+/// Align a compiled pattern against tokens with edit distance
+///
+/// Leading and trailing tokens outside the match are free, so the pattern
+/// can be found anywhere in the sequence. Substitution, missing slot and
+/// inserted token each cost 1; ties prefer more matched slots.
+pub fn align(pattern: &CompiledPattern, tokens: &[&str], locale: &str) -> Alignment {
+    let n = tokens.len();
+    let states = pattern.edges.len();
+
+    let mut cost: Vec<Vec<Option<Cost>>> = vec![vec![None; states]; n + 1];
+    let mut back = vec![vec![Back::Start; states]; n + 1];
+
+    fn improve(cell: &mut Option<Cost>, candidate: Cost, or_equal: bool) -> bool {
+        match cell {
+            None => {
+                *cell = Some(candidate);
+                true
+            }
+            Some(current) if candidate < *current || (or_equal && candidate == *current) => {
+                *cell = Some(candidate);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    for i in 0..=n {
+        // Free leading tokens: the match may start here
+        if improve(&mut cost[i][pattern.start], (0, 0, 0), false) {
+            back[i][pattern.start] = Back::Start;
+        }
+
+        // Relax non-consuming edges (epsilon and missing slots) until stable
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for state in 0..states {
+                let Some((c, k, s)) = cost[i][state] else { continue };
+                for edge in &pattern.edges[state] {
+                    let (to, candidate, step) = match *edge {
+                        Edge::Epsilon(to) => (to, (c, k, s), Back::Epsilon(state)),
+                        Edge::Slot(to, slot) => (to, (c + 1, k, s), Back::Missing(state, slot)),
+                    };
+                    if improve(&mut cost[i][to], candidate, false) {
+                        back[i][to] = step;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        if i == n {
+            break;
+        }
+
+        // Consume token i
+        for state in 0..states {
+            let Some((c, k, s)) = cost[i][state] else { continue };
+            for edge in &pattern.edges[state] {
+                if let Edge::Slot(to, slot) = *edge {
+                    if pattern.slots[slot].matches(tokens[i], locale) {
+                        // On ties prefer matching, which keeps matched slots contiguous
+                        if improve(&mut cost[i + 1][to], (c, k - 1, s), true) {
+                            back[i + 1][to] = Back::Match(state, slot);
+                        }
+                    } else if improve(&mut cost[i + 1][to], (c + 1, k, s + 1), false) {
+                        back[i + 1][to] = Back::Substitute(state, slot);
+                    }
+                }
+            }
+            // Extra token inside the match
+            if state != pattern.start && improve(&mut cost[i + 1][state], (c + 1, k, s), false) {
+                back[i + 1][state] = Back::Inserted(state);
+            }
+        }
+    }
+
+    // Free trailing tokens: pick the best end position
+    let end = (0..=n)
+        .filter(|&i| cost[i][pattern.accept].is_some())
+        .min_by_key(|&i| cost[i][pattern.accept])
+        .unwrap_or(0);
+
+    // Trace back the operations
+    let mut ops = Vec::new();
+    let (mut i, mut state) = (end, pattern.accept);
+    loop {
+        match back[i][state] {
+            Back::Start => break,
+            Back::Epsilon(prev) => state = prev,
+            Back::Missing(prev, slot) => {
+                ops.push((AlignOp::Missing, Some(slot), None));
+                state = prev;
+            }
+            Back::Match(prev, slot) => {
+                ops.push((AlignOp::Match, Some(slot), Some(i - 1)));
+                state = prev;
+                i -= 1;
+            }
+            Back::Substitute(prev, slot) => {
+                ops.push((AlignOp::Substitute, Some(slot), Some(i - 1)));
+                state = prev;
+                i -= 1;
+            }
+            Back::Inserted(prev) => {
+                ops.push((AlignOp::Inserted, None, Some(i - 1)));
+                state = prev;
+                i -= 1;
+            }
+        }
+    }
+    ops.reverse();
+
+    let matches = ops.iter().filter(|(op, _, _)| *op == AlignOp::Match).count();
+    let inserted = ops.iter().filter(|(op, _, _)| *op == AlignOp::Inserted).count();
+
+    Alignment { ops, matches, inserted }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn align_str(structure: &str, input: &str, locale: &str) -> Alignment {
+        let pattern = CompiledPattern::compile(&parse_pattern(structure).unwrap());
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+        align(&pattern, &tokens, locale)
+    }
+
+    #[test]
+    fn test_parse_simple_sequence() {
+        let expr = parse_pattern("N が N を V").unwrap();
+        assert_eq!(expr.atom_count(), 5);
+        assert!(matches!(expr, PatternExpr::Sequence(ref items) if items.len() == 5));
+    }
+
+    #[test]
+    fn test_parse_class_words() {
+        assert_eq!(parse_pattern("名詞").unwrap(), PatternExpr::Atom(PatternAtom::Noun));
+        assert_eq!(parse_pattern("Verbe").unwrap(), PatternExpr::Atom(PatternAtom::Verb));
+        assert_eq!(parse_pattern("Article").unwrap(), PatternExpr::Atom(PatternAtom::Article));
+        assert_eq!(
+            parse_pattern("について").unwrap(),
+            PatternExpr::Atom(PatternAtom::Literal("について".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_optional_repeat_alternative() {
+        let expr = parse_pattern("(N と)+ N (に|へ) [N を] V").unwrap();
+        match expr {
+            PatternExpr::Sequence(items) => {
+                assert!(matches!(items[0], PatternExpr::Repeat(_)));
+                assert!(matches!(items[2], PatternExpr::Alternative(_)));
+                assert!(matches!(items[3], PatternExpr::Optional(_)));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_pattern("N [が V").is_err());
+        assert!(parse_pattern("N が )").is_err());
+        assert!(parse_pattern("+ N").is_err());
+        assert!(parse_pattern("").is_err());
+        assert!(parse_pattern("[ ]").is_err());
+    }
+
+    #[test]
+    fn test_has_pattern_syntax() {
+        assert!(has_pattern_syntax("please V [Art] N"));
+        assert!(!has_pattern_syntax("名詞 が 名詞 を 動詞"));
+    }
+
+    #[test]
+    fn test_align_exact_sequence() {
+        let alignment = align_str("N を V", "_N:Doc を 分析して", "ja");
+        assert!(alignment.is_complete());
+        assert_eq!(alignment.score(), 1.0);
+    }
+
+    #[test]
+    fn test_align_optional_present_and_absent() {
+        let with = align_str("please V [Art] N", "please analyze the _N:doc", "en");
+        let without = align_str("please V [Art] N", "please analyze _N:doc", "en");
+
+        assert!(with.is_complete());
+        assert!(without.is_complete());
+        assert_eq!(without.slot_count(), 3);
+    }
+
+    #[test]
+    fn test_align_optional_modifiers() {
+        assert_eq!(parse_pattern("形容詞").unwrap(), PatternExpr::Atom(PatternAtom::Adjective));
+        assert!(align_str("V [Art] [Adj] N", "summarize the detailed _N:report", "en").is_complete());
+        assert!(align_str("V [Art] [Adj] N", "summarize _ADJ:tiny _N:report", "en").is_complete());
+        assert!(align_str("N を [Adv] V", "_N:Doc を 詳しく 説明して", "ja").is_complete());
+        assert!(align_str("N を [Adv] V", "_N:Doc を 説明して", "ja").is_complete());
+    }
+
+    #[test]
+    fn test_align_negation() {
+        assert!(align_str("do Neg V [Art] N", "do not translate the _N:title", "en").is_complete());
+        assert!(align_str("ne pas V [Art] N", "ne pas traduire le _N:titre", "fr").is_complete());
+    }
+
+    #[test]
+    fn test_align_conjunction() {
+        assert!(align_str("V [Art] N Conj V", "read the _N:file then summarize", "en").is_complete());
+        assert!(align_str("N を V 、 Conj V", "_N:文書 を 読み込んで 、 そして 要約して", "ja").is_complete());
+    }
+
+    #[test]
+    fn test_align_repeat() {
+        let alignment = align_str("(N と)+ N を V", "_N:A と _N:B と _N:C を 保存して", "ja");
+        assert!(alignment.is_complete());
+        assert_eq!(alignment.matches, 7);
+    }
+
+    #[test]
+    fn test_align_alternative() {
+        assert!(align_str("N (に|へ) V", "_N:東京 へ 送信して", "ja").is_complete());
+        assert!(align_str("N (に|へ) V", "_N:東京 に 送信して", "ja").is_complete());
+        assert!(!align_str("N (に|へ) V", "_N:東京 で 送信して", "ja").is_complete());
+    }
+
+    #[test]
+    fn test_align_partial_reports_missing() {
+        let alignment = align_str("N が N を V", "_N:Doc を", "ja");
+        let missing: Vec<usize> = alignment
+            .ops
+            .iter()
+            .filter(|(op, _, _)| *op == AlignOp::Missing)
+            .filter_map(|(_, slot, _)| *slot)
+            .collect();
+
+        assert_eq!(missing, vec![0, 1, 4]);
+        assert_eq!(alignment.score(), 0.4);
+    }
+
+    #[test]
+    fn test_align_anywhere_with_insertion() {
+        let alignment = align_str("N を V", "まず _N:Doc を とても 分析して", "ja");
+        assert_eq!(alignment.matches, 3);
+        assert_eq!(alignment.inserted, 1);
+        assert_eq!(alignment.score(), 0.75);
+    }
+
+    #[test]
+    fn test_align_empty_input() {
+        let alignment = align_str("N を V", "", "ja");
+        assert_eq!(alignment.matches, 0);
+        assert_eq!(alignment.score(), 0.0);
+        assert_eq!(alignment.slot_count(), 3);
+    }
+}
@@ -0,0 +1,493 @@
+/// Promps - Pattern Template Loader Module
+///
+/// This module loads user-defined pattern templates from JSON/TOML files
+/// and merges them with the built-in templates.
+///
+/// Sources (later sources override earlier ones with the same id):
+/// 1. Built-in templates (`get_pattern_templates_by_locale`)
+/// 2. User config directory (`<config>/org.zundou.promps/patterns/*.json|*.toml`)
+/// 3. Templates embedded in the current project file
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::pattern_lang::{has_pattern_syntax, parse_pattern};
+use super::validation::{
+    get_pattern_templates_by_locale, is_known_block_type, PatternSource, PatternTemplate,
+};
+
+// ============================================================================
+// File Format
+// ============================================================================
+
+/// A pattern template file
+///
+/// JSON:
+/// ```json
+/// { "locale": "ja", "patterns": [ { "id": "...", "blocks": [ ... ] } ] }
+/// ```
+///
+/// TOML:
+/// ```toml
+/// locale = "ja"
+/// [[patterns]]
+/// id = "..."
+/// [[patterns.blocks]]
+/// blockType = "promps_noun"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternFile {
+    /// Locale the templates apply to (all locales when omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Templates defined in the file
+    #[serde(default)]
+    pub patterns: Vec<PatternTemplate>,
+}
+
+/// A problem found while loading pattern templates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternLoadError {
+    /// File the problem was found in (empty for project-embedded templates)
+    pub path: String,
+    /// Template id, if the problem is specific to one template
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern_id: Option<String>,
+    /// Human-readable message
+    pub message: String,
+}
+
+/// Merged templates together with load problems
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternLoadReport {
+    /// All valid templates (built-in, user, project)
+    pub patterns: Vec<PatternTemplate>,
+    /// Files and templates that were skipped
+    pub errors: Vec<PatternLoadError>,
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+/// Get the user pattern directory
+///
+/// Follows the platform config directory used by Tauri's `app_config_dir`:
+/// - Windows: `%APPDATA%\org.zundou.promps\patterns`
+/// - macOS: `~/Library/Application Support/org.zundou.promps/patterns`
+/// - Linux: `$XDG_CONFIG_HOME/org.zundou.promps/patterns` (or `~/.config/...`)
+pub fn user_pattern_dir() -> Option<PathBuf> {
+    const APP_DIR: &str = "org.zundou.promps";
+
+    let config_dir = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+
+    config_dir.map(|dir| dir.join(APP_DIR).join("patterns"))
+}
+
+/// Parse a pattern file from its contents
+///
+/// # Arguments
+/// * `contents` - File contents
+/// * `is_toml` - Parse as TOML instead of JSON
+pub fn parse_pattern_file(contents: &str, is_toml: bool) -> Result<PatternFile, String> {
+    if is_toml {
+        toml::from_str(contents).map_err(|e| format!("Failed to parse TOML: {}", e))
+    } else {
+        serde_json::from_str(contents).map_err(|e| format!("Failed to parse JSON: {}", e))
+    }
+}
+
+/// Validate a single template
+///
+/// # Returns
+/// List of problems (empty if the template is valid)
+pub fn validate_pattern_template(pattern: &PatternTemplate) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if pattern.id.trim().is_empty() {
+        problems.push("Pattern id is empty".to_string());
+    }
+    if pattern.blocks.is_empty() {
+        problems.push("Pattern has no blocks".to_string());
+    }
+    for (i, block) in pattern.blocks.iter().enumerate() {
+        if !is_known_block_type(&block.block_type) {
+            problems.push(format!("Block {}: unknown block type '{}'", i + 1, block.block_type));
+        }
+    }
+    if has_pattern_syntax(&pattern.structure) {
+        if let Err(e) = parse_pattern(&pattern.structure) {
+            problems.push(format!("Invalid structure: {}", e));
+        }
+    }
+
+    problems
+}
+
+/// Load all pattern files from a directory
+///
+/// Files are read in name order; only `.json` and `.toml` files are considered.
+///
+/// # Returns
+/// (templates for the locale, problems found)
+pub fn load_pattern_dir(
+    dir: &Path,
+    locale: &str,
+    source: PatternSource,
+) -> (Vec<PatternTemplate>, Vec<PatternLoadError>) {
+    let mut patterns = Vec::new();
+    let mut errors = Vec::new();
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        // A missing directory simply means no user templates
+        Err(_) => return (patterns, errors),
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            matches!(
+                p.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref(),
+                Some("json") | Some("toml")
+            )
+        })
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let path_str = path.to_string_lossy().to_string();
+        let is_toml = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("toml"))
+            .unwrap_or(false);
+
+        let file = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read file: {}", e))
+            .and_then(|contents| parse_pattern_file(&contents, is_toml));
+
+        match file {
+            Ok(file) => {
+                if file.locale.as_deref().map(|l| l != locale).unwrap_or(false) {
+                    continue;
+                }
+                let (valid, mut problems) = validate_templates(file.patterns, source, &path_str);
+                patterns.extend(valid);
+                errors.append(&mut problems);
+            }
+            Err(message) => errors.push(PatternLoadError {
+                path: path_str,
+                pattern_id: None,
+                message,
+            }),
+        }
+    }
+
+    (patterns, errors)
+}
+
+/// Keep valid templates and tag them with their source
+fn validate_templates(
+    templates: Vec<PatternTemplate>,
+    source: PatternSource,
+    path: &str,
+) -> (Vec<PatternTemplate>, Vec<PatternLoadError>) {
+    let mut valid = Vec::new();
+    let mut errors = Vec::new();
+
+    for mut pattern in templates {
+        let problems = validate_pattern_template(&pattern);
+        if problems.is_empty() {
+            pattern.source = source;
+            valid.push(pattern);
+        } else {
+            for message in problems {
+                errors.push(PatternLoadError {
+                    path: path.to_string(),
+                    pattern_id: Some(pattern.id.clone()),
+                    message,
+                });
+            }
+        }
+    }
+
+    (valid, errors)
+}
+
+/// Merge templates; a later template replaces an earlier one with the same id
+fn merge_templates(base: &mut Vec<PatternTemplate>, overrides: Vec<PatternTemplate>) {
+    for pattern in overrides {
+        match base.iter_mut().find(|p| p.id == pattern.id) {
+            Some(existing) => *existing = pattern,
+            None => base.push(pattern),
+        }
+    }
+}
+
+/// Load built-in, user and project templates for a locale
+///
+/// # Arguments
+/// * `locale` - Locale code ("ja", "en", "fr")
+/// * `user_dir` - User pattern directory (skipped when None)
+/// * `project_patterns` - Templates embedded in the current project (those
+///   with another `locale` are skipped)
+///
+/// # Returns
+/// PatternLoadReport with merged templates and load problems
+pub fn load_pattern_templates(
+    locale: &str,
+    user_dir: Option<&Path>,
+    project_patterns: &[PatternTemplate],
+) -> PatternLoadReport {
+    let mut patterns = get_pattern_templates_by_locale(locale);
+    let mut errors = Vec::new();
+
+    if let Some(dir) = user_dir {
+        let (user, mut problems) = load_pattern_dir(dir, locale, PatternSource::User);
+        merge_templates(&mut patterns, user);
+        errors.append(&mut problems);
+    }
+
+    let project_patterns: Vec<PatternTemplate> = project_patterns
+        .iter()
+        .filter(|p| p.locale.as_deref().is_none_or(|l| l == locale))
+        .cloned()
+        .collect();
+    let (project, mut problems) =
+        validate_templates(project_patterns, PatternSource::Project, "");
+    merge_templates(&mut patterns, project);
+    errors.append(&mut problems);
+
+    PatternLoadReport { patterns, errors }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::validation::PatternBlock;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("promps_test_patterns_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const JSON_FILE: &str = r#"{
+        "locale": "ja",
+        "patterns": [{
+            "id": "ask_about",
+            "name": "質問文型",
+            "description": "「〇〇について教えて」",
+            "structure": "名詞 について 動詞",
+            "example": "データ について 教えて",
+            "blocks": [
+                { "blockType": "promps_noun", "label": "トピック", "isPlaceholder": true },
+                { "blockType": "promps_other", "label": "について", "defaultValue": "について" },
+                { "blockType": "promps_verb_teach", "label": "動詞" }
+            ]
+        }]
+    }"#;
+
+    const TOML_FILE: &str = r#"
+locale = "en"
+
+[[patterns]]
+id = "svo_basic"
+name = "Custom Command"
+description = "Overrides the built-in simple command"
+structure = "Verb Noun"
+example = "explain code"
+
+[[patterns.blocks]]
+blockType = "promps_verb_explain"
+label = "Action"
+isPlaceholder = true
+
+[[patterns.blocks]]
+blockType = "promps_noun"
+label = "Object"
+isPlaceholder = true
+"#;
+
+    #[test]
+    fn test_parse_pattern_file_json() {
+        let file = parse_pattern_file(JSON_FILE, false).unwrap();
+        assert_eq!(file.locale.as_deref(), Some("ja"));
+        assert_eq!(file.patterns.len(), 1);
+        assert_eq!(file.patterns[0].blocks.len(), 3);
+        assert!(file.patterns[0].blocks[0].is_placeholder);
+        assert!(!file.patterns[0].blocks[1].is_placeholder);
+    }
+
+    #[test]
+    fn test_parse_pattern_file_toml() {
+        let file = parse_pattern_file(TOML_FILE, true).unwrap();
+        assert_eq!(file.locale.as_deref(), Some("en"));
+        assert_eq!(file.patterns[0].id, "svo_basic");
+        assert_eq!(file.patterns[0].blocks[0].block_type, "promps_verb_explain");
+    }
+
+    #[test]
+    fn test_parse_pattern_file_invalid() {
+        assert!(parse_pattern_file("{ not json", false).is_err());
+        assert!(parse_pattern_file("patterns = [", true).is_err());
+    }
+
+    #[test]
+    fn test_validate_unknown_block_type() {
+        let pattern = PatternTemplate::new(
+            "bad", "Bad", "", "", "",
+            vec![PatternBlock::placeholder("promps_unknown", "X")],
+        );
+        let problems = validate_pattern_template(&pattern);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("promps_unknown"));
+    }
+
+    #[test]
+    fn test_validate_empty_blocks() {
+        let pattern = PatternTemplate::new("empty", "Empty", "", "", "", vec![]);
+        let problems = validate_pattern_template(&pattern);
+        assert!(problems.iter().any(|p| p.contains("no blocks")));
+    }
+
+    #[test]
+    fn test_validate_malformed_structure() {
+        let pattern = PatternTemplate::new(
+            "broken", "Broken", "", "(N と N を V", "",
+            vec![PatternBlock::placeholder("promps_noun", "X")],
+        );
+        let problems = validate_pattern_template(&pattern);
+        assert!(problems.iter().any(|p| p.starts_with("Invalid structure")));
+    }
+
+    #[test]
+    fn test_load_user_templates_merged_with_builtin() {
+        let dir = temp_dir("user_ja");
+        fs::write(dir.join("custom.json"), JSON_FILE).unwrap();
+
+        let report = load_pattern_templates("ja", Some(&dir), &[]);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(report.errors.is_empty());
+        assert_eq!(report.patterns.len(), 8);
+        let custom = report.patterns.iter().find(|p| p.id == "ask_about").unwrap();
+        assert_eq!(custom.source, PatternSource::User);
+        assert_eq!(report.patterns[0].source, PatternSource::Builtin);
+    }
+
+    #[test]
+    fn test_load_user_templates_other_locale_skipped() {
+        let dir = temp_dir("user_locale");
+        fs::write(dir.join("custom.json"), JSON_FILE).unwrap();
+
+        let report = load_pattern_templates("en", Some(&dir), &[]);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(report.patterns.len(), 7);
+    }
+
+    #[test]
+    fn test_load_user_toml_overrides_builtin() {
+        let dir = temp_dir("user_toml");
+        fs::write(dir.join("override.toml"), TOML_FILE).unwrap();
+
+        let report = load_pattern_templates("en", Some(&dir), &[]);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(report.patterns.len(), 7);
+        let svo = report.patterns.iter().find(|p| p.id == "svo_basic").unwrap();
+        assert_eq!(svo.name, "Custom Command");
+        assert_eq!(svo.source, PatternSource::User);
+    }
+
+    #[test]
+    fn test_load_reports_invalid_file_and_template() {
+        let dir = temp_dir("user_invalid");
+        fs::write(dir.join("broken.json"), "{").unwrap();
+        fs::write(
+            dir.join("unknown.json"),
+            r#"{ "patterns": [{ "id": "x", "name": "", "description": "", "structure": "",
+                "example": "", "blocks": [{ "blockType": "promps_bogus", "label": "?" }] }] }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let report = load_pattern_templates("ja", Some(&dir), &[]);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(report.patterns.len(), 7);
+        assert_eq!(report.errors.len(), 2);
+        assert!(report.errors.iter().any(|e| e.pattern_id.as_deref() == Some("x")));
+    }
+
+    #[test]
+    fn test_load_missing_user_dir() {
+        let report = load_pattern_templates("ja", Some(Path::new("/nonexistent/promps/patterns")), &[]);
+        assert!(report.errors.is_empty());
+        assert_eq!(report.patterns.len(), 7);
+    }
+
+    #[test]
+    fn test_project_templates_override_user() {
+        let dir = temp_dir("user_project");
+        fs::write(dir.join("custom.json"), JSON_FILE).unwrap();
+        let project = vec![PatternTemplate::new(
+            "ask_about", "Project Ask", "", "", "",
+            vec![PatternBlock::placeholder("promps_noun", "Topic")],
+        )];
+
+        let report = load_pattern_templates("ja", Some(&dir), &project);
+        let _ = fs::remove_dir_all(&dir);
+
+        let ask = report.patterns.iter().find(|p| p.id == "ask_about").unwrap();
+        assert_eq!(ask.name, "Project Ask");
+        assert_eq!(ask.source, PatternSource::Project);
+    }
+
+    #[test]
+    fn test_project_templates_filtered_by_locale() {
+        let mut ja_only = PatternTemplate::new(
+            "ja_only", "日本語のみ", "", "", "",
+            vec![PatternBlock::placeholder("promps_noun", "目的語")],
+        );
+        ja_only.locale = Some("ja".to_string());
+        let any = PatternTemplate::new(
+            "any_locale", "Any", "", "", "",
+            vec![PatternBlock::placeholder("promps_noun", "Object")],
+        );
+        let project = vec![ja_only, any];
+
+        let ja = load_pattern_templates("ja", None, &project);
+        let en = load_pattern_templates("en", None, &project);
+
+        assert!(ja.patterns.iter().any(|p| p.id == "ja_only"));
+        assert!(!en.patterns.iter().any(|p| p.id == "ja_only"));
+        assert!(en.patterns.iter().any(|p| p.id == "any_locale"));
+    }
+
+    #[test]
+    fn test_pattern_source_serialization() {
+        let report = load_pattern_templates("ja", None, &[]);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"source\":\"builtin\""));
+    }
+}
@@ -0,0 +1,387 @@
+/// Promps Project Inclusion
+///
+/// Resolves `_INCLUDE:path.promps` tokens (and the project-level includes
+/// list, which is emitted as leading `_INCLUDE:` tokens) by loading the
+/// included project, generating its prompt with its own locale and
+/// variables, and inlining it at the include position.
+///
+/// Includes are resolved recursively. Paths are relative to the including
+/// project, but must stay inside the directory of the top-level project;
+/// projects that include each other are reported as a cycle.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use promps::tokenizer::tokenize;
+use promps::{evaluate_conditions, generate_prompt_with_locale, parse_input, PartKind, PromptPart};
+
+use super::file_include::resolve_file_parts_within;
+use super::validation::{Severity, ValidationError, ValidationErrorCode};
+
+/// Included project, as needed to generate its prompt
+#[derive(Debug, Clone, Default)]
+pub struct IncludedProject {
+    /// DSL of the project (with its own `_INCLUDE:` tokens)
+    pub dsl: String,
+    /// Locale the project was written in (None = locale of the includer)
+    pub locale: Option<String>,
+    /// Variables for its `_IF:` sections
+    pub variables: HashMap<String, bool>,
+}
+
+/// Loads an included project (path is already resolved; the locale is the
+/// includer's, used for a project without its own)
+pub type ProjectLoader<'a> = dyn Fn(&Path, &str) -> Result<IncludedProject, String> + 'a;
+
+/// Reason an included project could not be inlined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncludeError {
+    /// The including project has not been saved yet
+    NoBaseDirectory,
+    /// Included project does not exist
+    NotFound,
+    /// Included project resolves outside the project directory
+    OutsideProject,
+    /// Included projects form a cycle (file names, first = last)
+    Cycle { chain: Vec<String> },
+    /// Included project could not be loaded
+    Invalid(String),
+}
+
+impl IncludeError {
+    /// Validation error code for this error
+    pub fn code(&self) -> ValidationErrorCode {
+        match self {
+            IncludeError::NoBaseDirectory
+            | IncludeError::NotFound
+            | IncludeError::Invalid(_) => ValidationErrorCode::IncludeNotFound,
+            IncludeError::OutsideProject => ValidationErrorCode::IncludeOutsideProject,
+            IncludeError::Cycle { .. } => ValidationErrorCode::IncludeCycle,
+        }
+    }
+
+    /// Localized message and suggestion
+    fn message(&self, path: &str, locale: &str) -> (String, String) {
+        match (self, locale) {
+            (IncludeError::NoBaseDirectory, "en") => (
+                format!("Cannot include '{}' from an unsaved project", path),
+                "Save the project first".to_string(),
+            ),
+            (IncludeError::NoBaseDirectory, "fr") => (
+                format!("Impossible d'inclure '{}' depuis un projet non enregistré", path),
+                "Enregistrez d'abord le projet".to_string(),
+            ),
+            (IncludeError::NoBaseDirectory, _) => (
+                format!("未保存のプロジェクトから「{}」を読み込めません", path),
+                "先にプロジェクトを保存してください".to_string(),
+            ),
+            (IncludeError::NotFound, "en") => (
+                format!("Included project '{}' not found", path),
+                "Check the path (relative to the project file)".to_string(),
+            ),
+            (IncludeError::NotFound, "fr") => (
+                format!("Projet inclus '{}' introuvable", path),
+                "Vérifiez le chemin (relatif au fichier du projet)".to_string(),
+            ),
+            (IncludeError::NotFound, _) => (
+                format!("読み込むプロジェクト「{}」が見つかりません", path),
+                "パスを確認してください（プロジェクトファイルからの相対パス）".to_string(),
+            ),
+            (IncludeError::OutsideProject, "en") => (
+                format!("'{}' is outside the project directory", path),
+                "Move the project into the project directory".to_string(),
+            ),
+            (IncludeError::OutsideProject, "fr") => (
+                format!("'{}' est en dehors du dossier du projet", path),
+                "Déplacez le projet dans le dossier du projet".to_string(),
+            ),
+            (IncludeError::OutsideProject, _) => (
+                format!("「{}」はプロジェクトのディレクトリの外にあります", path),
+                "プロジェクトのディレクトリ内に移動してください".to_string(),
+            ),
+            (IncludeError::Cycle { chain }, "en") => (
+                format!("Projects include each other: {}", chain.join(" → ")),
+                "Remove one of the includes".to_string(),
+            ),
+            (IncludeError::Cycle { chain }, "fr") => (
+                format!("Les projets s'incluent mutuellement : {}", chain.join(" → ")),
+                "Supprimez l'une des inclusions".to_string(),
+            ),
+            (IncludeError::Cycle { chain }, _) => (
+                format!("プロジェクトが循環して読み込まれています: {}", chain.join(" → ")),
+                "いずれかの読み込みを削除してください".to_string(),
+            ),
+            (IncludeError::Invalid(e), "en") => (
+                format!("Failed to load included project '{}': {}", path, e),
+                "Open the project and save it again".to_string(),
+            ),
+            (IncludeError::Invalid(e), "fr") => (
+                format!("Échec du chargement du projet inclus '{}' : {}", path, e),
+                "Ouvrez le projet et enregistrez-le à nouveau".to_string(),
+            ),
+            (IncludeError::Invalid(e), _) => (
+                format!("読み込むプロジェクト「{}」を開けません: {}", path, e),
+                "プロジェクトを開いて保存し直してください".to_string(),
+            ),
+        }
+    }
+}
+
+/// Display name of a project file in cycle messages
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+/// Recursive include resolution, rooted at the open project
+pub struct IncludeResolver<'a> {
+    /// Directory of the top-level project (includes cannot leave it)
+    root: Option<PathBuf>,
+    /// Projects being generated, outermost first
+    stack: Vec<PathBuf>,
+    load: &'a ProjectLoader<'a>,
+}
+
+impl<'a> IncludeResolver<'a> {
+    /// Create a resolver for the project at `project_path`
+    ///
+    /// # Arguments
+    /// * `project_path` - Path of the open `.promps` file, if saved
+    /// * `load` - Loads an included project
+    pub fn new(project_path: Option<&str>, load: &'a ProjectLoader<'a>) -> Self {
+        let project = project_path.map(|p| fs::canonicalize(p).unwrap_or_else(|_| PathBuf::from(p)));
+        IncludeResolver {
+            root: project.as_deref().and_then(Path::parent).map(Path::to_path_buf),
+            stack: project.into_iter().collect(),
+            load,
+        }
+    }
+
+    /// Resolve an include path against the including project's directory
+    pub fn resolve_path(&self, path: &str, base_dir: &Path) -> Result<PathBuf, IncludeError> {
+        let root = self.root.as_deref().ok_or(IncludeError::NoBaseDirectory)?;
+        let root = fs::canonicalize(root).map_err(|_| IncludeError::NotFound)?;
+        let full_path = fs::canonicalize(base_dir.join(path)).map_err(|_| IncludeError::NotFound)?;
+        if !full_path.starts_with(&root) {
+            return Err(IncludeError::OutsideProject);
+        }
+        if !full_path.is_file() {
+            return Err(IncludeError::NotFound);
+        }
+        Ok(full_path)
+    }
+
+    /// Generate the prompt of an included project
+    ///
+    /// # Arguments
+    /// * `path` - Path as written in the `_INCLUDE:` token
+    /// * `base_dir` - Directory of the including project
+    /// * `locale` - Locale of the including project (used if the included one has none)
+    pub fn include_prompt(&mut self, path: &str, base_dir: &Path, locale: &str) -> Result<String, IncludeError> {
+        let full_path = self.resolve_path(path, base_dir)?;
+        if let Some(start) = self.stack.iter().position(|p| *p == full_path) {
+            let chain = self.stack[start..]
+                .iter()
+                .chain(std::iter::once(&full_path))
+                .map(|p| file_name(p))
+                .collect();
+            return Err(IncludeError::Cycle { chain });
+        }
+
+        let project = (self.load)(&full_path, locale).map_err(IncludeError::Invalid)?;
+        let locale = project.locale.as_deref().unwrap_or(locale);
+        let dir = full_path.parent().map(Path::to_path_buf);
+
+        let mut parts = evaluate_conditions(&parse_input(&project.dsl), &project.variables);
+        resolve_file_parts_within(&mut parts, dir.as_deref(), self.root.as_deref());
+
+        self.stack.push(full_path);
+        let result = self.resolve_nested(&mut parts, dir.as_deref(), locale);
+        self.stack.pop();
+        result?;
+
+        Ok(generate_prompt_with_locale(&parts, locale))
+    }
+
+    /// Fill in nested includes; the first failure fails the whole include
+    fn resolve_nested(&mut self, parts: &mut [PromptPart], base_dir: Option<&Path>, locale: &str) -> Result<(), IncludeError> {
+        for part in parts.iter_mut() {
+            if let PartKind::Include { path, content } = &mut part.kind {
+                let base_dir = base_dir.ok_or(IncludeError::NoBaseDirectory)?;
+                *content = Some(self.include_prompt(path, base_dir, locale)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fill in the content of `_INCLUDE:` parts of the open project
+///
+/// Parts whose project cannot be included stay unresolved (rendered as a label).
+///
+/// # Arguments
+/// * `parts` - Parts of the open project
+/// * `project_path` - Path of the open `.promps` file, if saved
+/// * `locale` - Locale of the open project
+/// * `load` - Loads an included project
+///
+/// # Returns
+/// Number of projects that could not be included
+pub fn resolve_include_parts(
+    parts: &mut [PromptPart],
+    project_path: Option<&str>,
+    locale: &str,
+    load: &ProjectLoader,
+) -> usize {
+    let mut resolver = IncludeResolver::new(project_path, load);
+    let base_dir = resolver.root.clone();
+    let mut failed = 0;
+
+    for part in parts.iter_mut() {
+        if let PartKind::Include { path, content } = &mut part.kind {
+            let result = base_dir
+                .as_deref()
+                .ok_or(IncludeError::NoBaseDirectory)
+                .and_then(|dir| resolver.include_prompt(path, dir, locale));
+            match result {
+                Ok(prompt) => *content = Some(prompt),
+                Err(_) => failed += 1,
+            }
+        }
+    }
+
+    failed
+}
+
+/// Check every `_INCLUDE:` token in the input
+///
+/// # Arguments
+/// * `input` - DSL text
+/// * `project_path` - Path of the open `.promps` file, if saved
+/// * `locale` - Locale code for messages ("ja", "en", "fr")
+/// * `load` - Loads an included project
+///
+/// # Returns
+/// One error per project that cannot be included (position = token index)
+pub fn validate_project_includes(
+    input: &str,
+    project_path: Option<&str>,
+    locale: &str,
+    load: &ProjectLoader,
+) -> Vec<ValidationError> {
+    let mut resolver = IncludeResolver::new(project_path, load);
+    let base_dir = resolver.root.clone();
+
+    tokenize(input)
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| token.marker.as_deref() == Some("INCLUDE"))
+        .filter_map(|(i, token)| {
+            let error = base_dir
+                .as_deref()
+                .ok_or(IncludeError::NoBaseDirectory)
+                .and_then(|dir| resolver.include_prompt(&token.text, dir, locale))
+                .err()?;
+            let (message, suggestion) = error.message(&token.text, locale);
+            Some(ValidationError::new(error.code(), message, i, Severity::Error, Some(suggestion)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use promps::generate_prompt;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("promps_test_includes_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Test projects are plain files: first line = locale (or "-"), rest = DSL
+    fn load_test_project(path: &Path, _locale: &str) -> Result<IncludedProject, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let (locale, dsl) = text.split_once('\n').unwrap_or((&text, ""));
+        Ok(IncludedProject {
+            dsl: dsl.to_string(),
+            locale: (locale != "-").then(|| locale.to_string()),
+            variables: HashMap::from([("formal".to_string(), true)]),
+        })
+    }
+
+    fn project_path(dir: &Path) -> String {
+        dir.join("main.promps").to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_nested_includes_with_own_locale() {
+        let dir = temp_dir("nested");
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::write(dir.join("shared/style.promps"), "en\n_INCLUDE:tone.promps _N:Markdown で 答えて _FMT:json").unwrap();
+        fs::write(dir.join("shared/tone.promps"), "-\n_IF:formal 丁寧に _ELSE 気軽に _ENDIF").unwrap();
+
+        let mut parts = parse_input("_INCLUDE:shared/style.promps _N:Doc を 要約して");
+        let failed = resolve_include_parts(&mut parts, Some(&project_path(&dir)), "ja", &load_test_project);
+
+        assert_eq!(failed, 0);
+        assert_eq!(
+            generate_prompt(&parts),
+            "丁寧に\nMarkdown (NOUN) で 答えて\nConstraints:\n- Format: JSON\nDoc (NOUN) を 要約して\n"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_include_cycle_detected() {
+        let dir = temp_dir("cycle");
+        fs::write(dir.join("main.promps"), "-\n_INCLUDE:a.promps").unwrap();
+        fs::write(dir.join("a.promps"), "-\n_INCLUDE:b.promps").unwrap();
+        fs::write(dir.join("b.promps"), "-\n_INCLUDE:main.promps").unwrap();
+
+        let errors = validate_project_includes("_INCLUDE:a.promps", Some(&project_path(&dir)), "en", &load_test_project);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ValidationErrorCode::IncludeCycle);
+        assert!(errors[0].message.contains("main.promps → a.promps → b.promps → main.promps"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_include_cannot_escape_project_directory() {
+        let dir = temp_dir("escape");
+        let outside = temp_dir("escape_outside");
+        fs::create_dir_all(dir.join("project")).unwrap();
+        fs::write(outside.join("secret.promps"), "-\n秘密").unwrap();
+
+        let project = dir.join("project/main.promps").to_string_lossy().to_string();
+        let input = format!("_INCLUDE:../../promps_test_includes_escape_outside/secret.promps _INCLUDE:{}", outside.join("secret.promps").display());
+        let errors = validate_project_includes(&input, Some(&project), "en", &load_test_project);
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.code == ValidationErrorCode::IncludeOutsideProject));
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&outside);
+    }
+
+    #[test]
+    fn test_unresolved_include_stays_label() {
+        let dir = temp_dir("missing");
+
+        let mut parts = parse_input("_INCLUDE:gone.promps _N:Doc");
+        let failed = resolve_include_parts(&mut parts, Some(&project_path(&dir)), "ja", &load_test_project);
+        let errors = validate_project_includes("_INCLUDE:gone.promps", None, "ja", &load_test_project);
+
+        assert_eq!(failed, 1);
+        assert_eq!(generate_prompt(&parts), "gone.promps (INCLUDE) Doc (NOUN)\n");
+        assert_eq!(errors[0].code, ValidationErrorCode::IncludeNotFound);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
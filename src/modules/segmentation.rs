@@ -0,0 +1,394 @@
+/// Promps - Natural Language Segmentation Module
+///
+/// This module splits plain-text prompts (without `_N:` markers, and for
+/// Japanese without spaces) into DSL tokens. Particles, verbs and
+/// punctuation are found with `TokenType::classify*`; the remaining spans
+/// become noun candidates. Every token carries a confidence score so the
+/// UI can highlight guesses.
+///
+/// Japanese uses script boundaries: kanji/katakana/latin runs are noun
+/// candidates, and hiragana runs are split into particles, verb endings
+/// (して/する) and other text.
+
+use serde::{Deserialize, Serialize};
+
+use super::validation::{find_block_type, TokenType};
+
+// ============================================================================
+// Result Types
+// ============================================================================
+
+/// A segmented token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentedToken {
+    /// Original text of the token
+    pub text: String,
+    /// Token type
+    pub token_type: TokenType,
+    /// DSL form (e.g. "_N:ユーザー", "_V:分析して", "が")
+    pub dsl: String,
+    /// Confidence of the segmentation (0.0 - 1.0)
+    pub confidence: f64,
+}
+
+/// Result of segmenting plain text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentationResult {
+    /// Space-delimited DSL
+    pub dsl: String,
+    /// Tokens in order
+    pub tokens: Vec<SegmentedToken>,
+}
+
+/// Confidence for words that are fixed blocks (particles, fixed verbs, articles)
+const CONFIDENCE_FIXED: f64 = 0.95;
+/// Confidence for words recognized by the classifiers (e.g. custom verbs)
+const CONFIDENCE_CLASSIFIED: f64 = 0.8;
+/// Confidence for noun candidates bounded by script changes
+const CONFIDENCE_NOUN: f64 = 0.7;
+/// Confidence for English/French words assumed to be nouns
+const CONFIDENCE_NOUN_GUESS: f64 = 0.5;
+/// Confidence for text that matched nothing
+const CONFIDENCE_OTHER: f64 = 0.4;
+
+impl SegmentedToken {
+    fn new(text: &str, token_type: TokenType, confidence: f64) -> Self {
+        let dsl = match token_type {
+            TokenType::Noun => format!("_N:{}", text),
+            TokenType::Verb => format!("_V:{}", text),
+            _ => text.to_string(),
+        };
+        SegmentedToken {
+            text: text.to_string(),
+            token_type,
+            dsl,
+            confidence,
+        }
+    }
+}
+
+// ============================================================================
+// Japanese Segmentation
+// ============================================================================
+
+/// Character script class for Japanese segmentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Hiragana,
+    /// Kanji, katakana, latin letters and digits (noun material)
+    Content,
+    Punctuation,
+    Space,
+}
+
+fn script_of(c: char) -> Script {
+    match c {
+        c if c.is_whitespace() => Script::Space,
+        '\u{3041}'..='\u{309F}' => Script::Hiragana,
+        '、' | '。' | '！' | '？' | '!' | '?' | ',' | '.' | '「' | '」' | '（' | '）' => Script::Punctuation,
+        _ => Script::Content,
+    }
+}
+
+/// Check if every character is a kanji (CJK unified ideograph)
+fn is_kanji(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| ('\u{4E00}'..='\u{9FFF}').contains(&c))
+}
+
+/// Find a particle at the start of a hiragana run
+///
+/// Sentence-final particles (か/ね/よ/わ) only count at the end of the run,
+/// so words like わかりやすく are not split.
+fn match_particle(run: &str) -> Option<&str> {
+    const SENTENCE_FINAL: &[&str] = &["か", "ね", "よ", "わ"];
+
+    if run.starts_with("について") {
+        return Some("について");
+    }
+
+    // Longest match first (から before か)
+    let mut ends: Vec<usize> = run.char_indices().map(|(i, c)| i + c.len_utf8()).take(2).collect();
+    ends.reverse();
+    for end in ends {
+        let candidate = &run[..end];
+        if TokenType::classify(candidate) != TokenType::Particle {
+            continue;
+        }
+        if SENTENCE_FINAL.contains(&candidate) && end != run.len() {
+            continue;
+        }
+        return Some(candidate);
+    }
+    None
+}
+
+/// Segment Japanese text
+fn segment_ja(text: &str) -> Vec<SegmentedToken> {
+    let mut tokens = Vec::new();
+
+    // Group characters into runs of the same script
+    let mut runs: Vec<(Script, String)> = Vec::new();
+    for c in text.chars() {
+        let script = script_of(c);
+        match runs.last_mut() {
+            // Punctuation marks stay separate tokens
+            Some((last, run)) if *last == script && script != Script::Punctuation => run.push(c),
+            _ => runs.push((script, c.to_string())),
+        }
+    }
+
+    let mut pending_noun: Option<String> = None;
+    let flush_noun = |pending: &mut Option<String>, tokens: &mut Vec<SegmentedToken>| {
+        if let Some(noun) = pending.take() {
+            tokens.push(SegmentedToken::new(&noun, TokenType::Noun, CONFIDENCE_NOUN));
+        }
+    };
+
+    for (script, run) in runs {
+        match script {
+            Script::Space => flush_noun(&mut pending_noun, &mut tokens),
+            Script::Punctuation => {
+                flush_noun(&mut pending_noun, &mut tokens);
+                tokens.push(SegmentedToken::new(&run, TokenType::classify(&run), CONFIDENCE_FIXED));
+            }
+            Script::Content => {
+                flush_noun(&mut pending_noun, &mut tokens);
+                pending_noun = Some(run);
+            }
+            Script::Hiragana => {
+                let mut rest = run.as_str();
+
+                // Kanji + して/する is a verb (分析して, 要約する)
+                if let Some(stem) = pending_noun.as_deref() {
+                    if is_kanji(stem) {
+                        if let Some(ending) = ["して", "する"].iter().find(|e| rest.starts_with(**e)) {
+                            let verb = format!("{}{}", stem, ending);
+                            let confidence = if find_block_type(&verb, "ja").is_some() {
+                                CONFIDENCE_FIXED
+                            } else {
+                                CONFIDENCE_CLASSIFIED
+                            };
+                            pending_noun = None;
+                            tokens.push(SegmentedToken::new(&verb, TokenType::Verb, confidence));
+                            rest = &rest[ending.len()..];
+                        }
+                    }
+                }
+                flush_noun(&mut pending_noun, &mut tokens);
+
+                // Particles at the start of the run (には, からの, ...)
+                while let Some(particle) = match_particle(rest) {
+                    let token_type = if particle == "について" { TokenType::Other } else { TokenType::Particle };
+                    tokens.push(SegmentedToken::new(particle, token_type, CONFIDENCE_FIXED));
+                    rest = &rest[particle.len()..];
+                }
+
+                if !rest.is_empty() {
+                    let token_type = TokenType::classify(rest);
+                    let confidence = if token_type == TokenType::Other { CONFIDENCE_OTHER } else { CONFIDENCE_CLASSIFIED };
+                    tokens.push(SegmentedToken::new(rest, token_type, confidence));
+                }
+            }
+        }
+    }
+    flush_noun(&mut pending_noun, &mut tokens);
+
+    tokens
+}
+
+// ============================================================================
+// English/French Segmentation
+// ============================================================================
+
+/// Function words that stay unmarked (not noun candidates)
+const FUNCTION_WORDS: &[&str] = &[
+    // English
+    "and", "or", "but", "it", "me", "my", "your", "our", "all", "some", "is", "are",
+    // French
+    "et", "ou", "mais", "il", "elle", "moi", "mon", "ma", "mes", "votre", "vos", "tous", "est",
+];
+
+/// Split a word into leading elision (l', d'), the word and trailing punctuation
+fn split_word(word: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut word = word;
+
+    for prefix in ["l'", "d'", "L'", "D'", "l\u{2019}", "d\u{2019}"] {
+        if word.len() > prefix.len() && word.starts_with(prefix) {
+            parts.push(prefix.replace('\u{2019}', "'").to_lowercase());
+            word = &word[prefix.len()..];
+            break;
+        }
+    }
+
+    let trimmed = word.trim_end_matches(['.', ',', '!', '?', ';', ':']);
+    if !trimmed.is_empty() {
+        parts.push(trimmed.to_string());
+    }
+    for c in word[trimmed.len()..].chars() {
+        parts.push(c.to_string());
+    }
+
+    parts
+}
+
+/// Segment English or French text
+fn segment_latin(text: &str, locale: &str) -> Vec<SegmentedToken> {
+    let mut tokens = Vec::new();
+
+    for word in text.split_whitespace().flat_map(split_word) {
+        let lower = word.to_lowercase();
+
+        // French elision
+        if lower == "l'" || lower == "d'" {
+            let token_type = if lower == "l'" { TokenType::Article } else { TokenType::Preposition };
+            tokens.push(SegmentedToken::new(&lower, token_type, CONFIDENCE_CLASSIFIED));
+            continue;
+        }
+
+        let token_type = if locale == "fr" {
+            TokenType::classify_fr(&word)
+        } else {
+            TokenType::classify_en(&word)
+        };
+
+        let token = match token_type {
+            TokenType::Other if FUNCTION_WORDS.contains(&lower.as_str()) => {
+                SegmentedToken::new(&word, TokenType::Other, CONFIDENCE_CLASSIFIED)
+            }
+            TokenType::Other => {
+                // Capitalized words are more likely to be names
+                let confidence = if word.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
+                    CONFIDENCE_NOUN
+                } else {
+                    CONFIDENCE_NOUN_GUESS
+                };
+                SegmentedToken::new(&word, TokenType::Noun, confidence)
+            }
+            token_type => {
+                let confidence = if find_block_type(&word, locale).is_some() {
+                    CONFIDENCE_FIXED
+                } else {
+                    CONFIDENCE_CLASSIFIED
+                };
+                SegmentedToken::new(&word, token_type, confidence)
+            }
+        };
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Segment plain text into DSL tokens
+///
+/// # Arguments
+/// * `text` - Plain-text prompt
+/// * `locale` - Locale code ("ja", "en", "fr")
+///
+/// # Returns
+/// SegmentationResult with DSL and per-token confidence
+pub fn segment_text(text: &str, locale: &str) -> SegmentationResult {
+    let tokens = match locale {
+        "en" | "fr" => segment_latin(text, locale),
+        _ => segment_ja(text),
+    };
+    let dsl = tokens.iter().map(|t| t.dsl.as_str()).collect::<Vec<_>>().join(" ");
+
+    SegmentationResult { dsl, tokens }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_ja_basic_sentence() {
+        let result = segment_text("ユーザーがドキュメントを分析して。", "ja");
+        assert_eq!(result.dsl, "_N:ユーザー が _N:ドキュメント を _V:分析して 。");
+    }
+
+    #[test]
+    fn test_segment_ja_custom_verb_and_trailing_text() {
+        let result = segment_text("データを整理してください", "ja");
+        assert_eq!(result.dsl, "_N:データ を _V:整理して ください");
+
+        let verb = &result.tokens[2];
+        assert_eq!(verb.token_type, TokenType::Verb);
+        assert_eq!(verb.confidence, CONFIDENCE_CLASSIFIED);
+        assert_eq!(result.tokens[3].confidence, CONFIDENCE_OTHER);
+    }
+
+    #[test]
+    fn test_segment_ja_multi_char_particles() {
+        let result = segment_text("英語から日本語に翻訳して", "ja");
+        assert_eq!(result.dsl, "_N:英語 から _N:日本語 に _V:翻訳して");
+    }
+
+    #[test]
+    fn test_segment_ja_topic_and_stacked_particles() {
+        let result = segment_text("売上について東京には送信して", "ja");
+        assert_eq!(result.dsl, "_N:売上 について _N:東京 に は _V:送信して");
+    }
+
+    #[test]
+    fn test_segment_ja_does_not_split_words_on_final_particles() {
+        let result = segment_text("データをわかりやすく要約して", "ja");
+        assert_eq!(result.dsl, "_N:データ を わかりやすく _V:要約して");
+    }
+
+    #[test]
+    fn test_segment_ja_confidence() {
+        let result = segment_text("ユーザーがドキュメントを分析して", "ja");
+        assert_eq!(result.tokens[0].confidence, CONFIDENCE_NOUN);
+        assert_eq!(result.tokens[1].confidence, CONFIDENCE_FIXED);
+        assert_eq!(result.tokens[4].confidence, CONFIDENCE_FIXED);
+    }
+
+    #[test]
+    fn test_segment_en_sentence() {
+        let result = segment_text("Please translate the report to Japanese.", "en");
+        assert_eq!(result.dsl, "Please _V:translate the _N:report to _N:Japanese .");
+
+        let japanese = result.tokens.iter().find(|t| t.text == "Japanese").unwrap();
+        assert!(japanese.confidence > result.tokens[3].confidence);
+    }
+
+    #[test]
+    fn test_segment_en_function_words_unmarked() {
+        let result = segment_text("summarize my notes and slides", "en");
+        assert_eq!(result.dsl, "_V:summarize my _N:notes and _N:slides");
+    }
+
+    #[test]
+    fn test_segment_fr_elision() {
+        let result = segment_text("analyser l'article d'Alice", "fr");
+        assert_eq!(result.dsl, "_V:analyser l' _N:article d' _N:Alice");
+    }
+
+    #[test]
+    fn test_segment_empty() {
+        let result = segment_text("", "ja");
+        assert!(result.tokens.is_empty());
+        assert_eq!(result.dsl, "");
+    }
+
+    #[test]
+    fn test_segmentation_result_serialization() {
+        let result = segment_text("データを分析して", "ja");
+        let json = serde_json::to_string(&result).unwrap();
+
+        assert!(json.contains("\"tokenType\":\"Noun\""));
+        assert!(json.contains("\"confidence\":"));
+    }
+}
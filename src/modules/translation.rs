@@ -0,0 +1,663 @@
+/// Promps - Cross-locale DSL Translation Module
+///
+/// This module translates a DSL sequence between locales using the block
+/// vocabulary: Japanese SOV order (名詞 が 名詞 を 動詞) is reordered to
+/// English/French SVO order (Verb the Noun to Noun) and back.
+/// Nouns are never translated - only fixed blocks are mapped.
+
+use promps::tokenizer::{format_token, split_tokens};
+use serde::{Deserialize, Serialize};
+
+use super::validation::{block_word, find_block_type, find_verb_block, TokenType};
+
+// ============================================================================
+// Result Types
+// ============================================================================
+
+/// A token that could not be mapped to the target locale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationWarning {
+    /// Position in the source token sequence (0-indexed)
+    pub position: usize,
+    /// Source token
+    pub token: String,
+    /// Human-readable message (in the target locale)
+    pub message: String,
+}
+
+/// Result of translating a DSL sequence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationResult {
+    /// Translated DSL text
+    pub output: String,
+    /// Tokens that were kept as-is or dropped
+    pub warnings: Vec<TranslationWarning>,
+}
+
+// ============================================================================
+// Clause Model
+// ============================================================================
+
+/// A locale-independent clause: the parts of one action by role
+///
+/// A sentence with several verbs is split into several clauses
+/// (`_N:A を 分析して 、 _N:B を 要約して`), joined in the target locale.
+#[derive(Debug, Default)]
+struct Clause {
+    /// Follows another clause of the same sentence
+    joined: bool,
+    /// Conjunction joining it to the previous clause (then / そして), mapped to
+    /// the target locale; joined clauses without one use and / 、
+    connector: Option<String>,
+    /// Polite marker (please / veuillez) was present
+    polite: bool,
+    /// Subject phrase (名詞 が)
+    subject: Vec<String>,
+    /// Object phrase (名詞 を / noun after the verb)
+    object: Vec<String>,
+    /// Adjunct phrases with their particle block type (名詞 に, to Noun, ...)
+    adjuncts: Vec<(Option<&'static str>, Vec<String>)>,
+    /// Verb: (block type, original text) - block type is None for custom verbs
+    verb: Option<(Option<&'static str>, String)>,
+    /// Sentence-final punctuation block type
+    terminator: Option<&'static str>,
+}
+
+/// Is the locale written in SOV order (particles after nouns)?
+fn is_sov(locale: &str) -> bool {
+    locale != "en" && locale != "fr"
+}
+
+fn classify_with_locale(token: &str, locale: &str) -> TokenType {
+    match locale {
+        "en" => TokenType::classify_en(token),
+        "fr" => TokenType::classify_fr(token),
+        _ => TokenType::classify(token),
+    }
+}
+
+/// Localized warning message
+fn warning_message(kind: &str, token: &str, to: &str) -> String {
+    match (kind, to) {
+        ("verb", "en") => format!("Custom verb '{}' kept as-is", token),
+        ("verb", "fr") => format!("Verbe personnalisé '{}' conservé tel quel", token),
+        ("verb", _) => format!("カスタム動詞「{}」はそのまま残しました", token),
+        ("particle", "en") => format!("'{}' has no equivalent and was dropped", token),
+        ("particle", "fr") => format!("'{}' n'a pas d'équivalent et a été supprimé", token),
+        ("particle", _) => format!("「{}」に対応する助詞がないため削除しました", token),
+        (_, "en") => format!("'{}' could not be translated and was kept as-is", token),
+        (_, "fr") => format!("'{}' n'a pas pu être traduit et a été conservé", token),
+        (_, _) => format!("「{}」は翻訳できないためそのまま残しました", token),
+    }
+}
+
+// ============================================================================
+// Translation
+// ============================================================================
+
+/// Translate a DSL sequence from one locale to another
+///
+/// # Arguments
+/// * `input` - Space-delimited DSL tokens
+/// * `from` - Source locale code ("ja", "en", "fr")
+/// * `to` - Target locale code ("ja", "en", "fr")
+///
+/// # Returns
+/// TranslationResult with translated DSL and warnings for unmapped tokens
+///
+/// # Rules
+/// 1. Nouns (_N:) are kept unchanged
+/// 2. Particles map to prepositions via block type (に → to / à)
+/// 3. が/を are dropped in SVO locales; subject/object order is restored in SOV
+/// 4. A definite article is inserted before the object in SVO locales
+/// 5. Fixed verbs are mapped; custom verbs are kept with a warning
+pub fn translate_dsl(input: &str, from: &str, to: &str) -> TranslationResult {
+    let owned_tokens = split_tokens(input);
+    let tokens: Vec<&str> = owned_tokens.iter().map(|t| t.as_str()).collect();
+    let mut warnings = Vec::new();
+
+    if from == to || tokens.is_empty() {
+        return TranslationResult {
+            output: tokens.iter().map(|t| format_token(t)).collect::<Vec<_>>().join(" "),
+            warnings,
+        };
+    }
+
+    let clauses = if is_sov(from) {
+        parse_sov(&tokens, from, to, &mut warnings)
+    } else {
+        parse_svo(&tokens, from, to, &mut warnings)
+    };
+
+    let sentences: Vec<String> = clauses
+        .iter()
+        .map(|clause| {
+            let words = if is_sov(to) {
+                emit_sov(clause, to)
+            } else {
+                emit_svo(clause, to)
+            };
+            // Re-quote marker text (nouns, verbs, modifiers) that contains spaces
+            words
+                .iter()
+                .map(|w| format_token(w))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    TranslationResult {
+        output: sentences.join(" "),
+        warnings,
+    }
+}
+
+/// Map a non-noun token to the target locale, or keep it with a warning
+fn map_word(
+    position: usize,
+    token: &str,
+    from: &str,
+    to: &str,
+    warnings: &mut Vec<TranslationWarning>,
+) -> String {
+    match find_block_type(token, from).and_then(|bt| block_word(bt, to)) {
+        Some(word) if !word.is_empty() => word.to_string(),
+        _ => {
+            warnings.push(TranslationWarning {
+                position,
+                token: token.to_string(),
+                message: warning_message("other", token, to),
+            });
+            token.to_string()
+        }
+    }
+}
+
+fn is_terminator(block_type: &str) -> bool {
+    matches!(
+        block_type,
+        "promps_punct_kuten" | "promps_punct_period" | "promps_punct_exclaim" | "promps_punct_question"
+    )
+}
+
+/// Start the next clause of the same sentence
+fn next_clause(clauses: &mut Vec<Clause>, clause: &mut Clause, connector: Option<String>) {
+    clauses.push(std::mem::take(clause));
+    clause.joined = true;
+    clause.connector = connector;
+}
+
+/// Parse Japanese (SOV) tokens into clauses
+fn parse_sov(
+    tokens: &[&str],
+    from: &str,
+    to: &str,
+    warnings: &mut Vec<TranslationWarning>,
+) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+    let mut clause = Clause::default();
+    let mut phrase: Vec<String> = Vec::new();
+    // Phrases joined with と waiting for their role particle
+    let mut coordinated: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let token_type = classify_with_locale(token, from);
+        let is_verb = token_type == TokenType::Verb || find_verb_block(tokens, i, from).is_some();
+
+        // A noun, verb, 、 or conjunction after the verb starts the next clause
+        if clause.verb.is_some() {
+            let touten = find_block_type(token, from) == Some("promps_punct_touten");
+            let conjunction = token_type == TokenType::Conjunction;
+            if is_verb || touten || conjunction || token_type == TokenType::Noun {
+                flush_phrase(&mut clause, &mut coordinated, &mut phrase);
+                let connector = conjunction.then(|| map_word(i, token, from, to, warnings));
+                next_clause(&mut clauses, &mut clause, connector);
+                if touten || conjunction {
+                    i += 1;
+                    continue;
+                }
+            }
+        }
+
+        if is_verb {
+            match find_verb_block(tokens, i, from) {
+                Some((bt, len)) => {
+                    clause.verb = Some((Some(bt), token.to_string()));
+                    i += len;
+                }
+                None => {
+                    warnings.push(TranslationWarning {
+                        position: i,
+                        token: token.to_string(),
+                        message: warning_message("verb", token, to),
+                    });
+                    let text = token.strip_prefix("_V:").unwrap_or(token);
+                    clause.verb = Some((None, text.to_string()));
+                    i += 1;
+                }
+            }
+            continue;
+        }
+
+        match token_type {
+            TokenType::Noun => phrase.push(token.to_string()),
+            TokenType::Conjunction if is_empty_clause(&clause) => {
+                // そして at the start of a sentence
+                clause.joined = true;
+                clause.connector = Some(map_word(i, token, from, to, warnings));
+            }
+            TokenType::Particle => {
+                let block_type = find_block_type(token, from);
+                let mut full_phrase = std::mem::take(&mut coordinated);
+                full_phrase.append(&mut phrase);
+                match block_type {
+                    Some("promps_particle_ga") => clause.subject = full_phrase,
+                    Some("promps_particle_wo") => clause.object = full_phrase,
+                    Some("promps_particle_to") => {
+                        // A と B: coordinate with the next phrase
+                        full_phrase.push(block_word("promps_particle_to", to).unwrap_or("").to_string());
+                        coordinated = full_phrase;
+                    }
+                    Some(bt) => clause.adjuncts.push((Some(bt), full_phrase)),
+                    None => {
+                        warnings.push(TranslationWarning {
+                            position: i,
+                            token: token.to_string(),
+                            message: warning_message("particle", token, to),
+                        });
+                        clause.adjuncts.push((None, full_phrase));
+                    }
+                }
+            }
+            TokenType::Punctuation => match find_block_type(token, from) {
+                Some(bt) if is_terminator(bt) => {
+                    clause.terminator = Some(bt);
+                    flush_phrase(&mut clause, &mut coordinated, &mut phrase);
+                    clauses.push(std::mem::take(&mut clause));
+                }
+                Some("promps_punct_touten") => {
+                    // 読点 separates phrases; SVO order makes it redundant
+                }
+                _ => phrase.push(map_word(i, token, from, to, warnings)),
+            },
+            _ => phrase.push(map_word(i, token, from, to, warnings)),
+        }
+        i += 1;
+    }
+
+    flush_phrase(&mut clause, &mut coordinated, &mut phrase);
+    if !is_empty_clause(&clause) {
+        clauses.push(clause);
+    }
+    clauses
+}
+
+/// Attach trailing words without a particle to the clause as a bare adjunct
+fn flush_phrase(clause: &mut Clause, coordinated: &mut Vec<String>, phrase: &mut Vec<String>) {
+    let mut rest = std::mem::take(coordinated);
+    rest.append(phrase);
+    if !rest.is_empty() {
+        clause.adjuncts.push((None, rest));
+    }
+}
+
+fn is_empty_clause(clause: &Clause) -> bool {
+    !clause.joined
+        && !clause.polite
+        && clause.subject.is_empty()
+        && clause.object.is_empty()
+        && clause.adjuncts.is_empty()
+        && clause.verb.is_none()
+        && clause.terminator.is_none()
+}
+
+/// Parse English/French (SVO) tokens into clauses
+fn parse_svo(
+    tokens: &[&str],
+    from: &str,
+    to: &str,
+    warnings: &mut Vec<TranslationWarning>,
+) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+    let mut clause = Clause::default();
+    // Current phrase and the preposition that introduced it
+    let mut phrase: Vec<String> = Vec::new();
+    let mut preposition: Option<&'static str> = None;
+    // Conjunction (then / puis) waiting for the verb of the next clause
+    let mut connector: Option<String> = None;
+    let and_word = block_word("promps_particle_to", to).unwrap_or("");
+
+    fn close_phrase(clause: &mut Clause, phrase: &mut Vec<String>, preposition: &mut Option<&'static str>) {
+        if phrase.is_empty() {
+            return;
+        }
+        let words = std::mem::take(phrase);
+        match preposition.take() {
+            Some(bt) => clause.adjuncts.push((Some(bt), words)),
+            None if clause.verb.is_none() => clause.subject.extend(words),
+            None if clause.object.is_empty() && clause.adjuncts.is_empty() => clause.object = words,
+            None => clause.adjuncts.push((None, words)),
+        }
+    }
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let token_type = classify_with_locale(token, from);
+
+        // A second verb starts the next clause ("analyze the A and summarize the B")
+        let is_verb = token_type == TokenType::Verb || find_verb_block(tokens, i, from).is_some();
+        if is_verb && (clause.verb.is_some() || connector.is_some()) {
+            // Trailing "and" joins the clauses rather than two nouns
+            if !phrase.is_empty() && phrase.last().map(String::as_str) == Some(and_word) {
+                phrase.pop();
+            }
+            close_phrase(&mut clause, &mut phrase, &mut preposition);
+            if is_empty_clause(&clause) {
+                clause.joined = true;
+                clause.connector = connector.take();
+            } else {
+                next_clause(&mut clauses, &mut clause, connector.take());
+            }
+        }
+
+        if let Some((bt, len)) = find_verb_block(tokens, i, from) {
+            close_phrase(&mut clause, &mut phrase, &mut preposition);
+            clause.verb = Some((Some(bt), token.to_string()));
+            i += len;
+            continue;
+        }
+
+        match token_type {
+            TokenType::Verb => {
+                close_phrase(&mut clause, &mut phrase, &mut preposition);
+                warnings.push(TranslationWarning {
+                    position: i,
+                    token: token.to_string(),
+                    message: warning_message("verb", token, to),
+                });
+                let text = token.strip_prefix("_V:").unwrap_or(token);
+                clause.verb = Some((None, text.to_string()));
+            }
+            TokenType::Please => clause.polite = true,
+            TokenType::Noun => phrase.push(token.to_string()),
+            TokenType::Article => {
+                // Articles belong to the noun phrase; SOV targets drop them
+                if !is_sov(to) {
+                    phrase.push(map_word(i, token, from, to, warnings));
+                }
+            }
+            TokenType::Punctuation => match find_block_type(token, from) {
+                Some(bt) if is_terminator(bt) => {
+                    close_phrase(&mut clause, &mut phrase, &mut preposition);
+                    clause.terminator = Some(bt);
+                    clauses.push(std::mem::take(&mut clause));
+                }
+                _ => phrase.push(map_word(i, token, from, to, warnings)),
+            },
+            _ => match find_block_type(token, from) {
+                Some("promps_particle_to") if !phrase.is_empty() => {
+                    // A and B: coordination inside the current phrase
+                    phrase.push(and_word.to_string());
+                }
+                Some(bt) if bt.starts_with("promps_conjunction") => {
+                    close_phrase(&mut clause, &mut phrase, &mut preposition);
+                    connector = Some(map_word(i, token, from, to, warnings));
+                }
+                Some(bt) if bt.starts_with("promps_particle") => {
+                    close_phrase(&mut clause, &mut phrase, &mut preposition);
+                    preposition = Some(bt);
+                }
+                _ if token_type == TokenType::Preposition => {
+                    close_phrase(&mut clause, &mut phrase, &mut preposition);
+                    warnings.push(TranslationWarning {
+                        position: i,
+                        token: token.to_string(),
+                        message: warning_message("particle", token, to),
+                    });
+                }
+                _ => phrase.push(map_word(i, token, from, to, warnings)),
+            },
+        }
+        i += 1;
+    }
+
+    close_phrase(&mut clause, &mut phrase, &mut preposition);
+    if let Some(word) = connector {
+        // Conjunction without a following verb: kept at the end
+        clause.adjuncts.push((None, vec![word]));
+    }
+    if !is_empty_clause(&clause) {
+        clauses.push(clause);
+    }
+    clauses
+}
+
+/// Word joining a clause to the previous one (and / 、 unless a conjunction was given)
+fn connector_text(clause: &Clause, to: &str) -> Option<String> {
+    if !clause.joined {
+        return None;
+    }
+    let default = if is_sov(to) { "promps_punct_touten" } else { "promps_particle_to" };
+    clause
+        .connector
+        .clone()
+        .or_else(|| block_word(default, to).map(str::to_string))
+}
+
+/// Render a verb in the target locale
+fn verb_text(verb: &(Option<&'static str>, String), to: &str) -> String {
+    match verb.0.and_then(|bt| block_word(bt, to)) {
+        Some(word) => format!("_V:{}", word),
+        None => format!("_V:{}", verb.1),
+    }
+}
+
+/// Emit a clause in SOV order: [S が] [adjuncts] [O を] V
+fn emit_sov(clause: &Clause, to: &str) -> Vec<String> {
+    let mut words: Vec<String> = connector_text(clause, to).into_iter().collect();
+
+    if !clause.subject.is_empty() {
+        words.extend(clause.subject.iter().cloned());
+        words.push(block_word("promps_particle_ga", to).unwrap_or("").to_string());
+    }
+    for (particle, phrase) in &clause.adjuncts {
+        words.extend(phrase.iter().cloned());
+        if let Some(word) = particle.and_then(|bt| block_word(bt, to)) {
+            words.push(word.to_string());
+        }
+    }
+    if !clause.object.is_empty() {
+        words.extend(clause.object.iter().cloned());
+        words.push(block_word("promps_particle_wo", to).unwrap_or("").to_string());
+    }
+    if let Some(verb) = &clause.verb {
+        words.push(verb_text(verb, to));
+    }
+    if let Some(word) = clause.terminator.and_then(|bt| block_word(bt, to)) {
+        words.push(word.to_string());
+    }
+
+    words.retain(|w| !w.is_empty());
+    words
+}
+
+/// Emit a clause in SVO order: [please] [S] V [the O] [prep adjuncts]
+fn emit_svo(clause: &Clause, to: &str) -> Vec<String> {
+    let mut words: Vec<String> = connector_text(clause, to).into_iter().collect();
+
+    if clause.polite {
+        words.push(block_word("promps_article_please", to).unwrap_or("").to_string());
+    }
+    words.extend(clause.subject.iter().cloned());
+    if let Some(verb) = &clause.verb {
+        words.push(verb_text(verb, to));
+    }
+    if !clause.object.is_empty() {
+        // Insert a definite article unless the phrase already has a determiner
+        let has_article = clause
+            .object
+            .first()
+            .map(|w| classify_with_locale(w, to) == TokenType::Article)
+            .unwrap_or(false);
+        if !has_article {
+            words.push(block_word("promps_article_the", to).unwrap_or("").to_string());
+        }
+        words.extend(clause.object.iter().cloned());
+    }
+    for (particle, phrase) in &clause.adjuncts {
+        if let Some(word) = particle.and_then(|bt| block_word(bt, to)) {
+            words.push(word.to_string());
+        }
+        words.extend(phrase.iter().cloned());
+    }
+    if let Some(word) = clause.terminator.and_then(|bt| block_word(bt, to)) {
+        words.push(word.to_string());
+    }
+
+    words.retain(|w| !w.is_empty());
+    words
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_same_locale_unchanged() {
+        let result = translate_dsl("_N:User が _N:Doc を 分析して", "ja", "ja");
+        assert_eq!(result.output, "_N:User が _N:Doc を 分析して");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_translate_empty_input() {
+        let result = translate_dsl("", "ja", "en");
+        assert_eq!(result.output, "");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_translate_ja_to_en_object_verb() {
+        let result = translate_dsl("_N:ドキュメント を 要約して", "ja", "en");
+        assert_eq!(result.output, "_V:summarize the _N:ドキュメント");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_translate_ja_to_en_source_dest() {
+        let result = translate_dsl("_N:英語 から _N:日本語 に _N:メール を 翻訳して", "ja", "en");
+        assert_eq!(result.output, "_V:translate the _N:メール from _N:英語 to _N:日本語");
+    }
+
+    #[test]
+    fn test_translate_ja_to_fr_subject_kept_first() {
+        let result = translate_dsl("_N:User が _N:Doc を 分析して", "ja", "fr");
+        assert_eq!(result.output, "_N:User _V:analyser le _N:Doc");
+    }
+
+    #[test]
+    fn test_translate_ja_parallel_to_and() {
+        let result = translate_dsl("_N:データ と _N:結果 を 保存して", "ja", "en");
+        // 保存して is not a fixed verb block - kept with a warning
+        assert_eq!(result.output, "_V:保存して the _N:データ and _N:結果");
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].token, "保存して");
+    }
+
+    #[test]
+    fn test_translate_en_to_ja_reorders() {
+        let result = translate_dsl("translate the _N:text from _N:English to _N:Japanese", "en", "ja");
+        assert_eq!(result.output, "_N:English から _N:Japanese に _N:text を _V:翻訳して");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_translate_en_to_fr_keeps_order() {
+        let result = translate_dsl("please analyze the _N:report with _N:AI", "en", "fr");
+        assert_eq!(result.output, "veuillez _V:analyser le _N:report avec _N:AI");
+    }
+
+    #[test]
+    fn test_translate_quoted_verb() {
+        let result = translate_dsl("_N:Doc を _V:\"write down\"", "ja", "en");
+        assert_eq!(result.output, "_V:\"write down\" the _N:Doc");
+
+        let adjective = translate_dsl("_ADJ:\"very long\" _N:Doc を 要約して", "ja", "en");
+        assert!(adjective.output.contains("_ADJ:\"very long\""));
+    }
+
+    #[test]
+    fn test_translate_fr_multiword_verb() {
+        let result = translate_dsl("_V:mettre à jour le _N:fichier", "fr", "en");
+        assert_eq!(result.output, "_V:update the _N:fichier");
+    }
+
+    #[test]
+    fn test_translate_unknown_particle_warns() {
+        let result = translate_dsl("_N:User の _N:Doc を 分析して", "ja", "en");
+        assert!(result.warnings.iter().any(|w| w.token == "の"));
+        assert!(result.output.contains("_N:User"));
+    }
+
+    #[test]
+    fn test_translate_punctuation_and_sentences() {
+        let result = translate_dsl("_N:A を 分析して 。 _N:B を 要約して 。", "ja", "en");
+        assert_eq!(result.output, "_V:analyze the _N:A . _V:summarize the _N:B .");
+    }
+
+    #[test]
+    fn test_translate_ja_multiple_clauses() {
+        let expected = "_V:analyze the _N:A and _V:summarize the _N:B";
+        assert_eq!(translate_dsl("_N:A を 分析して _N:B を 要約して", "ja", "en").output, expected);
+        assert_eq!(translate_dsl("_N:A を 分析して 、 _N:B を 要約して", "ja", "en").output, expected);
+
+        let then = translate_dsl("_N:A を 分析して そして _N:B を 要約して 。", "ja", "fr");
+        assert_eq!(then.output, "_V:analyser le _N:A puis _V:résumer le _N:B .");
+    }
+
+    #[test]
+    fn test_translate_en_multiple_clauses() {
+        let result = translate_dsl("analyze the _N:A and summarize the _N:B", "en", "ja");
+        assert_eq!(result.output, "_N:A を _V:分析して 、 _N:B を _V:要約して");
+
+        let then = translate_dsl("analyze the _N:A then summarize the _N:B", "en", "ja");
+        assert_eq!(then.output, "_N:A を _V:分析して そして _N:B を _V:要約して");
+
+        // "and" between nouns stays a coordination
+        let nouns = translate_dsl("translate the _N:A and _N:B", "en", "ja");
+        assert_eq!(nouns.output, "_N:A と _N:B を _V:翻訳して");
+    }
+
+    #[test]
+    fn test_translate_quoted_noun() {
+        let result = translate_dsl("_N:\"New York\" に _N:Doc を 送信して", "ja", "en");
+        assert!(result.output.contains("to _N:\"New York\""));
+    }
+
+    #[test]
+    fn test_translate_round_trip_ja_en_ja() {
+        let source = "_N:英語 から _N:日本語 に _N:メール を _V:翻訳して";
+        let en = translate_dsl(source, "ja", "en");
+        let back = translate_dsl(&en.output, "en", "ja");
+        assert_eq!(back.output, source);
+    }
+
+    #[test]
+    fn test_translation_result_serialization() {
+        let result = translate_dsl("_N:A の _N:B", "ja", "en");
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("\"output\""));
+        assert!(json.contains("\"warnings\""));
+        assert!(json.contains("\"position\""));
+    }
+}
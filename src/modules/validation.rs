@@ -3,12 +3,30 @@
 /// This module provides grammar validation for DSL sequences.
 /// It checks for common Japanese grammar patterns and reports errors/warnings.
 
+use std::collections::HashMap;
+use std::ops::Range;
+
+use promps::{
+    is_keyword_token, is_list_separator, is_list_start, is_negation_opener_fr, token_role, Condition, ConstraintKind,
+    Glossary, NEGATION_CLOSERS_FR, NEGATION_OPENERS_FR, NEGATION_WORDS_EN, NEGATIVE_ENDINGS_JA,
+};
+use promps::tokenizer::{split_tokens, tokenize, Token};
 use serde::{Deserialize, Serialize};
 
+use super::locale_detect::{detect_locale, sentence_ranges, DEFAULT_LOCALE};
+use super::normalize::normalize_token;
+use super::pattern_lang::{
+    align, has_pattern_syntax, parse_pattern, AlignOp, Alignment, CompiledPattern, PatternAtom, PatternExpr,
+};
+
 // ============================================================================
 // Token Classification
 // ============================================================================
 
+/// Marker prefixes of tokens that act as nouns
+/// (code blocks, included files and included projects stand in for a noun)
+const NOUN_MARKERS: [&str; 4] = ["_N:", "_CODE:", "_FILE:", "_INCLUDE:"];
+
 /// Token types for grammar validation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TokenType {
@@ -26,6 +44,14 @@ pub enum TokenType {
     Preposition,
     /// Please marker (polite request marker in English)
     Please,
+    /// Adjective (形容詞) - tokens starting with _ADJ: and common adjectives
+    Adjective,
+    /// Adverb (副詞) - tokens starting with _ADV: and common adverbs
+    Adverb,
+    /// Negation (否定) - not, never, don't (English); ne, pas, jamais (French)
+    Negation,
+    /// Conjunction (接続詞) - そして, and, then, et, puis
+    Conjunction,
     /// Other (その他) - everything else
     Other,
 }
@@ -35,26 +61,43 @@ impl TokenType {
     pub fn classify(token: &str) -> Self {
         let token = token.trim();
 
-        // Check for noun marker
-        if token.starts_with("_N:") {
+        // Check for noun marker (code blocks and included files act as nouns)
+        if NOUN_MARKERS.iter().any(|m| token.starts_with(m)) {
             return TokenType::Noun;
         }
 
-        // Check for verb marker
-        if token.starts_with("_V:") {
-            return TokenType::Verb;
+        // Check for verb and modifier markers
+        if let Some(token_type) = Self::classify_marker(token) {
+            return token_type;
         }
 
+        // Word lists are matched on the normalized form (IME width variations)
+        let normalized = normalize_token(token, "ja");
+        let token = normalized.as_str();
+
         // Check for particles (助詞)
         if Self::is_particle(token) {
             return TokenType::Particle;
         }
 
+        // Check for conjunctions (接続詞) before verbs (そして ends in して)
+        if Self::is_conjunction(token) {
+            return TokenType::Conjunction;
+        }
+
         // Check for verbs (動詞)
         if Self::is_verb(token) {
             return TokenType::Verb;
         }
 
+        // Check for adjectives and adverbs (形容詞・副詞)
+        if Self::is_adjective(token) {
+            return TokenType::Adjective;
+        }
+        if Self::is_adverb(token) {
+            return TokenType::Adverb;
+        }
+
         // Check for punctuation (句読点)
         if Self::is_punctuation(token) {
             return TokenType::Punctuation;
@@ -91,30 +134,82 @@ impl TokenType {
             return true;
         }
 
+        // Negative requests (しないで, 使わないでください, 削除しないこと)
+        if NEGATIVE_ENDINGS_JA.iter().any(|ending| token.ends_with(ending) && token != *ending) {
+            return true;
+        }
+
         false
     }
 
+    /// Check if token is a common adjective (形容詞)
+    fn is_adjective(token: &str) -> bool {
+        const ADJECTIVES: &[&str] = &[
+            "詳しい", "短い", "長い", "新しい", "古い", "正しい",
+            "簡潔な", "簡単な", "重要な", "正確な", "具体的な", "丁寧な",
+        ];
+        ADJECTIVES.contains(&token)
+    }
+
+    /// Check if token is a common adverb (副詞)
+    fn is_adverb(token: &str) -> bool {
+        const ADVERBS: &[&str] = &[
+            "詳しく", "短く", "簡潔に", "簡単に", "丁寧に", "正確に",
+            "具体的に", "わかりやすく", "必ず", "すぐに", "もっと",
+        ];
+        ADVERBS.contains(&token)
+    }
+
+    /// Check if token is a conjunction (接続詞)
+    fn is_conjunction(token: &str) -> bool {
+        const CONJUNCTIONS: &[&str] = &[
+            "そして", "それから", "次に", "その後", "さらに", "または",
+        ];
+        CONJUNCTIONS.contains(&token)
+    }
+
+    /// Check if a verb is in the て-form (読み込んで, 要約して), which chains
+    /// it to the next clause
+    pub fn is_te_form(token: &str) -> bool {
+        let verb = normalize_token(token.strip_prefix("_V:").unwrap_or(token), "ja");
+        verb.ends_with('て') || verb.ends_with('で')
+    }
+
+    /// Classify the verb and modifier markers (`_V:`, `_ADJ:`, `_ADV:`)
+    fn classify_marker(token: &str) -> Option<Self> {
+        if token.starts_with("_V:") {
+            Some(TokenType::Verb)
+        } else if token.starts_with("_ADJ:") {
+            Some(TokenType::Adjective)
+        } else if token.starts_with("_ADV:") {
+            Some(TokenType::Adverb)
+        } else {
+            None
+        }
+    }
+
     /// Check if token is punctuation (句読点)
     fn is_punctuation(token: &str) -> bool {
+        // Normalized forms (full-width ！？ are folded to !?)
         const PUNCTUATION: &[&str] = &[
-            "、", "。", "！", "？", "\"", "'", ",", "/", "&",
+            "、", "。", "!", "?", "\"", "'", ",", "/", "&",
         ];
         PUNCTUATION.contains(&token)
     }
 
-    /// Check if token is a comma/touten (読点)
+    /// Check if token is a comma/touten (読点), including `，` and `､`
     pub fn is_touten(token: &str) -> bool {
-        token == "、"
+        normalize_token(token, "ja") == "、"
     }
 
-    /// Check if token is a period/kuten (句点)
+    /// Check if token is a period/kuten (句点), including `．` and `｡`
     pub fn is_kuten(token: &str) -> bool {
-        token == "。"
+        normalize_token(token, "ja") == "。"
     }
 
-    /// Check if token is an English period
+    /// Check if token is an English period, including `．` and `。`
     pub fn is_period(token: &str) -> bool {
-        token == "."
+        normalize_token(token, "en") == "."
     }
 
     // ========================================================================
@@ -124,18 +219,22 @@ impl TokenType {
     /// Classify a token for English mode
     pub fn classify_en(token: &str) -> Self {
         let token = token.trim();
-        let token_lower = token.to_lowercase();
 
-        // Check for noun marker
-        if token.starts_with("_N:") {
+        // Check for noun marker (code blocks and included files act as nouns)
+        if NOUN_MARKERS.iter().any(|m| token.starts_with(m)) {
             return TokenType::Noun;
         }
 
-        // Check for verb marker
-        if token.starts_with("_V:") {
-            return TokenType::Verb;
+        // Check for verb and modifier markers
+        if let Some(token_type) = Self::classify_marker(token) {
+            return token_type;
         }
 
+        // Word lists are matched on the normalized form (IME width variations)
+        let normalized = normalize_token(token, "en");
+        let token = normalized.as_str();
+        let token_lower = token.to_lowercase();
+
         // Check for articles
         if Self::is_article(&token_lower) {
             return TokenType::Article;
@@ -146,6 +245,11 @@ impl TokenType {
             return TokenType::Please;
         }
 
+        // Check for negation ("do not", "never")
+        if NEGATION_WORDS_EN.contains(&token_lower.replace('’', "'").as_str()) {
+            return TokenType::Negation;
+        }
+
         // Check for English verbs
         if Self::is_english_verb(&token_lower) {
             return TokenType::Verb;
@@ -156,6 +260,19 @@ impl TokenType {
             return TokenType::Preposition;
         }
 
+        // Check for adjectives and adverbs
+        if Self::is_english_adjective(&token_lower) {
+            return TokenType::Adjective;
+        }
+        if Self::is_english_adverb(&token_lower) {
+            return TokenType::Adverb;
+        }
+
+        // Check for conjunctions
+        if Self::is_english_conjunction(&token_lower) {
+            return TokenType::Conjunction;
+        }
+
         // Check for punctuation
         if Self::is_english_punctuation(token) {
             return TokenType::Punctuation;
@@ -179,6 +296,8 @@ impl TokenType {
             "show", "display", "list", "get", "make",
             "write", "read", "check", "verify", "validate",
             "compare", "format", "optimize", "review", "edit",
+            "use", "include", "add", "remove", "change", "mention",
+            "repeat", "reveal", "share", "ignore", "skip", "invent",
         ];
         VERBS.contains(&token)
     }
@@ -193,6 +312,32 @@ impl TokenType {
         PREPOSITIONS.contains(&token)
     }
 
+    /// Check if token is a common English adjective
+    fn is_english_adjective(token: &str) -> bool {
+        const ADJECTIVES: &[&str] = &[
+            "quick", "short", "brief", "long", "detailed", "new", "old",
+            "simple", "clear", "concise", "important", "main", "formal",
+            "casual", "complete", "accurate", "specific", "polite",
+        ];
+        ADJECTIVES.contains(&token)
+    }
+
+    /// Check if token is a common English adverb
+    fn is_english_adverb(token: &str) -> bool {
+        const ADVERBS: &[&str] = &[
+            "quickly", "briefly", "clearly", "concisely", "carefully",
+            "simply", "accurately", "politely", "formally", "thoroughly",
+            "always", "also",
+        ];
+        ADVERBS.contains(&token)
+    }
+
+    /// Check if token is an English conjunction
+    fn is_english_conjunction(token: &str) -> bool {
+        const CONJUNCTIONS: &[&str] = &["and", "then", "or", "but"];
+        CONJUNCTIONS.contains(&token)
+    }
+
     /// Check if token is English punctuation
     fn is_english_punctuation(token: &str) -> bool {
         const PUNCTUATION: &[&str] = &[
@@ -208,18 +353,22 @@ impl TokenType {
     /// Classify a token for French mode
     pub fn classify_fr(token: &str) -> Self {
         let token = token.trim();
-        let token_lower = token.to_lowercase();
 
-        // Check for noun marker
-        if token.starts_with("_N:") {
+        // Check for noun marker (code blocks and included files act as nouns)
+        if NOUN_MARKERS.iter().any(|m| token.starts_with(m)) {
             return TokenType::Noun;
         }
 
-        // Check for verb marker
-        if token.starts_with("_V:") {
-            return TokenType::Verb;
+        // Check for verb and modifier markers
+        if let Some(token_type) = Self::classify_marker(token) {
+            return token_type;
         }
 
+        // Word lists are matched on the normalized form (IME width variations)
+        let normalized = normalize_token(token, "fr");
+        let token = normalized.as_str();
+        let token_lower = token.to_lowercase();
+
         // Check for articles
         if Self::is_french_article(&token_lower) {
             return TokenType::Article;
@@ -230,6 +379,13 @@ impl TokenType {
             return TokenType::Please;
         }
 
+        // Check for negation ("ne … pas"; "plus" is only a closer after "ne")
+        if NEGATION_OPENERS_FR.contains(&token_lower.replace('’', "'").as_str())
+            || (NEGATION_CLOSERS_FR.contains(&token_lower.as_str()) && token_lower != "plus")
+        {
+            return TokenType::Negation;
+        }
+
         // Check for French verbs
         if Self::is_french_verb(&token_lower) {
             return TokenType::Verb;
@@ -240,6 +396,19 @@ impl TokenType {
             return TokenType::Preposition;
         }
 
+        // Check for adjectives and adverbs
+        if Self::is_french_adjective(&token_lower) {
+            return TokenType::Adjective;
+        }
+        if Self::is_french_adverb(&token_lower) {
+            return TokenType::Adverb;
+        }
+
+        // Check for conjunctions
+        if Self::is_french_conjunction(&token_lower) {
+            return TokenType::Conjunction;
+        }
+
         // Check for punctuation
         if Self::is_english_punctuation(token) {
             return TokenType::Punctuation;
@@ -278,6 +447,46 @@ impl TokenType {
         ];
         PREPOSITIONS.contains(&token)
     }
+
+    /// Check if token is a common French adjective
+    fn is_french_adjective(token: &str) -> bool {
+        const ADJECTIVES: &[&str] = &[
+            "court", "courte", "bref", "brève", "long", "longue",
+            "détaillé", "détaillée", "simple", "clair", "claire",
+            "concis", "concise", "important", "importante", "complet",
+            "complète", "précis", "précise", "formel", "formelle",
+            "nouveau", "nouvelle", "petit", "petite", "grand", "grande",
+            "bon", "bonne", "beau", "belle", "vieux", "vieille", "jeune",
+        ];
+        ADJECTIVES.contains(&token)
+    }
+
+    /// Check if a French adjective normally goes before the noun
+    /// (beauty, age, goodness and size: `un nouveau fichier`)
+    pub fn is_french_prenominal(token: &str) -> bool {
+        const PRENOMINAL: &[&str] = &[
+            "nouveau", "nouvelle", "petit", "petite", "grand", "grande",
+            "bon", "bonne", "beau", "belle", "vieux", "vieille", "jeune",
+            "long", "longue", "bref", "brève",
+        ];
+        PRENOMINAL.contains(&token.to_lowercase().as_str())
+    }
+
+    /// Check if token is a common French adverb
+    fn is_french_adverb(token: &str) -> bool {
+        const ADVERBS: &[&str] = &[
+            "brièvement", "clairement", "simplement", "rapidement",
+            "précisément", "soigneusement", "poliment", "complètement",
+            "toujours", "aussi",
+        ];
+        ADVERBS.contains(&token)
+    }
+
+    /// Check if token is a French conjunction
+    fn is_french_conjunction(token: &str) -> bool {
+        const CONJUNCTIONS: &[&str] = &["et", "puis", "ensuite", "ou", "mais"];
+        CONJUNCTIONS.contains(&token)
+    }
 }
 
 // ============================================================================
@@ -332,6 +541,72 @@ pub enum ValidationErrorCode {
     PeriodNotAtEnd,
     /// EN Rule 7: Missing verb in sentence
     MissingVerb,
+
+    // File Inclusion (_FILE:path)
+    /// Included file does not exist (or relative path without a saved project)
+    FileNotFound,
+    /// Included file exceeds the size limit
+    FileTooLarge,
+    /// Included file is not valid UTF-8 text
+    FileNotUtf8,
+    /// Included file is outside the project directory
+    FileOutsideProject,
+
+    // Few-shot Examples (_IN:"..." _OUT:"...")
+    /// Example input or output is empty
+    ExampleEmpty,
+    /// Example input without output, or output without input
+    ExampleUnbalanced,
+
+    // Output Constraints (_FMT:, _MAXWORDS:, _LANG:, _TONE:)
+    /// Same constraint given twice with different values
+    ConflictingConstraints,
+    /// Constraint value is empty or not valid (e.g. non-numeric word limit)
+    InvalidConstraintValue,
+
+    // Conditional Sections (_IF:var ... _ELSE ... _ENDIF)
+    /// `_IF:` without `_ENDIF`, or `_ELSE` / `_ENDIF` without `_IF:`
+    UnbalancedCondition,
+    /// Condition refers to a variable that the project does not define
+    UnknownCondition,
+
+    // Sub-prompt Inclusion (_INCLUDE:path.promps)
+    /// Included project does not exist or cannot be loaded
+    IncludeNotFound,
+    /// Included project is outside the project directory
+    IncludeOutsideProject,
+    /// Projects include each other
+    IncludeCycle,
+
+    // Project Glossary
+    /// Noun is not defined in the glossary (strict glossary mode)
+    UndefinedNoun,
+
+    // Noun Consistency
+    /// Noun is a likely variant spelling of another noun in the prompt
+    InconsistentNoun,
+
+    // Modifiers (_ADJ: / _ADV:)
+    /// Adjective on the unusual side of its noun for the locale
+    AdjectivePosition,
+    /// Adverb without a following verb (Japanese)
+    AdverbPosition,
+
+    // Negation
+    /// Negation not followed by a verb ("do not the report")
+    NegationWithoutVerb,
+    /// French "ne" without "pas" (or "pas" without "ne")
+    IncompleteNegation,
+
+    // Compound Sentences
+    /// Conjunction with no clause after it ("要約して そして 。", "and" at the end)
+    DanglingConjunction,
+
+    // List Sections (_LIST ... _ENDLIST)
+    /// `_LIST` without `_ENDLIST`, `_ENDLIST` without `_LIST`, or nested `_LIST`
+    UnbalancedList,
+    /// List without items, or an empty item between two separators
+    EmptyListItem,
 }
 
 /// Auto-fix action type
@@ -342,6 +617,8 @@ pub enum AutoFixActionType {
     InsertBefore,
     /// Insert a block after the target position
     InsertAfter,
+    /// Replace the text of the block at the target position
+    Replace,
 }
 
 /// Auto-fix action for automatic error correction
@@ -356,6 +633,9 @@ pub struct AutoFixAction {
     pub target_position: usize,
     /// Label for the fix button
     pub label: String,
+    /// New block text (Replace only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
 }
 
 /// A single validation error or warning
@@ -429,6 +709,21 @@ pub struct ValidationResult {
     pub error_count: usize,
     /// Count of warnings
     pub warning_count: usize,
+    /// Locale detected for each sentence (only when validating with detection)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detected_locales: Vec<SentenceLocale>,
+}
+
+/// Locale detected for a sentence
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentenceLocale {
+    /// First token position of the sentence
+    pub start: usize,
+    /// Position after the last token of the sentence
+    pub end: usize,
+    /// Locale code ("ja", "en", "fr") the sentence was validated with
+    pub locale: String,
 }
 
 impl ValidationResult {
@@ -439,6 +734,7 @@ impl ValidationResult {
             errors: Vec::new(),
             error_count: 0,
             warning_count: 0,
+            detected_locales: Vec::new(),
         }
     }
 
@@ -478,15 +774,21 @@ impl Default for ValidationResult {
 /// # Rules
 /// 1. Particle must follow a noun (Error)
 /// 2. No consecutive particles (Error)
-/// 3. Verb should be at end (Warning)
+/// 3. Verb should be at end of its clause (Warning)
 /// 4. Consecutive nouns without particle (Warning)
 /// 5. Missing subject - no が with verb (Warning)
-/// 6. Missing object - no を with verb (Warning)
+/// 6. Missing object - no を with verb, per clause (Warning)
+/// 10. Adverb should come before a verb (Warning)
+/// 11. Conjunction should be followed by a clause (Warning)
+///
+/// Compound sentences are split into clauses at conjunctions (そして) and
+/// after a て-form verb that is followed by another verb (読み込んで 、 要約して).
 pub fn validate_sequence(input: &str) -> ValidationResult {
     let mut result = ValidationResult::new();
 
     // Tokenize input
-    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let owned_tokens = split_tokens(input);
+    let tokens: Vec<&str> = owned_tokens.iter().map(|t| t.as_str()).collect();
 
     if tokens.is_empty() {
         return result;
@@ -522,6 +824,7 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
                             block_type: "promps_noun".to_string(),
                             target_position: i,
                             label: "名詞を追加".to_string(),
+                            replacement: None,
                         },
                     ));
                 }
@@ -541,6 +844,7 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
                             block_type: "promps_noun".to_string(),
                             target_position: i,
                             label: "名詞を追加".to_string(),
+                            replacement: None,
                         },
                     ));
                 }
@@ -565,6 +869,7 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
                         block_type: "promps_particle_to".to_string(),
                         target_position: i,
                         label: "「と」を追加".to_string(),
+                        replacement: None,
                     },
                 ));
             }
@@ -585,8 +890,12 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
                             Severity::Error,
                             Some("読点を削除するか、別の助詞を使用してください".to_string()),
                         ));
-                    } else if prev_type != Some(TokenType::Particle) {
-                        // Rule 8: Touten not after particle
+                    } else if prev_type != Some(TokenType::Particle)
+                        && prev_type != Some(TokenType::Conjunction)
+                        && !(prev_type == Some(TokenType::Verb) && TokenType::is_te_form(prev_token))
+                    {
+                        // Rule 8: Touten not after particle (or a て-form
+                        // verb / conjunction joining clauses)
                         result.add_error(ValidationError::new(
                             ValidationErrorCode::ToutenNotAfterParticle,
                             "読点「、」は助詞の後でのみ使用できます".to_string(),
@@ -624,12 +933,15 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
         prev_type = Some(token_type);
     }
 
-    // Rule 3: Verb should be at end (check after loop)
-    // Find all verb positions
-    for (i, _token, token_type) in classified.iter() {
-        if *token_type == TokenType::Verb && *i < tokens.len() - 1 {
+    // Rule 3: Verb should be at end of its clause (check after loop)
+    let clauses = split_clauses(&classified, "ja");
+    for clause in &clauses {
+        for (i, _token, token_type) in &classified[clause.clone()] {
+            if *token_type != TokenType::Verb {
+                continue;
+            }
             // Check if there are non-particle tokens after this verb
-            let has_significant_after = classified[*i + 1..]
+            let has_significant_after = classified[*i + 1..clause.end]
                 .iter()
                 .any(|(_, _, t)| *t == TokenType::Noun || *t == TokenType::Verb);
 
@@ -645,6 +957,24 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
         }
     }
 
+    // Rule 10: Adverb should come before a verb (詳しく 説明して)
+    for (i, token, token_type) in classified.iter() {
+        if *token_type == TokenType::Adverb
+            && !classified[*i + 1..].iter().any(|(_, _, t)| *t == TokenType::Verb)
+        {
+            result.add_error(ValidationError::new(
+                ValidationErrorCode::AdverbPosition,
+                format!("副詞「{}」の後に動詞がありません", modifier_text(token)),
+                *i,
+                Severity::Warning,
+                Some("副詞を動詞の前に移動してください".to_string()),
+            ));
+        }
+    }
+
+    // Rule 11: Conjunction should be followed by a clause
+    add_dangling_conjunctions(&mut result, &classified, "ja");
+
     // Check for presence of verb (needed for Rules 5 and 6)
     let has_verb = classified.iter().any(|(_, _, t)| *t == TokenType::Verb);
 
@@ -656,7 +986,7 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
             .map(|(i, _, _)| *i)
             .unwrap_or(0);
 
-        // Rule 5: Missing subject (no が with verb)
+        // Rule 5: Missing subject (no が with verb), shared by all clauses
         let has_ga = tokens.iter().any(|t| *t == "が");
         if !has_ga {
             result.add_error(ValidationError::with_autofix(
@@ -670,13 +1000,26 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
                     block_type: "promps_particle_ga".to_string(),
                     target_position: 0,
                     label: "「が」を追加".to_string(),
+                    replacement: None,
                 },
             ));
         }
+    }
 
-        // Rule 6: Missing object (no を with verb)
-        let has_wo = tokens.iter().any(|t| *t == "を");
-        if !has_wo {
+    // Rule 6: Missing object (no を with verb), per clause; a clause may
+    // share the object of an earlier one in the same sentence
+    // (_N:ファイル を 読み込んで 、 要約して)
+    for clause in &clauses {
+        let sentence_start = tokens[..clause.start]
+            .iter()
+            .rposition(|t| is_sentence_final(t))
+            .map_or(0, |p| p + 1);
+        let has_wo = tokens[sentence_start..clause.end].iter().any(|t| *t == "を");
+        let verb_pos = classified[clause.clone()]
+            .iter()
+            .find(|(_, _, t)| *t == TokenType::Verb)
+            .map(|(i, _, _)| *i);
+        if let (Some(verb_pos), false) = (verb_pos, has_wo) {
             result.add_error(ValidationError::with_autofix(
                 ValidationErrorCode::MissingObject,
                 "目的語がありません（「を」がありません）".to_string(),
@@ -688,6 +1031,7 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
                     block_type: "promps_particle_wo".to_string(),
                     target_position: verb_pos,
                     label: "「を」を追加".to_string(),
+                    replacement: None,
                 },
             ));
         }
@@ -696,6 +1040,172 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
     result
 }
 
+/// Check if a token type is a modifier (adjective or adverb)
+fn is_modifier(token_type: TokenType) -> bool {
+    matches!(token_type, TokenType::Adjective | TokenType::Adverb)
+}
+
+/// Modifier text without its `_ADJ:` / `_ADV:` marker (for messages)
+fn modifier_text(token: &str) -> &str {
+    token
+        .strip_prefix("_ADJ:")
+        .or_else(|| token.strip_prefix("_ADV:"))
+        .unwrap_or(token)
+}
+
+/// Check if a token ends a sentence (。 . ! ?, including full-width forms)
+fn is_sentence_final(token: &str) -> bool {
+    TokenType::is_period(token) || matches!(normalize_token(token, "en").as_str(), "!" | "?")
+}
+
+/// Check if the conjunction at `i` starts a new clause
+///
+/// Japanese conjunctions and "then" / "puis" / "ensuite" always do; "and",
+/// "et" and the like only when an action follows ("read the file and
+/// summarize it"), not when joining nouns ("the report and the summary").
+fn conjunction_starts_clause(classified: &[(usize, &str, TokenType)], i: usize, locale: &str) -> bool {
+    const SEQUENCE_WORDS: &[&str] = &["then", "puis", "ensuite"];
+
+    if locale == "ja" || SEQUENCE_WORDS.contains(&classified[i].1.to_lowercase().as_str()) {
+        return true;
+    }
+    classified[i + 1..]
+        .iter()
+        .map(|(_, _, t)| *t)
+        .find(|t| !matches!(t, TokenType::Please | TokenType::Adverb | TokenType::Negation))
+        == Some(TokenType::Verb)
+}
+
+/// Split classified tokens into clauses
+///
+/// Clauses end at sentence-final punctuation and before a conjunction that
+/// starts a new action. In Japanese, a て-form verb followed by another verb
+/// in the same sentence also ends its clause, together with a following 、
+/// (`_N:ファイル を 読み込んで 、 要約して`).
+///
+/// # Returns
+/// Token ranges, covering the whole input in order
+fn split_clauses(classified: &[(usize, &str, TokenType)], locale: &str) -> Vec<Range<usize>> {
+    let mut clauses = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < classified.len() {
+        let (_, token, token_type) = classified[i];
+
+        if token_type == TokenType::Conjunction && i > start && conjunction_starts_clause(classified, i, locale) {
+            clauses.push(start..i);
+            start = i;
+        }
+
+        let mut end = None;
+        if is_sentence_final(token) {
+            end = Some(i + 1);
+        } else if locale == "ja" && token_type == TokenType::Verb && TokenType::is_te_form(token) {
+            let verb_follows = classified[i + 1..]
+                .iter()
+                .take_while(|(_, t, _)| !is_sentence_final(t))
+                .any(|(_, _, t)| *t == TokenType::Verb);
+            if verb_follows {
+                let touten = classified.get(i + 1).is_some_and(|(_, t, _)| TokenType::is_touten(t));
+                end = Some(if touten { i + 2 } else { i + 1 });
+            }
+        }
+
+        if let Some(end) = end {
+            clauses.push(start..end);
+            start = end;
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+    if start < classified.len() {
+        clauses.push(start..classified.len());
+    }
+
+    clauses
+}
+
+/// Split a sentence into clauses and flag the prohibitions
+///
+/// Used for the prohibitions section of the prompt (see
+/// `promps::ProhibitionFinder`). The sentence locale is detected, falling
+/// back to `locale`. A clause is a prohibition when it has a Japanese
+/// negative request (〜しないでください), an English negation right before a
+/// verb ("do not translate", not "the API, not the CLI"), or a French
+/// `ne … pas` (`n'importe quel` is not one).
+pub fn prohibition_clauses(tokens: &[String], locale: &str) -> Vec<(Range<usize>, bool)> {
+    let locale = detect_locale(&tokenize(&tokens.join(" "))).unwrap_or(locale);
+    let words: Vec<&str> = tokens.iter().map(String::as_str).collect();
+    let classified: Vec<(usize, &str, TokenType)> = words
+        .iter()
+        .enumerate()
+        .map(|(i, token)| {
+            let token_type = match locale {
+                "en" => TokenType::classify_en(token),
+                "fr" => TokenType::classify_fr(token),
+                _ => TokenType::classify(token),
+            };
+            (i, *token, token_type)
+        })
+        .collect();
+
+    split_clauses(&classified, locale)
+        .into_iter()
+        .map(|range| {
+            let negated = range.clone().any(|i| match locale {
+                "ja" => {
+                    classified[i].2 == TokenType::Verb
+                        && NEGATIVE_ENDINGS_JA.iter().any(|ending| words[i].ends_with(ending))
+                }
+                "fr" => is_negation_opener_fr(&words[range.clone()], i - range.start),
+                _ => {
+                    classified[i].2 == TokenType::Negation
+                        && classified[i + 1..range.end]
+                            .iter()
+                            .map(|(_, _, t)| *t)
+                            .find(|t| *t != TokenType::Adverb)
+                            == Some(TokenType::Verb)
+                }
+            });
+            (range, negated)
+        })
+        .collect()
+}
+
+/// Warn about conjunctions with no clause after them
+fn add_dangling_conjunctions(result: &mut ValidationResult, classified: &[(usize, &str, TokenType)], locale: &str) {
+    for (i, token, token_type) in classified {
+        let dangling = *token_type == TokenType::Conjunction
+            && classified.get(*i + 1).is_none_or(|(_, t, _)| is_sentence_final(t));
+        if !dangling {
+            continue;
+        }
+        let (message, suggestion) = match locale {
+            "en" => (
+                format!("'{}' should be followed by another action", token),
+                "Add the next step after the conjunction, or remove it",
+            ),
+            "fr" => (
+                format!("« {} » doit être suivi d'une autre action", token),
+                "Ajoutez l'étape suivante après la conjonction, ou supprimez-la",
+            ),
+            _ => (
+                format!("接続詞「{}」の後に文がありません", token),
+                "接続詞の後に次の動作を追加するか、削除してください",
+            ),
+        };
+        result.add_error(ValidationError::new(
+            ValidationErrorCode::DanglingConjunction,
+            message,
+            *i,
+            Severity::Warning,
+            Some(suggestion.to_string()),
+        ));
+    }
+}
+
 // ============================================================================
 // English Validation (英語バリデーション)
 // ============================================================================
@@ -708,7 +1218,598 @@ pub fn validate_sequence(input: &str) -> ValidationResult {
 ///
 /// # Returns
 /// ValidationResult with any errors/warnings found
+///
+/// Chat role sections (`_ROLE:system` ...) are validated independently,
+/// with positions relative to the whole input.
 pub fn validate_sequence_with_locale(input: &str, locale: &str) -> ValidationResult {
+    let tokens = tokenize(input);
+    let role_positions: Vec<usize> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| token_role(t).is_some())
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut result = if role_positions.is_empty() {
+        validate_section(input, locale)
+    } else {
+        validate_role_sections(input, &tokens, &role_positions, locale)
+    };
+
+    add_whole_input_errors(&mut result, &tokens, locale);
+    result
+}
+
+/// Validate a DSL token sequence, detecting the locale of each sentence
+///
+/// # Arguments
+/// * `input` - Space-delimited DSL tokens
+///
+/// # Returns
+/// ValidationResult with any errors/warnings found and the detected
+/// locale of each sentence (`detected_locales`)
+///
+/// Sentences without any hint take the locale of the previous sentence
+/// (the first one defaults to Japanese). Checks over the whole input use
+/// the locale of the most tokens.
+pub fn validate_sequence_auto(input: &str) -> ValidationResult {
+    let tokens = tokenize(input);
+    let mut result = ValidationResult::new();
+    let mut previous = DEFAULT_LOCALE;
+
+    for range in sentence_ranges(&tokens) {
+        let locale = detect_locale(&tokens[range.clone()]).unwrap_or(previous);
+        add_range_errors(&mut result, input, &tokens, range.clone(), locale);
+        result.detected_locales.push(SentenceLocale {
+            start: range.start,
+            end: range.end,
+            locale: locale.to_string(),
+        });
+        previous = locale;
+    }
+
+    // Locale of the most tokens (earlier locales win ties)
+    let mut dominant = DEFAULT_LOCALE;
+    let mut dominant_tokens = 0;
+    for locale in ["ja", "en", "fr"] {
+        let count: usize = result
+            .detected_locales
+            .iter()
+            .filter(|s| s.locale == locale)
+            .map(|s| s.end - s.start)
+            .sum();
+        if count > dominant_tokens {
+            dominant = locale;
+            dominant_tokens = count;
+        }
+    }
+    add_whole_input_errors(&mut result, &tokens, dominant);
+    result
+}
+
+/// Add the checks over the whole input (examples, constraints, conditions)
+fn add_whole_input_errors(result: &mut ValidationResult, tokens: &[Token], locale: &str) {
+    for error in validate_examples(tokens, locale)
+        .into_iter()
+        .chain(validate_constraints(tokens, locale))
+        .chain(validate_conditions(tokens, locale))
+        .chain(validate_lists(tokens, locale))
+    {
+        result.add_error(error);
+    }
+}
+
+/// Validate a token range on its own, with positions relative to the whole input
+fn add_range_errors(result: &mut ValidationResult, input: &str, tokens: &[Token], range: Range<usize>, locale: &str) {
+    if range.is_empty() {
+        return;
+    }
+    let section = &input[tokens[range.start].start..tokens[range.end - 1].end];
+    for mut error in validate_section(section, locale).errors {
+        error.position += range.start;
+        if let Some(autofix) = error.autofix.as_mut() {
+            autofix.target_position += range.start;
+        }
+        result.add_error(error);
+    }
+}
+
+/// Validate the sections between `_ROLE:` markers independently
+fn validate_role_sections(input: &str, tokens: &[Token], role_positions: &[usize], locale: &str) -> ValidationResult {
+    // Token ranges between role markers
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for &pos in role_positions {
+        ranges.push(start..pos);
+        start = pos + 1;
+    }
+    ranges.push(start..tokens.len());
+
+    let mut result = ValidationResult::new();
+    for range in ranges {
+        add_range_errors(&mut result, input, tokens, range, locale);
+    }
+    result
+}
+
+/// Check output constraints
+///
+/// - A constraint kind given twice with different values conflicts
+/// - Values must not be empty; `_MAXWORDS:` must be a positive integer
+fn validate_constraints(tokens: &[Token], locale: &str) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut seen: Vec<(ConstraintKind, &str)> = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        let Some(kind) = token.marker.as_deref().and_then(ConstraintKind::from_marker) else {
+            continue;
+        };
+        let value = token.text.trim();
+        let marker = kind.marker();
+
+        let invalid = value.is_empty()
+            || (kind == ConstraintKind::MaxWords && !value.parse::<u32>().is_ok_and(|n| n > 0));
+        if invalid {
+            let (message, suggestion) = match locale {
+                "en" => (
+                    format!("Invalid value '{}' for _{}:", value, marker),
+                    if kind == ConstraintKind::MaxWords { "Use a positive number (e.g. _MAXWORDS:200)" } else { "Enter a value" },
+                ),
+                "fr" => (
+                    format!("Valeur '{}' invalide pour _{}:", value, marker),
+                    if kind == ConstraintKind::MaxWords { "Utilisez un nombre positif (ex. _MAXWORDS:200)" } else { "Saisissez une valeur" },
+                ),
+                _ => (
+                    format!("_{}: の値「{}」が不正です", marker, value),
+                    if kind == ConstraintKind::MaxWords { "正の数を指定してください（例: _MAXWORDS:200）" } else { "値を指定してください" },
+                ),
+            };
+            errors.push(ValidationError::new(
+                ValidationErrorCode::InvalidConstraintValue,
+                message,
+                i,
+                Severity::Error,
+                Some(suggestion.to_string()),
+            ));
+            continue;
+        }
+
+        match seen.iter().find(|(k, _)| *k == kind) {
+            Some((_, first)) if !first.eq_ignore_ascii_case(value) => {
+                let (message, suggestion) = match locale {
+                    "en" => (
+                        format!("_{}:{} conflicts with _{}:{}", marker, value, marker, first),
+                        "Keep only one of them",
+                    ),
+                    "fr" => (
+                        format!("_{}:{} est en conflit avec _{}:{}", marker, value, marker, first),
+                        "N'en gardez qu'un seul",
+                    ),
+                    _ => (
+                        format!("_{}:{} と _{}:{} が矛盾しています", marker, value, marker, first),
+                        "どちらか一方を削除してください",
+                    ),
+                };
+                errors.push(ValidationError::new(
+                    ValidationErrorCode::ConflictingConstraints,
+                    message,
+                    i,
+                    Severity::Error,
+                    Some(suggestion.to_string()),
+                ));
+            }
+            Some(_) => {}
+            None => seen.push((kind, value)),
+        }
+    }
+
+    errors
+}
+
+/// Check that conditional sections are balanced
+///
+/// Every `_IF:` needs an `_ENDIF`, with at most one `_ELSE` in between.
+/// An `_IF:` opened inside a list must end before the list does.
+fn validate_conditions(tokens: &[Token], locale: &str) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    // Open sections: (position of _IF:, seen _ELSE, opened inside a list)
+    let mut open: Vec<(usize, bool, bool)> = Vec::new();
+    let mut in_list = false;
+
+    let unbalanced = |position: usize, keyword: &str| {
+        let (message, suggestion) = match (locale, keyword) {
+            ("en", "_IF") => ("Condition _IF: has no _ENDIF".to_string(), "Add _ENDIF at the end of the section"),
+            ("en", _) => (format!("{} without a matching _IF:", keyword), "Remove it or add an _IF: before it"),
+            ("fr", "_IF") => ("La condition _IF: n'a pas de _ENDIF".to_string(), "Ajoutez _ENDIF à la fin de la section"),
+            ("fr", _) => (format!("{} sans _IF: correspondant", keyword), "Supprimez-le ou ajoutez un _IF: avant"),
+            (_, "_IF") => ("条件 _IF: に対応する _ENDIF がありません".to_string(), "セクションの最後に _ENDIF を追加してください"),
+            (_, _) => (format!("{} に対応する _IF: がありません", keyword), "削除するか、前に _IF: を追加してください"),
+        };
+        ValidationError::new(
+            ValidationErrorCode::UnbalancedCondition,
+            message,
+            position,
+            Severity::Error,
+            Some(suggestion.to_string()),
+        )
+    };
+
+    for (i, token) in tokens.iter().enumerate() {
+        if is_list_start(token) {
+            in_list = true;
+        } else if in_list && (is_keyword_token(token, "_ENDLIST") || token_role(token).is_some()) {
+            // The end of the list also ends the sections opened inside it
+            while let Some(&(position, _, true)) = open.last() {
+                errors.push(unbalanced(position, "_IF"));
+                open.pop();
+            }
+            in_list = false;
+        } else if token.marker.as_deref() == Some("IF") {
+            open.push((i, false, in_list));
+        } else if is_keyword_token(token, "_ELSE") {
+            match open.last_mut() {
+                Some((_, seen_else @ false, _)) => *seen_else = true,
+                _ => errors.push(unbalanced(i, "_ELSE")),
+            }
+        } else if is_keyword_token(token, "_ENDIF") {
+            match open.pop() {
+                // Also ends a list opened inside the section (see validate_lists)
+                Some((_, _, false)) => in_list = false,
+                Some(_) => {}
+                None => errors.push(unbalanced(i, "_ENDIF")),
+            }
+        }
+    }
+    for (position, _, _) in open {
+        errors.push(unbalanced(position, "_IF"));
+    }
+
+    errors
+}
+
+/// Check list sections
+///
+/// - `_LIST` needs an `_ENDLIST` before the next `_ROLE:` and before the end
+///   of the `_IF:` section it is in, lists do not nest, and `_ENDLIST` needs
+///   a `_LIST` (Error)
+/// - A list needs items, and separators must not leave an empty item (Warning)
+fn validate_lists(tokens: &[Token], locale: &str) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    // Open list: (position of _LIST, has items, current item is empty, _IF: depth)
+    let mut open: Option<(usize, bool, bool, usize)> = None;
+    let mut if_depth = 0;
+
+    let unbalanced = |position: usize, keyword: &str| {
+        let (message, suggestion) = match (locale, keyword) {
+            ("en", "_LIST") => ("List _LIST has no _ENDLIST", "Add _ENDLIST after the last item"),
+            ("en", "nested") => ("Lists cannot be nested", "Close the list with _ENDLIST first"),
+            ("en", _) => ("_ENDLIST without a matching _LIST", "Remove it or add a _LIST before it"),
+            ("fr", "_LIST") => ("La liste _LIST n'a pas de _ENDLIST", "Ajoutez _ENDLIST après le dernier élément"),
+            ("fr", "nested") => ("Les listes ne peuvent pas être imbriquées", "Fermez d'abord la liste avec _ENDLIST"),
+            ("fr", _) => ("_ENDLIST sans _LIST correspondant", "Supprimez-le ou ajoutez un _LIST avant"),
+            (_, "_LIST") => ("リスト _LIST に対応する _ENDLIST がありません", "最後の項目の後に _ENDLIST を追加してください"),
+            (_, "nested") => ("リストの中に _LIST は使えません", "先に _ENDLIST でリストを閉じてください"),
+            (_, _) => ("_ENDLIST に対応する _LIST がありません", "削除するか、前に _LIST を追加してください"),
+        };
+        ValidationError::new(
+            ValidationErrorCode::UnbalancedList,
+            message.to_string(),
+            position,
+            Severity::Error,
+            Some(suggestion.to_string()),
+        )
+    };
+    let empty = |position: usize, whole_list: bool| {
+        let (message, suggestion) = match (locale, whole_list) {
+            ("en", true) => ("List has no items", "Add items between _LIST and _ENDLIST"),
+            ("en", false) => ("Empty list item", "Remove the extra separator"),
+            ("fr", true) => ("La liste n'a aucun élément", "Ajoutez des éléments entre _LIST et _ENDLIST"),
+            ("fr", false) => ("Élément de liste vide", "Supprimez le séparateur en trop"),
+            (_, true) => ("リストに項目がありません", "_LIST と _ENDLIST の間に項目を追加してください"),
+            (_, false) => ("リストの項目が空です", "余分な区切りを削除してください"),
+        };
+        ValidationError::new(
+            ValidationErrorCode::EmptyListItem,
+            message.to_string(),
+            position,
+            Severity::Warning,
+            Some(suggestion.to_string()),
+        )
+    };
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.marker.as_deref() == Some("IF") {
+            if_depth += 1;
+        } else if is_keyword_token(token, "_ELSE") || is_keyword_token(token, "_ENDIF") {
+            // _ELSE / _ENDIF of a section opened before the list also end the list
+            if let Some((start, _, _, depth)) = open {
+                if depth == if_depth && depth > 0 {
+                    errors.push(unbalanced(start, "_LIST"));
+                    open = None;
+                }
+            }
+            if is_keyword_token(token, "_ENDIF") {
+                if_depth = if_depth.saturating_sub(1);
+            }
+        }
+
+        if let Some((start, has_items, item_empty, depth)) = open.as_mut() {
+            // The end of the list also ends the sections opened inside it
+            if is_keyword_token(token, "_ENDLIST") {
+                if !*has_items {
+                    errors.push(empty(*start, true));
+                }
+                if_depth = *depth;
+                open = None;
+            } else if token_role(token).is_some() {
+                errors.push(unbalanced(*start, "_LIST"));
+                if_depth = *depth;
+                open = None;
+            } else if is_list_start(token) {
+                errors.push(unbalanced(i, "nested"));
+            } else if is_list_separator(token) {
+                if *item_empty {
+                    errors.push(empty(i, false));
+                }
+                *item_empty = true;
+            } else {
+                *has_items = true;
+                *item_empty = false;
+            }
+        } else if is_list_start(token) {
+            open = Some((i, false, true, if_depth));
+        } else if is_keyword_token(token, "_ENDLIST") {
+            errors.push(unbalanced(i, "_ENDLIST"));
+        }
+    }
+    if let Some((start, _, _, _)) = open {
+        errors.push(unbalanced(start, "_LIST"));
+    }
+
+    errors
+}
+
+/// Check that every `_IF:` condition refers to a defined variable
+///
+/// # Arguments
+/// * `input` - DSL text
+/// * `variables` - Variables defined in the project
+/// * `locale` - Locale code for messages ("ja", "en", "fr")
+///
+/// # Returns
+/// One warning per condition on an unknown variable (it evaluates to false)
+pub fn validate_condition_variables(
+    input: &str,
+    variables: &HashMap<String, bool>,
+    locale: &str,
+) -> Vec<ValidationError> {
+    tokenize(input)
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| token.marker.as_deref() == Some("IF"))
+        .filter_map(|(i, token)| {
+            let condition = Condition::parse(&token.text);
+            if variables.contains_key(&condition.variable) {
+                return None;
+            }
+            let (message, suggestion) = match locale {
+                "en" => (
+                    format!("Unknown variable '{}' in _IF: (treated as false)", condition.variable),
+                    "Define the variable in the project or fix its name",
+                ),
+                "fr" => (
+                    format!("Variable inconnue '{}' dans _IF: (considérée comme fausse)", condition.variable),
+                    "Définissez la variable dans le projet ou corrigez son nom",
+                ),
+                _ => (
+                    format!("_IF: の変数「{}」が未定義です（偽として扱います）", condition.variable),
+                    "プロジェクトで変数を定義するか、名前を修正してください",
+                ),
+            };
+            Some(ValidationError::new(
+                ValidationErrorCode::UnknownCondition,
+                message,
+                i,
+                Severity::Warning,
+                Some(suggestion.to_string()),
+            ))
+        })
+        .collect()
+}
+
+/// Check that every noun is defined in the glossary (strict mode only)
+///
+/// # Arguments
+/// * `input` - DSL text
+/// * `glossary` - Project glossary
+/// * `locale` - Locale code for messages ("ja", "en", "fr")
+///
+/// # Returns
+/// One warning per undefined noun (first use only)
+pub fn validate_glossary_nouns(input: &str, glossary: &Glossary, locale: &str) -> Vec<ValidationError> {
+    if !glossary.strict {
+        return Vec::new();
+    }
+
+    let mut reported: Vec<String> = Vec::new();
+    let mut errors = Vec::new();
+    for (i, token) in tokenize(input).iter().enumerate() {
+        let noun = token.text.trim();
+        if !token.is_noun() || glossary.lookup(noun).is_some() || reported.iter().any(|n| n == noun) {
+            continue;
+        }
+        reported.push(noun.to_string());
+
+        let (message, suggestion) = match locale {
+            "en" => (
+                format!("Noun '{}' is not in the glossary", noun),
+                "Add a definition to the project glossary",
+            ),
+            "fr" => (
+                format!("Le nom '{}' n'est pas dans le glossaire", noun),
+                "Ajoutez une définition au glossaire du projet",
+            ),
+            _ => (
+                format!("名詞「{}」が用語集に定義されていません", noun),
+                "プロジェクトの用語集に定義を追加してください",
+            ),
+        };
+        errors.push(ValidationError::new(
+            ValidationErrorCode::UndefinedNoun,
+            message,
+            i,
+            Severity::Warning,
+            Some(suggestion.to_string()),
+        ));
+    }
+
+    errors
+}
+
+/// Check few-shot example pairs
+///
+/// An `_OUT:` must directly follow its `_IN:`; both sides should have text.
+fn validate_examples(tokens: &[Token], locale: &str) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut pending_input: Option<usize> = None;
+
+    let unbalanced = |position: usize, missing_output: bool| {
+        let (message, suggestion) = match (locale, missing_output) {
+            ("en", true) => ("Example input has no output (_OUT:)", "Add an _OUT: right after the input"),
+            ("en", false) => ("Example output has no input (_IN:)", "Add an _IN: right before the output"),
+            ("fr", true) => ("L'entrée de l'exemple n'a pas de sortie (_OUT:)", "Ajoutez un _OUT: juste après l'entrée"),
+            ("fr", false) => ("La sortie de l'exemple n'a pas d'entrée (_IN:)", "Ajoutez un _IN: juste avant la sortie"),
+            (_, true) => ("例の入力（_IN:）に対応する出力（_OUT:）がありません", "入力の直後に _OUT: を追加してください"),
+            (_, false) => ("例の出力（_OUT:）の前に入力（_IN:）がありません", "出力の直前に _IN: を追加してください"),
+        };
+        ValidationError::new(
+            ValidationErrorCode::ExampleUnbalanced,
+            message,
+            position,
+            Severity::Error,
+            Some(suggestion.to_string()),
+        )
+    };
+    let empty = |position: usize, is_input: bool| {
+        let (message, suggestion) = match (locale, is_input) {
+            ("en", true) => ("Example input is empty", "Enter the example input"),
+            ("en", false) => ("Example output is empty", "Enter the expected output"),
+            ("fr", true) => ("L'entrée de l'exemple est vide", "Saisissez l'entrée de l'exemple"),
+            ("fr", false) => ("La sortie de l'exemple est vide", "Saisissez la sortie attendue"),
+            (_, true) => ("例の入力が空です", "例の入力を記入してください"),
+            (_, false) => ("例の出力が空です", "期待する出力を記入してください"),
+        };
+        ValidationError::new(
+            ValidationErrorCode::ExampleEmpty,
+            message,
+            position,
+            Severity::Warning,
+            Some(suggestion.to_string()),
+        )
+    };
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token.marker.as_deref() {
+            Some("IN") => {
+                if let Some(p) = pending_input {
+                    errors.push(unbalanced(p, true));
+                }
+                if token.text.trim().is_empty() {
+                    errors.push(empty(i, true));
+                }
+                pending_input = Some(i);
+            }
+            Some("OUT") => {
+                if pending_input.is_none() {
+                    errors.push(unbalanced(i, false));
+                }
+                if token.text.trim().is_empty() {
+                    errors.push(empty(i, false));
+                }
+                pending_input = None;
+            }
+            _ => {
+                if let Some(p) = pending_input.take() {
+                    errors.push(unbalanced(p, true));
+                }
+            }
+        }
+    }
+    if let Some(p) = pending_input {
+        errors.push(unbalanced(p, true));
+    }
+
+    errors
+}
+
+/// Token ranges of the list sections (`_LIST` to `_ENDLIST` inclusive,
+/// or to the end of the input when unclosed)
+fn list_ranges(tokens: &[Token]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match start {
+            None if is_list_start(token) => start = Some(i),
+            Some(s) if is_keyword_token(token, "_ENDLIST") => {
+                ranges.push(s..i + 1);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        ranges.push(s..tokens.len());
+    }
+
+    ranges
+}
+
+/// Validate a single section with the locale's rules
+///
+/// Each list section stands for a single noun in the sentence around it
+/// (`_N:AI が _LIST _N:名前 _N:住所 _ENDLIST を 抽出して`), so its items are
+/// not checked as a sentence; `validate_lists` checks them.
+fn validate_section(input: &str, locale: &str) -> ValidationResult {
+    let tokens = tokenize(input);
+    let lists = list_ranges(&tokens);
+    if lists.is_empty() {
+        return validate_sentence_rules(input, locale);
+    }
+
+    // Input with every list replaced by a placeholder noun, and the
+    // position in `input` of every token of it
+    let mut reduced: Vec<&str> = Vec::new();
+    let mut positions = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        positions.push(i);
+        match lists.iter().find(|range| range.start == i) {
+            Some(range) => {
+                reduced.push("_N:_LIST");
+                i = range.end;
+            }
+            None => {
+                reduced.push(&input[tokens[i].start..tokens[i].end]);
+                i += 1;
+            }
+        }
+    }
+    positions.push(tokens.len());
+
+    let mut result = ValidationResult::new();
+    for mut error in validate_sentence_rules(&reduced.join(" "), locale).errors {
+        error.position = positions[error.position];
+        if let Some(autofix) = error.autofix.as_mut() {
+            autofix.target_position = positions[autofix.target_position.min(reduced.len())];
+        }
+        result.add_error(error);
+    }
+    result
+}
+
+/// Validate a single sentence section with the locale's grammar rules
+fn validate_sentence_rules(input: &str, locale: &str) -> ValidationResult {
     match locale {
         "en" => validate_sequence_en(input),
         "fr" => validate_sequence_fr(input),
@@ -731,12 +1832,16 @@ pub fn validate_sequence_with_locale(input: &str, locale: &str) -> ValidationRes
 /// 4. Preposition must be followed by noun/article (Warning)
 /// 5. "please" should be at start or before verb (Warning)
 /// 6. Period should be at end (Warning)
-/// 7. Missing verb in sentence (Warning)
+/// 7. Missing verb in a clause (Warning)
+/// 8. Adjective should come before its noun (Warning)
+/// 9. Negation should be followed by a verb (Warning)
+/// 10. Conjunction should be followed by another action (Warning)
 pub fn validate_sequence_en(input: &str) -> ValidationResult {
     let mut result = ValidationResult::new();
 
     // Tokenize input
-    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let owned_tokens = split_tokens(input);
+    let tokens: Vec<&str> = owned_tokens.iter().map(|t| t.as_str()).collect();
 
     if tokens.is_empty() {
         return result;
@@ -752,38 +1857,46 @@ pub fn validate_sequence_en(input: &str) -> ValidationResult {
     // Track state for validation
     let mut prev_type: Option<TokenType> = None;
     let mut prev_token: Option<&str> = None;
+    // Article still waiting for its noun (modifiers may come in between)
+    let mut pending_article: Option<(usize, &str)> = None;
 
     for (i, token, token_type) in classified.iter() {
         let i = *i;
         let token_type = *token_type;
 
-        // Rule 1: Article must be followed by noun (or other content)
-        // Check previous token
-        if let Some(prev) = prev_type {
-            if prev == TokenType::Article {
-                // Article should be followed by Noun, Other, or another content word
-                // Not by Verb, Preposition, Punctuation, or another Article
-                if token_type == TokenType::Verb
-                    || token_type == TokenType::Preposition
-                    || token_type == TokenType::Punctuation
-                    || token_type == TokenType::Article
-                    || token_type == TokenType::Please
-                {
-                    result.add_error(ValidationError::with_autofix(
-                        ValidationErrorCode::ArticleNotBeforeNoun,
-                        format!("Article '{}' should be followed by a noun", prev_token.unwrap_or("")),
-                        i - 1,
-                        Severity::Error,
-                        Some("Add a noun after the article".to_string()),
-                        AutoFixAction {
-                            action_type: AutoFixActionType::InsertAfter,
-                            block_type: "promps_noun".to_string(),
-                            target_position: i - 1,
-                            label: "Add noun".to_string(),
-                        },
-                    ));
-                }
+        // Rule 1: Article must be followed by noun (or other content),
+        // skipping modifiers ("the very detailed report")
+        if let Some((article_pos, article)) = pending_article {
+            // Article should be followed by Noun, Other, or another content word
+            // Not by Verb, Preposition, Punctuation, or another Article
+            if token_type == TokenType::Verb
+                || token_type == TokenType::Preposition
+                || token_type == TokenType::Punctuation
+                || token_type == TokenType::Article
+                || token_type == TokenType::Please
+                || token_type == TokenType::Conjunction
+            {
+                result.add_error(ValidationError::with_autofix(
+                    ValidationErrorCode::ArticleNotBeforeNoun,
+                    format!("Article '{}' should be followed by a noun", article),
+                    article_pos,
+                    Severity::Error,
+                    Some("Add a noun after the article".to_string()),
+                    AutoFixAction {
+                        action_type: AutoFixActionType::InsertAfter,
+                        block_type: "promps_noun".to_string(),
+                        target_position: article_pos,
+                        label: "Add noun".to_string(),
+                        replacement: None,
+                    },
+                ));
             }
+            if !is_modifier(token_type) {
+                pending_article = None;
+            }
+        }
+        if token_type == TokenType::Article {
+            pending_article = Some((i, *token));
         }
 
         // Rule 2: No consecutive articles
@@ -802,6 +1915,7 @@ pub fn validate_sequence_en(input: &str) -> ValidationResult {
             if prev == TokenType::Preposition {
                 if token_type != TokenType::Noun
                     && token_type != TokenType::Article
+                    && token_type != TokenType::Adjective
                     && token_type != TokenType::Other
                 {
                     result.add_error(ValidationError::with_autofix(
@@ -815,6 +1929,7 @@ pub fn validate_sequence_en(input: &str) -> ValidationResult {
                             block_type: "promps_noun".to_string(),
                             target_position: i - 1,
                             label: "Add noun".to_string(),
+                            replacement: None,
                         },
                     ));
                 }
@@ -841,6 +1956,41 @@ pub fn validate_sequence_en(input: &str) -> ValidationResult {
             }
         }
 
+        // Rule 9: Negation should be followed by a verb ("do not translate"),
+        // adverbs may come in between ("never quickly summarize")
+        if token_type == TokenType::Negation {
+            let next = classified[i + 1..]
+                .iter()
+                .map(|(_, _, t)| *t)
+                .find(|t| !matches!(t, TokenType::Negation | TokenType::Adverb));
+            if next != Some(TokenType::Verb) && prev_type != Some(TokenType::Negation) {
+                result.add_error(ValidationError::new(
+                    ValidationErrorCode::NegationWithoutVerb,
+                    format!("'{}' should be followed by a verb", token),
+                    i,
+                    Severity::Warning,
+                    Some("Add the action that should not be done".to_string()),
+                ));
+            }
+        }
+
+        // Rule 8: Adjective should come before its noun ("the detailed report")
+        if token_type == TokenType::Adjective && prev_type == Some(TokenType::Noun) {
+            let before_noun = classified
+                .get(i + 1)
+                .map(|(_, _, t)| matches!(t, TokenType::Noun | TokenType::Adjective))
+                .unwrap_or(false);
+            if !before_noun {
+                result.add_error(ValidationError::new(
+                    ValidationErrorCode::AdjectivePosition,
+                    format!("Adjective '{}' usually comes before the noun", modifier_text(token)),
+                    i,
+                    Severity::Warning,
+                    Some("Move the adjective before the noun".to_string()),
+                ));
+            }
+        }
+
         // Rule 6: Period should be at end (checked in post-loop)
         // Punctuation rules
         if token_type == TokenType::Punctuation {
@@ -859,47 +2009,52 @@ pub fn validate_sequence_en(input: &str) -> ValidationResult {
         prev_token = Some(*token);
     }
 
-    // Rule 7: Missing verb check
-    let has_verb = classified.iter().any(|(_, _, t)| *t == TokenType::Verb);
-    if !has_verb && !tokens.is_empty() {
-        // Only warn if there's some content (nouns, etc.)
-        let has_content = classified.iter().any(|(_, _, t)| {
-            matches!(t, TokenType::Noun | TokenType::Other | TokenType::Article)
+    // Rule 7: Missing verb check, per clause ("summarize the _N:report then the _N:notes")
+    for clause in split_clauses(&classified, "en") {
+        let has_verb = classified[clause.clone()].iter().any(|(_, _, t)| *t == TokenType::Verb);
+        if has_verb {
+            continue;
+        }
+        let has_content = classified[clause.clone()].iter().any(|(_, _, t)| {
+            matches!(t, TokenType::Noun | TokenType::Other | TokenType::Article) || is_modifier(*t)
         });
         if has_content {
             result.add_error(ValidationError::with_autofix(
                 ValidationErrorCode::MissingVerb,
                 "Sentence has no verb (action)".to_string(),
-                0,
+                clause.start,
                 Severity::Warning,
                 Some("Add a verb to specify the action".to_string()),
                 AutoFixAction {
                     action_type: AutoFixActionType::InsertBefore,
                     block_type: "promps_verb_analyze".to_string(),
-                    target_position: 0,
+                    target_position: clause.start,
                     label: "Add verb".to_string(),
+                    replacement: None,
                 },
             ));
         }
     }
 
-    // Final check: Article at end without noun
-    if let Some((last_i, last_token, last_type)) = classified.last() {
-        if *last_type == TokenType::Article {
-            result.add_error(ValidationError::with_autofix(
-                ValidationErrorCode::ArticleNotBeforeNoun,
-                format!("Article '{}' at end of sentence needs a noun", last_token),
-                *last_i,
-                Severity::Error,
-                Some("Add a noun after the article".to_string()),
-                AutoFixAction {
-                    action_type: AutoFixActionType::InsertAfter,
-                    block_type: "promps_noun".to_string(),
-                    target_position: *last_i,
-                    label: "Add noun".to_string(),
-                },
-            ));
-        }
+    // Rule 10: Conjunction should be followed by another action
+    add_dangling_conjunctions(&mut result, &classified, "en");
+
+    // Final check: Article (and its modifiers) at end without noun
+    if let Some((article_pos, article)) = pending_article {
+        result.add_error(ValidationError::with_autofix(
+            ValidationErrorCode::ArticleNotBeforeNoun,
+            format!("Article '{}' at end of sentence needs a noun", article),
+            article_pos,
+            Severity::Error,
+            Some("Add a noun after the article".to_string()),
+            AutoFixAction {
+                action_type: AutoFixActionType::InsertAfter,
+                block_type: "promps_noun".to_string(),
+                target_position: article_pos,
+                label: "Add noun".to_string(),
+                replacement: None,
+            },
+        ));
     }
 
     result
@@ -924,12 +2079,16 @@ pub fn validate_sequence_en(input: &str) -> ValidationResult {
 /// 4. Preposition must be followed by noun/article (Warning)
 /// 5. "veuillez" should be at start or before verb (Warning)
 /// 6. Period should be at end (Warning)
-/// 7. Missing verb in sentence (Warning)
+/// 7. Missing verb in a clause (Warning)
+/// 8. Adjective should usually come after its noun (Warning)
+/// 9. "ne" needs "pas" (or jamais, rien...) in the same sentence (Error)
+/// 10. Conjunction should be followed by another action (Warning)
 pub fn validate_sequence_fr(input: &str) -> ValidationResult {
     let mut result = ValidationResult::new();
 
     // Tokenize input
-    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let owned_tokens = split_tokens(input);
+    let tokens: Vec<&str> = owned_tokens.iter().map(|t| t.as_str()).collect();
 
     if tokens.is_empty() {
         return result;
@@ -945,36 +2104,45 @@ pub fn validate_sequence_fr(input: &str) -> ValidationResult {
     // Track state for validation
     let mut prev_type: Option<TokenType> = None;
     let mut prev_token: Option<&str> = None;
+    // Article still waiting for its noun (modifiers may come in between)
+    let mut pending_article: Option<(usize, &str)> = None;
 
     for (i, token, token_type) in classified.iter() {
         let i = *i;
         let token_type = *token_type;
 
-        // Rule 1: Article must be followed by noun (or other content)
-        if let Some(prev) = prev_type {
-            if prev == TokenType::Article {
-                if token_type == TokenType::Verb
-                    || token_type == TokenType::Preposition
-                    || token_type == TokenType::Punctuation
-                    || token_type == TokenType::Article
-                    || token_type == TokenType::Please
-                {
-                    result.add_error(ValidationError::with_autofix(
-                        ValidationErrorCode::ArticleNotBeforeNoun,
-                        format!("L'article '{}' doit être suivi d'un nom", prev_token.unwrap_or("")),
-                        i - 1,
-                        Severity::Error,
-                        Some("Ajoutez un nom après l'article".to_string()),
-                        AutoFixAction {
-                            action_type: AutoFixActionType::InsertAfter,
-                            block_type: "promps_noun".to_string(),
-                            target_position: i - 1,
-                            label: "Ajouter un nom".to_string(),
-                        },
-                    ));
-                }
+        // Rule 1: Article must be followed by noun (or other content),
+        // skipping modifiers ("un nouveau fichier")
+        if let Some((article_pos, article)) = pending_article {
+            if token_type == TokenType::Verb
+                || token_type == TokenType::Preposition
+                || token_type == TokenType::Punctuation
+                || token_type == TokenType::Article
+                || token_type == TokenType::Please
+                || token_type == TokenType::Conjunction
+            {
+                result.add_error(ValidationError::with_autofix(
+                    ValidationErrorCode::ArticleNotBeforeNoun,
+                    format!("L'article '{}' doit être suivi d'un nom", article),
+                    article_pos,
+                    Severity::Error,
+                    Some("Ajoutez un nom après l'article".to_string()),
+                    AutoFixAction {
+                        action_type: AutoFixActionType::InsertAfter,
+                        block_type: "promps_noun".to_string(),
+                        target_position: article_pos,
+                        label: "Ajouter un nom".to_string(),
+                        replacement: None,
+                    },
+                ));
+            }
+            if !is_modifier(token_type) {
+                pending_article = None;
             }
         }
+        if token_type == TokenType::Article {
+            pending_article = Some((i, *token));
+        }
 
         // Rule 2: No consecutive articles
         if token_type == TokenType::Article && prev_type == Some(TokenType::Article) {
@@ -992,6 +2160,7 @@ pub fn validate_sequence_fr(input: &str) -> ValidationResult {
             if prev == TokenType::Preposition {
                 if token_type != TokenType::Noun
                     && token_type != TokenType::Article
+                    && token_type != TokenType::Adjective
                     && token_type != TokenType::Other
                 {
                     result.add_error(ValidationError::with_autofix(
@@ -1005,6 +2174,7 @@ pub fn validate_sequence_fr(input: &str) -> ValidationResult {
                             block_type: "promps_noun".to_string(),
                             target_position: i - 1,
                             label: "Ajouter un nom".to_string(),
+                            replacement: None,
                         },
                     ));
                 }
@@ -1027,6 +2197,21 @@ pub fn validate_sequence_fr(input: &str) -> ValidationResult {
             }
         }
 
+        // Rule 8: Most adjectives come after the noun ("le rapport détaillé");
+        // beauty, age, goodness and size adjectives go before it
+        if token_type == TokenType::Adjective
+            && !TokenType::is_french_prenominal(modifier_text(token))
+            && classified.get(i + 1).map(|(_, _, t)| *t == TokenType::Noun).unwrap_or(false)
+        {
+            result.add_error(ValidationError::new(
+                ValidationErrorCode::AdjectivePosition,
+                format!("L'adjectif '{}' se place généralement après le nom", modifier_text(token)),
+                i,
+                Severity::Warning,
+                Some("Déplacez l'adjectif après le nom".to_string()),
+            ));
+        }
+
         // Rule 6: Period should be at end
         if token_type == TokenType::Punctuation {
             if TokenType::is_period(token) && i < tokens.len() - 1 {
@@ -1044,51 +2229,108 @@ pub fn validate_sequence_fr(input: &str) -> ValidationResult {
         prev_token = Some(*token);
     }
 
-    // Rule 7: Missing verb check
-    let has_verb = classified.iter().any(|(_, _, t)| *t == TokenType::Verb);
-    if !has_verb && !tokens.is_empty() {
-        let has_content = classified.iter().any(|(_, _, t)| {
-            matches!(t, TokenType::Noun | TokenType::Other | TokenType::Article)
+    // Rule 9: "ne" … "pas" pairs within a sentence
+    for error in validate_negation_fr(&tokens) {
+        result.add_error(error);
+    }
+
+    // Rule 7: Missing verb check, per clause ("résumer le _N:rapport puis les _N:notes")
+    for clause in split_clauses(&classified, "fr") {
+        let has_verb = classified[clause.clone()].iter().any(|(_, _, t)| *t == TokenType::Verb);
+        if has_verb {
+            continue;
+        }
+        let has_content = classified[clause.clone()].iter().any(|(_, _, t)| {
+            matches!(t, TokenType::Noun | TokenType::Other | TokenType::Article) || is_modifier(*t)
         });
         if has_content {
             result.add_error(ValidationError::with_autofix(
                 ValidationErrorCode::MissingVerb,
                 "La phrase ne contient pas de verbe (action)".to_string(),
-                0,
+                clause.start,
                 Severity::Warning,
                 Some("Ajoutez un verbe pour préciser l'action".to_string()),
                 AutoFixAction {
                     action_type: AutoFixActionType::InsertBefore,
                     block_type: "promps_verb_analyze".to_string(),
-                    target_position: 0,
+                    target_position: clause.start,
                     label: "Ajouter un verbe".to_string(),
+                    replacement: None,
                 },
             ));
         }
     }
 
-    // Final check: Article at end without noun
-    if let Some((last_i, last_token, last_type)) = classified.last() {
-        if *last_type == TokenType::Article {
-            result.add_error(ValidationError::with_autofix(
-                ValidationErrorCode::ArticleNotBeforeNoun,
-                format!("L'article '{}' en fin de phrase nécessite un nom", last_token),
-                *last_i,
-                Severity::Error,
-                Some("Ajoutez un nom après l'article".to_string()),
-                AutoFixAction {
-                    action_type: AutoFixActionType::InsertAfter,
-                    block_type: "promps_noun".to_string(),
-                    target_position: *last_i,
-                    label: "Ajouter un nom".to_string(),
-                },
-            ));
-        }
+    // Rule 10: Conjunction should be followed by another action
+    add_dangling_conjunctions(&mut result, &classified, "fr");
+
+    // Final check: Article (and its modifiers) at end without noun
+    if let Some((article_pos, article)) = pending_article {
+        result.add_error(ValidationError::with_autofix(
+            ValidationErrorCode::ArticleNotBeforeNoun,
+            format!("L'article '{}' en fin de phrase nécessite un nom", article),
+            article_pos,
+            Severity::Error,
+            Some("Ajoutez un nom après l'article".to_string()),
+            AutoFixAction {
+                action_type: AutoFixActionType::InsertAfter,
+                block_type: "promps_noun".to_string(),
+                target_position: article_pos,
+                label: "Ajouter un nom".to_string(),
+                replacement: None,
+            },
+        ));
     }
 
     result
 }
 
+/// Check French negations: "ne" / "n'" needs a closing word before the end
+/// of the sentence (Error), a closing word without "ne" is informal (Warning)
+fn validate_negation_fr(tokens: &[&str]) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut open: Option<usize> = None;
+
+    let unclosed = |i: usize, token: &str| {
+        ValidationError::new(
+            ValidationErrorCode::IncompleteNegation,
+            format!("« {} » sans « pas » (ou jamais, rien...)", token),
+            i,
+            Severity::Error,
+            Some("Ajoutez « pas » après le verbe".to_string()),
+        )
+    };
+
+    for (i, token) in tokens.iter().enumerate() {
+        let lower = token.to_lowercase();
+        if is_negation_opener_fr(tokens, i) {
+            if let Some(pos) = open {
+                errors.push(unclosed(pos, tokens[pos]));
+            }
+            open = Some(i);
+        } else if NEGATION_CLOSERS_FR.contains(&lower.as_str()) {
+            if open.take().is_none() && lower != "plus" {
+                errors.push(ValidationError::new(
+                    ValidationErrorCode::IncompleteNegation,
+                    format!("« {} » sans « ne » (registre familier)", token),
+                    i,
+                    Severity::Warning,
+                    Some("Ajoutez « ne » avant le verbe".to_string()),
+                ));
+            }
+        } else if TokenType::is_period(token) || matches!(*token, "!" | "?") {
+            if let Some(pos) = open.take() {
+                errors.push(unclosed(pos, tokens[pos]));
+            }
+        }
+    }
+    if let Some(pos) = open {
+        errors.push(unclosed(pos, tokens[pos]));
+    }
+
+    errors
+}
+
 // ============================================================================
 // Pattern Templates (Phase 6 Step 3)
 // ============================================================================
@@ -1109,6 +2351,25 @@ pub struct PatternTemplate {
     pub example: String,
     /// Block types to insert (in order)
     pub blocks: Vec<PatternBlock>,
+    /// Where the template was loaded from
+    #[serde(default)]
+    pub source: PatternSource,
+    /// Locale the template applies to (all locales when omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+/// Origin of a pattern template
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternSource {
+    /// Compiled into the application
+    #[default]
+    Builtin,
+    /// Loaded from the user config directory
+    User,
+    /// Embedded in the current project file
+    Project,
 }
 
 /// A block in a pattern template
@@ -1120,6 +2381,7 @@ pub struct PatternBlock {
     /// Display label for the slot
     pub label: String,
     /// Whether this is a placeholder that user should fill
+    #[serde(default)]
     pub is_placeholder: bool,
     /// Default value for text fields (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1143,6 +2405,8 @@ impl PatternTemplate {
             structure: structure.into(),
             example: example.into(),
             blocks,
+            source: PatternSource::Builtin,
+            locale: None,
         }
     }
 }
@@ -1243,7 +2507,7 @@ pub fn get_pattern_templates() -> Vec<PatternTemplate> {
             "parallel_to",
             "並列文型（と）",
             "「AとBを」で複数の対象を指定",
-            "名詞 と 名詞 を 動詞",
+            "(名詞 と)+ 名詞 を 動詞",
             "データ と 結果 を 保存して",
             vec![
                 PatternBlock::placeholder("promps_noun", "対象1"),
@@ -1328,12 +2592,12 @@ pub fn get_pattern_templates_en() -> Vec<PatternTemplate> {
                 PatternBlock::placeholder("promps_noun", "Target"),
             ],
         ),
-        // Pattern 4: Polite request (please Verb Noun)
+        // Pattern 4: Polite request (please Verb [Article] Noun)
         PatternTemplate::new(
             "polite",
             "Polite Request",
             "Polite imperative with 'please'",
-            "please Verb Noun",
+            "please Verb [Article] Noun",
             "please analyze this data",
             vec![
                 PatternBlock::fixed("promps_article_please", "please"),
@@ -1432,12 +2696,12 @@ pub fn get_pattern_templates_fr() -> Vec<PatternTemplate> {
                 PatternBlock::placeholder("promps_noun", "Cible"),
             ],
         ),
-        // Pattern 4: Polite request (veuillez Verb Noun)
+        // Pattern 4: Polite request (veuillez Verb [Article] Noun)
         PatternTemplate::new(
             "polite",
             "Requête polie",
             "Impératif poli avec 'veuillez'",
-            "veuillez Verbe Nom",
+            "veuillez Verbe [Article] Nom",
             "veuillez analyser ce document",
             vec![
                 PatternBlock::fixed("promps_article_please", "veuillez"),
@@ -1517,110 +2781,215 @@ pub struct PatternMatchResult {
     pub missing_elements: Vec<String>,
     /// Whether the pattern is complete
     pub is_complete: bool,
+    /// Sentence the best match was found in (0-indexed)
+    pub sentence_index: usize,
+    /// Token range covered by the match (absolute positions, end exclusive)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_range: Option<MatchRange>,
+    /// Positions of extra tokens inside the matched range
+    pub inserted_positions: Vec<usize>,
+    /// Indices of pattern blocks that are missing or mismatched
+    pub missing_slots: Vec<usize>,
+}
+
+/// Token range of a pattern match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchRange {
+    /// First matched token position
+    pub start: usize,
+    /// Position after the last matched token
+    pub end: usize,
 }
 
-/// Analyze current input against patterns with locale support
-pub fn analyze_patterns_with_locale(input: &str, locale: &str) -> Vec<PatternMatchResult> {
-    let tokens: Vec<&str> = input.split_whitespace().collect();
-    let patterns = get_pattern_templates_by_locale(locale);
+/// Analyze current input against a given set of pattern templates
+pub fn analyze_patterns_with_templates(
+    input: &str,
+    locale: &str,
+    patterns: &[PatternTemplate],
+) -> Vec<PatternMatchResult> {
+    let owned_tokens = split_tokens(input);
+    let tokens: Vec<&str> = owned_tokens.iter().map(|t| t.as_str()).collect();
     let mut results = Vec::new();
 
     for pattern in patterns {
-        let result = match_pattern_with_locale(&tokens, &pattern, locale);
+        let result = match_pattern_with_locale(&tokens, pattern, locale);
         results.push(result);
     }
 
-    // Sort by match score (highest first)
-    results.sort_by(|a, b| b.match_score.partial_cmp(&a.match_score).unwrap());
+    // Sort by match score (highest first), complete patterns first on ties
+    results.sort_by(|a, b| {
+        b.match_score
+            .partial_cmp(&a.match_score)
+            .unwrap()
+            .then(b.is_complete.cmp(&a.is_complete))
+    });
 
     results
 }
 
 /// Match input tokens against a pattern
-/// Only matches if tokens match from the BEGINNING of the pattern
 #[allow(dead_code)]
 fn match_pattern(tokens: &[&str], pattern: &PatternTemplate) -> PatternMatchResult {
     match_pattern_with_locale(tokens, pattern, "ja")
 }
 
-/// Match input tokens against a pattern with locale support
-fn match_pattern_with_locale(tokens: &[&str], pattern: &PatternTemplate, locale: &str) -> PatternMatchResult {
-    // Build expected tokens from pattern (with specific particle values)
-    let expected: Vec<ExpectedToken> = pattern
+/// Split tokens into sentences at sentence-final punctuation
+///
+/// # Returns
+/// (start position, tokens) per sentence; the terminator stays with its sentence
+fn split_sentences<'a>(tokens: &[&'a str]) -> Vec<(usize, Vec<&'a str>)> {
+    const TERMINATORS: &[&str] = &["。", ".", "！", "？", "!", "?"];
+
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut current = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        current.push(*token);
+        if TERMINATORS.contains(token) {
+            sentences.push((start, std::mem::take(&mut current)));
+            start = i + 1;
+        }
+    }
+    if !current.is_empty() {
+        sentences.push((start, current));
+    }
+
+    sentences
+}
+
+/// Pattern compiled for matching, with slot-to-block mapping
+struct PatternSlots {
+    compiled: CompiledPattern,
+    /// (block index, label) per slot
+    slots: Vec<(usize, String)>,
+}
+
+/// Compile a template for matching
+///
+/// The `structure` string is used when it parses as a pattern and either
+/// lines up with the blocks (one atom per block) or uses optional, repeat
+/// or alternative syntax. Otherwise the blocks are matched in order, with
+/// adjective and adverb blocks as optional slots.
+fn compile_template(pattern: &PatternTemplate, locale: &str) -> PatternSlots {
+    if let Ok(expr) = parse_pattern(&pattern.structure) {
+        let compiled = CompiledPattern::compile(&expr);
+        if compiled.slots.len() == pattern.blocks.len() {
+            let slots = pattern
+                .blocks
+                .iter()
+                .enumerate()
+                .map(|(i, b)| (i, b.label.clone()))
+                .collect();
+            return PatternSlots { compiled, slots };
+        }
+        if has_pattern_syntax(&pattern.structure) {
+            let slots = compiled
+                .slots
+                .iter()
+                .enumerate()
+                .map(|(i, atom)| (i, atom.label()))
+                .collect();
+            return PatternSlots { compiled, slots };
+        }
+    }
+
+    // Build expected tokens from blocks, skipping blocks with no output
+    // in this locale (e.g. が/を in English)
+    let (slots, items): (Vec<(usize, String)>, Vec<PatternExpr>) = pattern
         .blocks
         .iter()
-        .map(|b| ExpectedToken::from_block_type_with_locale(&b.block_type, locale))
-        .collect();
+        .enumerate()
+        .map(|(i, b)| (i, b, ExpectedToken::from_block_type_with_locale(&b.block_type, locale)))
+        .filter(|(_, _, exp)| !matches!(exp, ExpectedToken::Particle("")))
+        .map(|(i, b, exp)| {
+            let atom = PatternExpr::Atom(exp.to_atom());
+            let item = match exp {
+                ExpectedToken::Adjective | ExpectedToken::Adverb => PatternExpr::Optional(Box::new(atom)),
+                _ => atom,
+            };
+            ((i, b.label.clone()), item)
+        })
+        .unzip();
 
-    // Check consecutive match from the beginning
-    let mut consecutive_match_count = 0;
-    let mut missing_elements = Vec::new();
-    let mut had_mismatch = false;
+    PatternSlots {
+        compiled: CompiledPattern::compile(&PatternExpr::Sequence(items)),
+        slots,
+    }
+}
 
-    for (i, exp) in expected.iter().enumerate() {
-        if i < tokens.len() && !had_mismatch {
-            let token = tokens[i];
-            let token_type = match locale {
-                "en" => TokenType::classify_en(token),
-                "fr" => TokenType::classify_fr(token),
-                _ => TokenType::classify(token),
-            };
+/// Match input tokens against a pattern with locale support
+///
+/// Each sentence is aligned separately and the best-scoring sentence is
+/// reported (the later sentence wins ties, as it is usually being edited).
+fn match_pattern_with_locale(tokens: &[&str], pattern: &PatternTemplate, locale: &str) -> PatternMatchResult {
+    let PatternSlots { compiled, slots } = compile_template(pattern, locale);
+
+    let mut sentences = split_sentences(tokens);
+    if sentences.is_empty() {
+        // Empty input: align against nothing so required slots are reported
+        sentences.push((0, Vec::new()));
+    }
+
+    let mut best: Option<(usize, usize, Alignment)> = None;
+    for (index, (start, sentence)) in sentences.into_iter().enumerate() {
+        let alignment = align(&compiled, &sentence, locale);
+        let better = match &best {
+            None => true,
+            Some((_, _, b)) => alignment.score() >= b.score(),
+        };
+        if better {
+            best = Some((index, start, alignment));
+        }
+    }
+    let (sentence_index, offset, alignment) = best.expect("at least one sentence");
 
-            // Check if token matches expected
-            let matches = match exp {
-                ExpectedToken::Noun => token_type == TokenType::Noun,
-                ExpectedToken::Particle(p) => {
-                    // For English, also check if token matches (case-insensitive)
-                    // Empty particles match position only
-                    if p.is_empty() {
-                        true // Skip empty particle markers
-                    } else if locale == "en" || locale == "fr" {
-                        token.to_lowercase() == *p
-                    } else {
-                        token == *p
-                    }
-                }
-                ExpectedToken::Verb => token_type == TokenType::Verb,
-                ExpectedToken::Article(a) => {
-                    token.to_lowercase() == *a
-                }
-                ExpectedToken::Other(text) => token == *text,
-            };
+    let mut missing_elements = Vec::new();
+    let mut missing_slots = Vec::new();
+    let mut inserted_positions = Vec::new();
+    let mut positions = Vec::new();
 
-            if matches {
-                consecutive_match_count += 1;
-            } else {
-                // Mismatch found - stop counting matches
-                had_mismatch = true;
+    for (op, slot, token) in &alignment.ops {
+        if let Some(t) = token {
+            positions.push(offset + t);
+        }
+        match op {
+            AlignOp::Match => {}
+            AlignOp::Substitute => {
+                let (block_index, label) = &slots[slot.unwrap()];
                 let msg = match locale {
-                    "en" => format!("Position {}: {} required", i + 1, pattern.blocks[i].label),
-                    "fr" => format!("Position {} : {} requis", i + 1, pattern.blocks[i].label),
-                    _ => format!("位置{}: {} が必要", i + 1, pattern.blocks[i].label),
+                    "en" => format!("Position {}: {} required", block_index + 1, label),
+                    "fr" => format!("Position {} : {} requis", block_index + 1, label),
+                    _ => format!("位置{}: {} が必要", block_index + 1, label),
                 };
                 missing_elements.push(msg);
+                missing_slots.push(*block_index);
             }
-        } else {
-            // Token missing or already had mismatch
-            missing_elements.push(pattern.blocks[i].label.clone());
+            AlignOp::Missing => {
+                let (block_index, label) = &slots[slot.unwrap()];
+                missing_elements.push(label.clone());
+                missing_slots.push(*block_index);
+            }
+            AlignOp::Inserted => inserted_positions.push(offset + token.unwrap()),
         }
     }
 
-    let total_expected = expected.len();
-
-    // Only give positive score if we have consecutive matches from start
-    // and no mismatches within the token range
-    let match_score = if had_mismatch || consecutive_match_count == 0 {
-        0.0
-    } else {
-        consecutive_match_count as f64 / total_expected as f64
+    let matched_range = match (positions.first(), positions.last()) {
+        (Some(&start), Some(&end)) if alignment.matches > 0 => Some(MatchRange { start, end: end + 1 }),
+        _ => None,
     };
 
     PatternMatchResult {
         pattern_id: pattern.id.clone(),
         pattern_name: pattern.name.clone(),
-        match_score,
+        match_score: alignment.score(),
         missing_elements,
-        is_complete: consecutive_match_count == total_expected && tokens.len() == total_expected,
+        is_complete: alignment.is_complete(),
+        sentence_index,
+        matched_range,
+        inserted_positions,
+        missing_slots,
     }
 }
 
@@ -1630,6 +2999,8 @@ enum ExpectedToken {
     Noun,
     Particle(&'static str),
     Verb,
+    Adjective,
+    Adverb,
     Article(&'static str),
     Other(&'static str),
 }
@@ -1647,82 +3018,174 @@ impl ExpectedToken {
             ExpectedToken::Noun
         } else if block_type.starts_with("promps_article") {
             // Articles (English/French mode)
-            let article = if locale == "fr" {
-                match block_type {
-                    "promps_article_a" => "un",
-                    "promps_article_an" => "une",
-                    "promps_article_the" => "le",
-                    "promps_article_this" => "ce",
-                    "promps_article_that" => "cette",
-                    "promps_article_please" => "veuillez",
-                    _ => "le", // default
-                }
-            } else {
-                match block_type {
-                    "promps_article_a" => "a",
-                    "promps_article_an" => "an",
-                    "promps_article_the" => "the",
-                    "promps_article_this" => "this",
-                    "promps_article_that" => "that",
-                    "promps_article_please" => "please",
-                    _ => "the", // default
-                }
-            };
-            ExpectedToken::Article(article)
+            let default = if locale == "fr" { "le" } else { "the" };
+            ExpectedToken::Article(block_word(block_type, locale).unwrap_or(default))
         } else if block_type.starts_with("promps_particle") {
-            // Extract specific particle from block type
-            // Use different values per locale
-            let particle = match locale {
-                "en" => match block_type {
-                    "promps_particle_ga" => "",    // Subject marker (omitted in English)
-                    "promps_particle_wo" => "",    // Object marker (omitted in English)
-                    "promps_particle_ni" => "to",
-                    "promps_particle_de" => "with",
-                    "promps_particle_to" => "and",
-                    "promps_particle_he" => "toward",
-                    "promps_particle_kara" => "from",
-                    "promps_particle_made" => "until",
-                    "promps_particle_yori" => "than",
-                    _ => "", // default
-                },
-                "fr" => match block_type {
-                    "promps_particle_ga" => "",    // Subject marker (omitted in French)
-                    "promps_particle_wo" => "",    // Object marker (omitted in French)
-                    "promps_particle_ni" => "\u{00e0}",  // à
-                    "promps_particle_de" => "avec",
-                    "promps_particle_to" => "et",
-                    "promps_particle_he" => "vers",
-                    "promps_particle_kara" => "de",
-                    "promps_particle_made" => "jusqu'\u{00e0}",  // jusqu'à
-                    "promps_particle_yori" => "que",
-                    _ => "", // default
-                },
-                _ => match block_type {
-                    "promps_particle_ga" => "が",
-                    "promps_particle_wo" => "を",
-                    "promps_particle_ni" => "に",
-                    "promps_particle_de" => "で",
-                    "promps_particle_to" => "と",
-                    "promps_particle_he" => "へ",
-                    "promps_particle_kara" => "から",
-                    "promps_particle_made" => "まで",
-                    "promps_particle_yori" => "より",
-                    _ => "が", // default
-                },
-            };
-            ExpectedToken::Particle(particle)
+            // Specific particle per locale (subject/object markers are empty in English/French)
+            let default = if locale == "en" || locale == "fr" { "" } else { "が" };
+            ExpectedToken::Particle(block_word(block_type, locale).unwrap_or(default))
+        } else if block_type.starts_with("promps_conjunction") {
+            ExpectedToken::Other(block_word(block_type, locale).unwrap_or("then"))
         } else if block_type.starts_with("promps_verb") {
             ExpectedToken::Verb
+        } else if block_type == "promps_adjective" {
+            ExpectedToken::Adjective
+        } else if block_type == "promps_adverb" {
+            ExpectedToken::Adverb
         } else if block_type == "promps_other" {
             match locale {
                 "en" => ExpectedToken::Other("about"),
                 "fr" => ExpectedToken::Other("concernant"),
                 _ => ExpectedToken::Other("について"),
             }
-        } else {
-            ExpectedToken::Other("")
+        } else {
+            ExpectedToken::Other("")
+        }
+    }
+
+    /// Convert to a pattern slot
+    fn to_atom(&self) -> PatternAtom {
+        match self {
+            ExpectedToken::Noun => PatternAtom::Noun,
+            ExpectedToken::Verb => PatternAtom::Verb,
+            ExpectedToken::Adjective => PatternAtom::Adjective,
+            ExpectedToken::Adverb => PatternAtom::Adverb,
+            ExpectedToken::Particle(text) | ExpectedToken::Article(text) | ExpectedToken::Other(text) => {
+                PatternAtom::Literal(text.to_string())
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Block Vocabulary (ブロック語彙)
+// ============================================================================
+
+/// Output text of fixed blocks per locale: (block type, ja, en, fr)
+///
+/// Mirrors the `blockly.*.output` strings in `res/js/i18n.js`.
+/// Empty text means the block produces no output in that locale.
+const BLOCK_WORDS: &[(&str, &str, &str, &str)] = &[
+    // Particles (助詞) / prepositions
+    ("promps_particle_ga", "が", "", ""),
+    ("promps_particle_wo", "を", "", ""),
+    ("promps_particle_ni", "に", "to", "\u{00e0}"),
+    ("promps_particle_de", "で", "with", "avec"),
+    ("promps_particle_to", "と", "and", "et"),
+    ("promps_particle_he", "へ", "toward", "vers"),
+    ("promps_particle_kara", "から", "from", "de"),
+    ("promps_particle_made", "まで", "until", "jusqu'\u{00e0}"),
+    ("promps_particle_yori", "より", "than", "que"),
+    // Conjunctions (接続詞)
+    ("promps_conjunction_then", "そして", "then", "puis"),
+    // Articles (冠詞)
+    ("promps_article_a", "a", "a", "un"),
+    ("promps_article_an", "an", "an", "une"),
+    ("promps_article_the", "the", "the", "le"),
+    ("promps_article_this", "this", "this", "ce"),
+    ("promps_article_that", "that", "that", "cette"),
+    ("promps_article_please", "please", "please", "veuillez"),
+    // Verbs (動詞)
+    ("promps_verb_analyze", "分析して", "analyze", "analyser"),
+    ("promps_verb_summarize", "要約して", "summarize", "r\u{00e9}sumer"),
+    ("promps_verb_translate", "翻訳して", "translate", "traduire"),
+    ("promps_verb_create", "作成して", "create", "cr\u{00e9}er"),
+    ("promps_verb_generate", "生成して", "generate", "g\u{00e9}n\u{00e9}rer"),
+    ("promps_verb_convert", "変換して", "convert", "convertir"),
+    ("promps_verb_delete", "削除して", "delete", "supprimer"),
+    ("promps_verb_update", "更新して", "update", "mettre \u{00e0} jour"),
+    ("promps_verb_extract", "抽出して", "extract", "extraire"),
+    ("promps_verb_explain", "説明して", "explain", "expliquer"),
+    ("promps_verb_describe", "解説して", "describe", "d\u{00e9}crire"),
+    ("promps_verb_teach", "教えて", "teach", "enseigner"),
+    // Punctuation (句読点) - sentence marks first so reverse lookup prefers them
+    ("promps_punct_kuten", "。", ".", "."),
+    ("promps_punct_touten", "、", ",", ","),
+    ("promps_punct_exclaim", "！", "!", "!"),
+    ("promps_punct_question", "？", "?", "?"),
+    ("promps_punct_dquote", "\"", "\"", "\""),
+    ("promps_punct_squote", "'", "'", "'"),
+    ("promps_punct_comma", ",", ",", ","),
+    ("promps_punct_slash", "/", "/", "/"),
+    ("promps_punct_amp", "&", "&", "&"),
+    ("promps_punct_period", ".", ".", "."),
+];
+
+/// Get the output text of a fixed block for a locale
+///
+/// # Returns
+/// `None` for unknown block types and text-field blocks (noun, other, custom verb)
+pub fn block_word(block_type: &str, locale: &str) -> Option<&'static str> {
+    BLOCK_WORDS
+        .iter()
+        .find(|(bt, _, _, _)| *bt == block_type)
+        .map(|(_, ja, en, fr)| match locale {
+            "en" => *en,
+            "fr" => *fr,
+            _ => *ja,
+        })
+}
+
+/// List fixed block types that produce output in a locale (palette order)
+pub fn block_types(locale: &str) -> Vec<&'static str> {
+    BLOCK_WORDS
+        .iter()
+        .filter(|(bt, _, _, _)| !block_word(bt, locale).unwrap_or("").is_empty())
+        .map(|(bt, _, _, _)| *bt)
+        .collect()
+}
+
+/// Check if a block type exists in the block palette
+pub fn is_known_block_type(block_type: &str) -> bool {
+    matches!(
+        block_type,
+        "promps_noun" | "promps_other" | "promps_verb_custom" | "promps_adjective" | "promps_adverb"
+    )
+        || BLOCK_WORDS.iter().any(|(bt, _, _, _)| *bt == block_type)
+}
+
+/// Find the fixed block type that outputs `word` in a locale
+///
+/// Matching is case-insensitive for English and French.
+/// Empty outputs (e.g. が/を in English) never match.
+pub fn find_block_type(word: &str, locale: &str) -> Option<&'static str> {
+    let word = word.trim();
+    if word.is_empty() {
+        return None;
+    }
+    let word_lower = word.to_lowercase();
+
+    BLOCK_WORDS
+        .iter()
+        .find(|(bt, _, _, _)| {
+            let text = block_word(bt, locale).unwrap_or("");
+            !text.is_empty() && (text == word || (locale != "ja" && text == word_lower))
+        })
+        .map(|(bt, _, _, _)| *bt)
+}
+
+/// Find the fixed verb block at token `i` (a verb may span several tokens)
+///
+/// # Returns
+/// (block type, number of tokens consumed)
+pub fn find_verb_block(tokens: &[&str], i: usize, locale: &str) -> Option<(&'static str, usize)> {
+    // Longest match first ("mettre à jour")
+    for len in (1..=3).rev() {
+        if i + len > tokens.len() {
+            continue;
+        }
+        let phrase = tokens[i..i + len]
+            .iter()
+            .map(|t| t.strip_prefix("_V:").unwrap_or(t))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if let Some(bt) = find_block_type(&phrase, locale) {
+            if bt.starts_with("promps_verb") {
+                return Some((bt, len));
+            }
         }
     }
+    None
 }
 
 // ============================================================================
@@ -1733,6 +3196,10 @@ impl ExpectedToken {
 mod tests {
     use super::*;
 
+    fn analyze_builtin(input: &str, locale: &str) -> Vec<PatternMatchResult> {
+        analyze_patterns_with_templates(input, locale, &get_pattern_templates_by_locale(locale))
+    }
+
     // Token classification tests
 
     #[test]
@@ -2035,7 +3502,7 @@ mod tests {
     #[test]
     fn test_analyze_patterns_osv_match() {
         // "_N:Doc を _N:User が" should match osv_emphasis pattern
-        let results = analyze_patterns_with_locale("_N:Doc を _N:User が", "ja");
+        let results = analyze_builtin("_N:Doc を _N:User が", "ja");
         let osv_match = results.iter().find(|r| r.pattern_id == "osv_emphasis").unwrap();
 
         // 4 out of 5 tokens match
@@ -2046,7 +3513,7 @@ mod tests {
     #[test]
     fn test_analyze_patterns_osv_complete() {
         // Complete OSV pattern
-        let results = analyze_patterns_with_locale("_N:Doc を _N:User が 分析して", "ja");
+        let results = analyze_builtin("_N:Doc を _N:User が 分析して", "ja");
         let osv_match = results.iter().find(|r| r.pattern_id == "osv_emphasis").unwrap();
 
         assert_eq!(osv_match.match_score, 1.0);
@@ -2071,7 +3538,7 @@ mod tests {
 
     #[test]
     fn test_analyze_patterns_empty_input() {
-        let results = analyze_patterns_with_locale("", "ja");
+        let results = analyze_builtin("", "ja");
         assert!(!results.is_empty());
         // All patterns should have 0 match score for empty input
         for result in &results {
@@ -2082,7 +3549,7 @@ mod tests {
     #[test]
     fn test_analyze_patterns_partial_match() {
         // "_N:Doc を" should partially match ov_simple pattern
-        let results = analyze_patterns_with_locale("_N:Doc を", "ja");
+        let results = analyze_builtin("_N:Doc を", "ja");
         let ov_match = results.iter().find(|r| r.pattern_id == "ov_simple").unwrap();
 
         // 2 out of 3 tokens match
@@ -2093,7 +3560,7 @@ mod tests {
     #[test]
     fn test_analyze_patterns_complete_match() {
         // "_N:Doc を 分析して" should match ov_simple completely
-        let results = analyze_patterns_with_locale("_N:Doc を 分析して", "ja");
+        let results = analyze_builtin("_N:Doc を 分析して", "ja");
         let ov_match = results.iter().find(|r| r.pattern_id == "ov_simple").unwrap();
 
         assert_eq!(ov_match.match_score, 1.0);
@@ -2102,7 +3569,7 @@ mod tests {
 
     #[test]
     fn test_analyze_patterns_sorted_by_score() {
-        let results = analyze_patterns_with_locale("_N:Doc を 分析して", "ja");
+        let results = analyze_builtin("_N:Doc を 分析して", "ja");
 
         // Results should be sorted by match_score descending
         for i in 1..results.len() {
@@ -2110,6 +3577,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_analyze_patterns_leading_token_ignored() {
+        // A leading adverb no longer hides the pattern
+        let results = analyze_builtin("まず _N:Doc を 分析して", "ja");
+        let ov_match = results.iter().find(|r| r.pattern_id == "ov_simple").unwrap();
+
+        assert_eq!(ov_match.match_score, 1.0);
+        assert!(ov_match.is_complete);
+        assert_eq!(ov_match.matched_range, Some(MatchRange { start: 1, end: 4 }));
+    }
+
+    #[test]
+    fn test_analyze_patterns_second_sentence() {
+        let results = analyze_builtin(
+            "_N:A を 分析して 。 _N:英語 から _N:日本語 に 翻訳して",
+            "ja",
+        );
+        let source_match = results.iter().find(|r| r.pattern_id == "source_dest").unwrap();
+
+        assert!(source_match.is_complete);
+        assert_eq!(source_match.sentence_index, 1);
+        assert_eq!(source_match.matched_range, Some(MatchRange { start: 4, end: 9 }));
+    }
+
+    #[test]
+    fn test_analyze_patterns_inserted_token() {
+        let results = analyze_builtin("_N:Doc を とても 分析して", "ja");
+        let ov_match = results.iter().find(|r| r.pattern_id == "ov_simple").unwrap();
+
+        // 3 matched slots over 3 slots + 1 extra token
+        assert_eq!(ov_match.match_score, 0.75);
+        assert!(!ov_match.is_complete);
+        assert_eq!(ov_match.inserted_positions, vec![2]);
+    }
+
+    #[test]
+    fn test_analyze_patterns_substituted_slot() {
+        let results = analyze_builtin("_N:Doc が 分析して", "ja");
+        let ov_match = results.iter().find(|r| r.pattern_id == "ov_simple").unwrap();
+
+        assert!(ov_match.match_score > 0.6 && ov_match.match_score < 0.7);
+        assert_eq!(ov_match.missing_slots, vec![1]);
+        assert!(ov_match.missing_elements[0].contains("位置2"));
+    }
+
+    #[test]
+    fn test_analyze_patterns_missing_slots_reported() {
+        let results = analyze_builtin("_N:Doc を", "ja");
+        let sov_match = results.iter().find(|r| r.pattern_id == "sov_basic").unwrap();
+
+        // Matches the object half of N が N を V
+        assert_eq!(sov_match.match_score, 0.4);
+        assert_eq!(sov_match.missing_slots, vec![0, 1, 4]);
+    }
+
+    #[test]
+    fn test_analyze_patterns_en_anywhere() {
+        let results = analyze_builtin("now analyze _N:document", "en");
+        let svo_match = results.iter().find(|r| r.pattern_id == "svo_basic").unwrap();
+
+        assert!(svo_match.is_complete);
+        assert_eq!(svo_match.matched_range, Some(MatchRange { start: 1, end: 3 }));
+    }
+
+    #[test]
+    fn test_analyze_patterns_repeated_slot() {
+        let results = analyze_builtin("_N:A と _N:B と _N:C を 保存して", "ja");
+        let parallel = results.iter().find(|r| r.pattern_id == "parallel_to").unwrap();
+
+        assert!(parallel.is_complete);
+        assert_eq!(parallel.match_score, 1.0);
+    }
+
+    #[test]
+    fn test_analyze_patterns_optional_slot() {
+        let with_article = analyze_builtin("please analyze the _N:data", "en");
+        let without_article = analyze_builtin("please analyze _N:data", "en");
+
+        for results in [with_article, without_article] {
+            let polite = results.iter().find(|r| r.pattern_id == "polite").unwrap();
+            assert!(polite.is_complete);
+        }
+    }
+
+    #[test]
+    fn test_analyze_patterns_custom_structure_alternative() {
+        let pattern = PatternTemplate::new(
+            "dest", "Destination", "", "N (に|へ) N を V", "",
+            vec![PatternBlock::placeholder("promps_noun", "Dest")],
+        );
+        let results = analyze_patterns_with_templates("_N:東京 へ _N:Doc を 送信して", "ja", std::slice::from_ref(&pattern));
+        assert!(results[0].is_complete);
+
+        // Missing slots fall back to atom labels when blocks do not line up
+        let results = analyze_patterns_with_templates("_N:東京 へ _N:Doc", "ja", &[pattern]);
+        assert!(!results[0].is_complete);
+        assert_eq!(results[0].missing_elements, vec!["を".to_string(), "V".to_string()]);
+        assert_eq!(results[0].missing_slots, vec![4, 5]);
+    }
+
     #[test]
     fn test_pattern_template_serialization() {
         let patterns = get_pattern_templates();
@@ -2122,12 +3689,15 @@ mod tests {
 
     #[test]
     fn test_pattern_match_result_serialization() {
-        let results = analyze_patterns_with_locale("_N:Doc を", "ja");
+        let results = analyze_builtin("_N:Doc を", "ja");
         let json = serde_json::to_string(&results).unwrap();
 
         assert!(json.contains("\"patternId\":"));
         assert!(json.contains("\"matchScore\":"));
         assert!(json.contains("\"isComplete\":"));
+        assert!(json.contains("\"matchedRange\":"));
+        assert!(json.contains("\"insertedPositions\":"));
+        assert!(json.contains("\"missingSlots\":"));
     }
 
     // ========================================================================
@@ -2472,7 +4042,7 @@ mod tests {
 
     #[test]
     fn test_analyze_patterns_en() {
-        let results = analyze_patterns_with_locale("analyze _N:document", "en");
+        let results = analyze_builtin("analyze _N:document", "en");
         // Should find matching patterns
         let has_match = results.iter().any(|r| r.match_score > 0.0);
         assert!(has_match, "Should find at least one matching English pattern");
@@ -2480,7 +4050,7 @@ mod tests {
 
     #[test]
     fn test_analyze_patterns_en_complete() {
-        let results = analyze_patterns_with_locale("analyze _N:document", "en");
+        let results = analyze_builtin("analyze _N:document", "en");
         let svo_match = results.iter().find(|r| r.pattern_id == "svo_basic");
         if let Some(m) = svo_match {
             assert_eq!(m.match_score, 1.0, "Should be complete match");
@@ -2676,18 +4246,597 @@ mod tests {
 
     #[test]
     fn test_analyze_patterns_fr() {
-        let results = analyze_patterns_with_locale("analyser _N:document", "fr");
+        let results = analyze_builtin("analyser _N:document", "fr");
         let has_match = results.iter().any(|r| r.match_score > 0.0);
         assert!(has_match, "Should find at least one matching French pattern");
     }
 
     #[test]
     fn test_analyze_patterns_fr_complete() {
-        let results = analyze_patterns_with_locale("analyser _N:document", "fr");
+        let results = analyze_builtin("analyser _N:document", "fr");
         let svo_match = results.iter().find(|r| r.pattern_id == "svo_basic");
         if let Some(m) = svo_match {
             assert_eq!(m.match_score, 1.0, "Should be complete match");
             assert!(m.is_complete, "Should be marked as complete");
         }
     }
+
+    #[test]
+    fn test_code_block_contents_skipped() {
+        // Particles and verbs inside the code block are not validated
+        let input = "_N:User が\n_CODE:text\nを を 分析して が\n_END\nを 説明して";
+        let result = validate_sequence(input);
+
+        assert!(!result.errors.iter().any(|e| e.code == ValidationErrorCode::ConsecutiveParticles));
+        assert!(!result.errors.iter().any(|e| e.code == ValidationErrorCode::ParticleWithoutNoun));
+    }
+
+    #[test]
+    fn test_code_block_acts_as_noun_en() {
+        let result = validate_sequence_en("explain the _CODE:py\n  x = 1\n_END");
+        assert!(!result.errors.iter().any(|e| e.code == ValidationErrorCode::ArticleNotBeforeNoun));
+    }
+
+    #[test]
+    fn test_role_sections_validated_independently() {
+        // Without sections the first verb would not be at the end
+        let input = "_ROLE:system _N:AI が _N:質問 を 回答して _ROLE:user _N:私 が _N:文書 を 要約して";
+        let result = validate_sequence_with_locale(input, "ja");
+
+        assert!(!result.errors.iter().any(|e| e.code == ValidationErrorCode::VerbNotAtEnd));
+        assert!(result.is_valid);
+    }
+
+    #[test]
+    fn test_role_section_positions_offset() {
+        let result = validate_sequence_with_locale("_ROLE:system _N:AI が _ROLE:user を _N:文書", "ja");
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::ParticleWithoutNoun)
+            .unwrap();
+
+        assert_eq!(error.position, 4);
+        assert_eq!(error.autofix.as_ref().unwrap().target_position, 4);
+    }
+
+    #[test]
+    fn test_balanced_examples() {
+        let result = validate_sequence_with_locale("_N:英語 に 翻訳して _IN:猫 _OUT:cat _IN:犬 _OUT:dog", "ja");
+        assert!(!result.errors.iter().any(|e| e.code == ValidationErrorCode::ExampleUnbalanced));
+        assert!(!result.errors.iter().any(|e| e.code == ValidationErrorCode::ExampleEmpty));
+    }
+
+    #[test]
+    fn test_unbalanced_examples() {
+        let result = validate_sequence_with_locale("_IN:a _IN:b _OUT:B 説明 _OUT:c _IN:d", "en");
+        let positions: Vec<usize> = result
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::ExampleUnbalanced)
+            .map(|e| e.position)
+            .collect();
+
+        assert_eq!(positions, vec![0, 4, 5]);
+        assert!(!result.is_valid);
+    }
+
+    #[test]
+    fn test_empty_example_side() {
+        let result = validate_sequence_with_locale("_IN:\"\" _OUT:x _IN:y _OUT:", "fr");
+        let empty: Vec<usize> = result
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::ExampleEmpty)
+            .map(|e| e.position)
+            .collect();
+
+        assert_eq!(empty, vec![0, 3]);
+        assert!(result.errors.iter().all(|e| e.severity == Severity::Warning || e.code != ValidationErrorCode::ExampleEmpty));
+    }
+
+    #[test]
+    fn test_conflicting_formats() {
+        let result = validate_sequence_with_locale("_FMT:json _N:Doc を 要約して _FMT:yaml _FMT:JSON", "ja");
+        let conflicts: Vec<usize> = result
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::ConflictingConstraints)
+            .map(|e| e.position)
+            .collect();
+
+        assert_eq!(conflicts, vec![4]);
+    }
+
+    #[test]
+    fn test_invalid_word_limit() {
+        let result = validate_sequence_with_locale("summarize the _N:report _MAXWORDS:many _MAXWORDS:0 _LANG:", "en");
+        let invalid: Vec<usize> = result
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::InvalidConstraintValue)
+            .map(|e| e.position)
+            .collect();
+
+        assert_eq!(invalid, vec![3, 4, 5]);
+        assert!(!result.is_valid);
+    }
+
+    #[test]
+    fn test_valid_constraints() {
+        let result = validate_sequence_with_locale("_N:Doc を 要約して _FMT:json _MAXWORDS:200 _LANG:en _TONE:formal", "ja");
+        assert!(!result.errors.iter().any(|e| matches!(
+            e.code,
+            ValidationErrorCode::ConflictingConstraints | ValidationErrorCode::InvalidConstraintValue
+        )));
+    }
+
+    #[test]
+    fn test_balanced_conditions() {
+        let result = validate_sequence_with_locale("_N:Doc を 要約して _IF:cite 出典 _ELSE 簡潔に _ENDIF", "ja");
+        assert!(!result.errors.iter().any(|e| e.code == ValidationErrorCode::UnbalancedCondition));
+    }
+
+    #[test]
+    fn test_unbalanced_conditions() {
+        let result = validate_sequence_with_locale("_ENDIF _IF:a x _ELSE y _ELSE z _IF:b w", "en");
+        let positions: Vec<usize> = result
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::UnbalancedCondition)
+            .map(|e| e.position)
+            .collect();
+
+        assert_eq!(positions, vec![0, 5, 1, 7]);
+        assert!(!result.is_valid);
+    }
+
+    #[test]
+    fn test_unknown_condition_variables() {
+        let variables = HashMap::from([("cite".to_string(), true)]);
+        let errors = validate_condition_variables("_IF:cite a _ENDIF _IF:!draft b _ENDIF", &variables, "en");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ValidationErrorCode::UnknownCondition);
+        assert_eq!(errors[0].position, 3);
+        assert!(errors[0].message.contains("'draft'"));
+    }
+
+    #[test]
+    fn test_undefined_nouns_in_strict_glossary() {
+        let mut glossary = Glossary {
+            entries: vec![promps::GlossaryEntry {
+                term: "注文".to_string(),
+                definition: "購入リクエスト".to_string(),
+                aliases: vec!["Order".to_string()],
+            }],
+            strict: false,
+        };
+        let input = "_N:顧客 の _N:order を _N:顧客 に 送って";
+        assert!(validate_glossary_nouns(input, &glossary, "ja").is_empty());
+
+        glossary.strict = true;
+        let errors = validate_glossary_nouns(input, &glossary, "ja");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ValidationErrorCode::UndefinedNoun);
+        assert_eq!(errors[0].position, 0);
+        assert_eq!(errors[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_classify_normalizes_width_variants() {
+        assert_eq!(TokenType::classify("!"), TokenType::Punctuation);
+        assert_eq!(TokenType::classify("?"), TokenType::Punctuation);
+        assert_eq!(TokenType::classify("，"), TokenType::Punctuation);
+        assert_eq!(TokenType::classify_en("．"), TokenType::Punctuation);
+        assert_eq!(TokenType::classify_en("。"), TokenType::Punctuation);
+        assert_eq!(TokenType::classify_en("ＴＨＥ"), TokenType::Article);
+        assert_eq!(TokenType::classify_fr("ｌａ"), TokenType::Article);
+        assert!(TokenType::is_kuten("．"));
+        assert!(TokenType::is_touten("､"));
+        assert!(!TokenType::is_touten(","));
+    }
+
+    #[test]
+    fn test_ime_variants_validate_like_canonical_forms() {
+        let ja = validate_sequence("_N:Doc を 要約して ．");
+        assert!(!ja.errors.iter().any(|e| e.code == ValidationErrorCode::KutenNotAfterVerb));
+
+        let en = validate_sequence_en("ｓｕｍｍａｒｉｚｅ the _N:report 。");
+        assert!(!en.errors.iter().any(|e| e.code == ValidationErrorCode::MissingVerb));
+        assert!(!en.errors.iter().any(|e| e.code == ValidationErrorCode::PeriodNotAtEnd));
+    }
+
+    #[test]
+    fn test_auto_locale_per_sentence() {
+        // "the" before a verb is an English error; Japanese rules apply to the first sentence
+        let input = "_N:文書 を 要約して 。 the translate _N:report .";
+        let result = validate_sequence_auto(input);
+
+        let locales: Vec<(usize, usize, &str)> = result
+            .detected_locales
+            .iter()
+            .map(|s| (s.start, s.end, s.locale.as_str()))
+            .collect();
+        assert_eq!(locales, vec![(0, 4, "ja"), (4, 8, "en")]);
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::ArticleNotBeforeNoun)
+            .unwrap();
+        assert_eq!(error.position, 4);
+    }
+
+    #[test]
+    fn test_auto_locale_inherits_previous_sentence() {
+        let result = validate_sequence_auto("résumer le _N:rapport .  _N:123");
+
+        assert_eq!(result.detected_locales.len(), 2);
+        assert_eq!(result.detected_locales[1].locale, "fr");
+    }
+
+    #[test]
+    fn test_auto_locale_defaults_to_japanese() {
+        let result = validate_sequence_auto("_N:123");
+        assert_eq!(result.detected_locales[0].locale, "ja");
+        assert!(validate_sequence_with_locale("_N:123", "ja").detected_locales.is_empty());
+    }
+
+    #[test]
+    fn test_classify_modifiers() {
+        assert_eq!(TokenType::classify("詳しく"), TokenType::Adverb);
+        assert_eq!(TokenType::classify("重要な"), TokenType::Adjective);
+        assert_eq!(TokenType::classify("_ADV:ゆっくり"), TokenType::Adverb);
+        assert_eq!(TokenType::classify_en("Quick"), TokenType::Adjective);
+        assert_eq!(TokenType::classify_en("_ADJ:tiny"), TokenType::Adjective);
+        assert_eq!(TokenType::classify_en("briefly"), TokenType::Adverb);
+        assert_eq!(TokenType::classify_fr("détaillé"), TokenType::Adjective);
+        assert_eq!(TokenType::classify_fr("clairement"), TokenType::Adverb);
+    }
+
+    #[test]
+    fn test_article_skips_adjectives() {
+        let valid = validate_sequence_en("summarize the quick _ADJ:tiny _N:summary");
+        assert!(!valid.errors.iter().any(|e| e.code == ValidationErrorCode::ArticleNotBeforeNoun));
+
+        // Adjective without a noun after the article
+        let result = validate_sequence_en("summarize the detailed .");
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::ArticleNotBeforeNoun)
+            .unwrap();
+        assert_eq!(error.position, 1);
+
+        let at_end = validate_sequence_fr("résumer le nouveau");
+        assert!(at_end.errors.iter().any(|e| e.code == ValidationErrorCode::ArticleNotBeforeNoun));
+    }
+
+    #[test]
+    fn test_adjective_position_en() {
+        let result = validate_sequence_en("summarize the _N:report detailed");
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::AdjectivePosition)
+            .unwrap();
+        assert_eq!(error.position, 3);
+        assert_eq!(error.severity, Severity::Warning);
+
+        let before = validate_sequence_en("summarize the detailed _N:report");
+        assert!(!before.errors.iter().any(|e| e.code == ValidationErrorCode::AdjectivePosition));
+    }
+
+    #[test]
+    fn test_adjective_position_fr() {
+        let before = validate_sequence_fr("résumer le détaillé _N:rapport");
+        assert_eq!(
+            before.errors.iter().filter(|e| e.code == ValidationErrorCode::AdjectivePosition).count(),
+            1
+        );
+
+        // After the noun, or a prenominal adjective before it
+        let after = validate_sequence_fr("résumer le _N:rapport détaillé");
+        let prenominal = validate_sequence_fr("créer un nouveau _N:fichier");
+        assert!(!after.errors.iter().any(|e| e.code == ValidationErrorCode::AdjectivePosition));
+        assert!(!prenominal.errors.iter().any(|e| e.code == ValidationErrorCode::AdjectivePosition));
+    }
+
+    #[test]
+    fn test_adverb_before_verb_ja() {
+        let valid = validate_sequence("_N:AI が _N:文書 を 詳しく 説明して");
+        assert!(!valid.errors.iter().any(|e| e.code == ValidationErrorCode::AdverbPosition));
+
+        let result = validate_sequence("_N:AI が _N:文書 を 説明して _ADV:ゆっくり");
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::AdverbPosition)
+            .unwrap();
+        assert_eq!(error.position, 5);
+        assert!(error.message.contains("「ゆっくり」"));
+    }
+
+    #[test]
+    fn test_optional_modifier_slots_in_template() {
+        let blocks = vec![
+            PatternBlock::fixed("promps_verb_summarize", "Action"),
+            PatternBlock::fixed("promps_article_the", "the"),
+            PatternBlock::placeholder("promps_adjective", "Adjective"),
+            PatternBlock::placeholder("promps_noun", "Object"),
+        ];
+        // Optional slot in the structure, and adjective blocks matched as optional
+        let templates = [
+            PatternTemplate::new("structure", "Structure", "", "V the [Adj] N", "", blocks.clone()),
+            PatternTemplate::new("blocks", "Blocks", "", "", "", blocks),
+        ];
+
+        for input in ["summarize the brief _N:report", "summarize the _N:report"] {
+            let results = analyze_patterns_with_templates(input, "en", &templates);
+            assert!(results.iter().all(|r| r.is_complete), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_classify_negation() {
+        assert_eq!(TokenType::classify_en("not"), TokenType::Negation);
+        assert_eq!(TokenType::classify_en("Never"), TokenType::Negation);
+        assert_eq!(TokenType::classify_fr("ne"), TokenType::Negation);
+        assert_eq!(TokenType::classify_fr("n'"), TokenType::Negation);
+        assert_eq!(TokenType::classify_fr("pas"), TokenType::Negation);
+        // Negative endings keep the Japanese token a verb
+        assert_eq!(TokenType::classify("削除しないでください"), TokenType::Verb);
+    }
+
+    #[test]
+    fn test_negation_without_verb_en() {
+        let result = validate_sequence_en("do not the _N:report");
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::NegationWithoutVerb)
+            .unwrap();
+        assert_eq!(error.severity, Severity::Warning);
+
+        let valid = validate_sequence_en("do not translate the _N:report");
+        assert!(!valid.errors.iter().any(|e| e.code == ValidationErrorCode::NegationWithoutVerb));
+    }
+
+    #[test]
+    fn test_incomplete_negation_fr() {
+        let result = validate_sequence_fr("ne traduire le _N:texte .");
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::IncompleteNegation)
+            .unwrap();
+        assert_eq!(error.position, 0);
+        assert_eq!(error.severity, Severity::Error);
+
+        let valid = validate_sequence_fr("ne pas traduire le _N:texte");
+        assert!(!valid.errors.iter().any(|e| e.code == ValidationErrorCode::IncompleteNegation));
+
+        // Colloquial negation without « ne »
+        let colloquial = validate_sequence_fr("traduire pas le _N:texte");
+        let warning = colloquial
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::IncompleteNegation)
+            .unwrap();
+        assert_eq!(warning.severity, Severity::Warning);
+
+        // « n'importe » is not a negation
+        let any = validate_sequence_fr("utilisez n'importe quel _N:format .");
+        assert!(!any.errors.iter().any(|e| e.code == ValidationErrorCode::IncompleteNegation));
+    }
+
+    #[test]
+    fn test_prohibition_clauses() {
+        let tokens = |input: &str| -> Vec<String> { input.split(' ').map(String::from).collect() };
+        let flags = |input: &str, locale: &str| -> Vec<bool> {
+            prohibition_clauses(&tokens(input), locale).into_iter().map(|(_, negated)| negated).collect()
+        };
+
+        assert_eq!(
+            prohibition_clauses(&tokens("_N:A を 分析して 、 _N:B を 削除しないでください 。"), "ja"),
+            vec![(0..4, false), (4..8, true)]
+        );
+        assert_eq!(flags("use the _N:API , not the _N:CLI .", "en"), vec![false]);
+        assert_eq!(flags("you shouldn't translate the _N:title .", "en"), vec![true]);
+        assert_eq!(flags("do not use the _N:CLI .", "en"), vec![true]);
+        assert_eq!(flags("utilisez n'importe quel _N:format .", "fr"), vec![false]);
+        assert_eq!(flags("n'utilisez pas le _N:format .", "fr"), vec![true]);
+        assert_eq!(flags("Don’t translate the _N:title .", "en"), vec![true]);
+        assert_eq!(flags("ajoutez plus de _N:détails .", "fr"), vec![false]);
+        // A bare ending is not a negative verb
+        assert_eq!(flags("_N:文書 を ないで 要約して 。", "ja"), vec![false]);
+    }
+
+    #[test]
+    fn test_negative_verb_ja() {
+        let result = validate_sequence("_N:AI が _N:個人情報 を 含めないでください");
+        assert!(result.is_valid);
+        assert!(!result.errors.iter().any(|e| e.code == ValidationErrorCode::MissingVerb));
+    }
+
+    #[test]
+    fn test_classify_conjunction() {
+        assert_eq!(TokenType::classify("そして"), TokenType::Conjunction);
+        assert_eq!(TokenType::classify_en("then"), TokenType::Conjunction);
+        assert_eq!(TokenType::classify_en("And"), TokenType::Conjunction);
+        assert_eq!(TokenType::classify_fr("puis"), TokenType::Conjunction);
+        assert!(TokenType::is_te_form("読み込んで"));
+        assert!(TokenType::is_te_form("_V:要約して"));
+        assert!(!TokenType::is_te_form("実行する"));
+    }
+
+    #[test]
+    fn test_compound_sentence_ja() {
+        // て-form chaining with a shared object
+        let chained = validate_sequence("_N:AI が _N:ファイル を 読み込んで 、 要約して");
+        assert!(chained.is_valid);
+        assert_eq!(chained.warning_count, 0);
+
+        let with_conjunction = validate_sequence("_N:AI が _N:ファイル を 翻訳して そして _N:要点 を 要約して");
+        assert_eq!(with_conjunction.warning_count, 0);
+
+        // Each clause still needs its verb at the end
+        let result = validate_sequence("_N:AI が _N:ファイル を 読み込んで 、 要約して _N:結果");
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::VerbNotAtEnd)
+            .unwrap();
+        assert_eq!(error.position, 6);
+    }
+
+    #[test]
+    fn test_missing_object_per_clause_ja() {
+        let result = validate_sequence("_N:AI が 要約して 、 _N:文書 を 翻訳して");
+        let errors: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::MissingObject)
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].position, 2);
+    }
+
+    #[test]
+    fn test_missing_object_not_shared_across_sentences() {
+        let result = validate_sequence("_N:ファイル を 読み込んで 。 _N:ユーザー が 要約して 。");
+        let errors: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::MissingObject)
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].position, 6);
+    }
+
+    #[test]
+    fn test_compound_sentence_en() {
+        let valid = validate_sequence_en("read the _N:file and summarize it");
+        assert_eq!(valid.warning_count, 0);
+        assert!(valid.is_valid);
+
+        // "then" starts a clause that needs its own verb
+        let result = validate_sequence_en("summarize the _N:report then the _N:notes");
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::MissingVerb)
+            .unwrap();
+        assert_eq!(error.position, 3);
+
+        // "and" between nouns does not start a clause
+        let nouns = validate_sequence_en("compare the _N:report and the _N:summary");
+        assert!(!nouns.errors.iter().any(|e| e.code == ValidationErrorCode::MissingVerb));
+    }
+
+    #[test]
+    fn test_compound_sentence_fr() {
+        let valid = validate_sequence_fr("lire le _N:fichier puis résumer le _N:texte");
+        assert_eq!(valid.warning_count, 0);
+
+        let result = validate_sequence_fr("résumer le _N:rapport puis les _N:notes");
+        assert!(result.errors.iter().any(|e| e.code == ValidationErrorCode::MissingVerb && e.position == 3));
+    }
+
+    #[test]
+    fn test_dangling_conjunction() {
+        let ja = validate_sequence("_N:AI が _N:文書 を 要約して そして 。");
+        assert!(ja.errors.iter().any(|e| e.code == ValidationErrorCode::DanglingConjunction && e.position == 5));
+
+        let en = validate_sequence_en("summarize the _N:report and");
+        let error = en
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::DanglingConjunction)
+            .unwrap();
+        assert_eq!(error.position, 3);
+        assert_eq!(error.severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_list_is_a_single_noun() {
+        let result = validate_sequence_with_locale("_N:AI が _LIST _N:名前 _N:住所 _ENDLIST を 抽出して", "ja");
+        assert!(result.is_valid);
+        assert_eq!(result.warning_count, 0);
+
+        // Positions after a list refer to the whole input
+        let result = validate_sequence_with_locale("_LIST _N:A , _N:B _ENDLIST が を", "ja");
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::ConsecutiveParticles)
+            .unwrap();
+        assert_eq!(error.position, 6);
+    }
+
+    #[test]
+    fn test_list_items_on_lines_auto_locale() {
+        let result = validate_sequence_auto("_N:AI が _LIST\n_N:名前\n_N:住所 。\n_ENDLIST を 抽出して");
+        assert!(result.is_valid);
+        assert_eq!(result.warning_count, 0);
+        assert_eq!(result.detected_locales.len(), 1);
+    }
+
+    #[test]
+    fn test_unbalanced_list() {
+        let unclosed = validate_sequence_with_locale("_V:compare _LIST _N:A _N:B", "en");
+        let error = unclosed
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::UnbalancedList)
+            .unwrap();
+        assert_eq!(error.position, 1);
+        assert!(error.message.contains("_ENDLIST"));
+
+        let stray = validate_sequence_with_locale("_N:A _ENDLIST", "fr");
+        assert!(stray.errors.iter().any(|e| e.code == ValidationErrorCode::UnbalancedList && e.position == 1));
+
+        let nested = validate_sequence_with_locale("_LIST _N:A _LIST _N:B _ENDLIST", "ja");
+        assert!(nested.errors.iter().any(|e| e.code == ValidationErrorCode::UnbalancedList && e.position == 2));
+    }
+
+    #[test]
+    fn test_list_crossing_condition() {
+        let codes = |input: &str| -> Vec<(ValidationErrorCode, usize)> {
+            validate_sequence_with_locale(input, "ja")
+                .errors
+                .iter()
+                .filter(|e| matches!(e.code, ValidationErrorCode::UnbalancedList | ValidationErrorCode::UnbalancedCondition))
+                .map(|e| (e.code, e.position))
+                .collect()
+        };
+
+        // _IF: inside the list ends with the list; its _ENDIF is stray
+        let crossed = codes("_N:Z を _LIST _IF:x _N:A _ENDLIST _N:B _ENDIF");
+        assert!(crossed.contains(&(ValidationErrorCode::UnbalancedCondition, 3)));
+        assert!(crossed.contains(&(ValidationErrorCode::UnbalancedCondition, 7)));
+
+        // List inside the _IF: ends with the section; its _ENDLIST is stray
+        let crossed = codes("_IF:x _LIST _N:A _ENDIF _N:B _ENDLIST");
+        assert!(crossed.contains(&(ValidationErrorCode::UnbalancedList, 1)));
+        assert!(crossed.contains(&(ValidationErrorCode::UnbalancedList, 5)));
+
+        assert!(codes("_LIST _N:A 、 _IF:x _N:B _ENDIF _ENDLIST").is_empty());
+        assert!(codes("_IF:x _LIST _N:A _ENDLIST _ELSE _N:B _ENDIF").is_empty());
+    }
+
+    #[test]
+    fn test_empty_list_items() {
+        let result = validate_sequence_with_locale("_LIST _N:A 、 、 _N:B _ENDLIST", "ja");
+        let warning = result
+            .errors
+            .iter()
+            .find(|e| e.code == ValidationErrorCode::EmptyListItem)
+            .unwrap();
+        assert_eq!(warning.position, 3);
+        assert_eq!(warning.severity, Severity::Warning);
+
+        let empty = validate_sequence_with_locale("_V:compare _LIST _ENDLIST", "en");
+        assert!(empty.errors.iter().any(|e| e.code == ValidationErrorCode::EmptyListItem && e.position == 1));
+    }
 }
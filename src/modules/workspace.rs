@@ -0,0 +1,471 @@
+/// Promps - Blockly Workspace Compiler Module
+///
+/// This module walks Blockly's serialized workspace JSON (as saved in the
+/// `workspace` field of a `.promps` file) and emits the same DSL as
+/// `getWorkspaceCode()` in `res/js/blockly-config.js`. It also imports
+/// DSL text back into a workspace so existing prompts can be edited as blocks.
+///
+/// Serialization format:
+/// ```json
+/// {"blocks": {"blocks": [
+///   {"type": "promps_noun", "x": 20, "y": 20, "fields": {"TEXT": "User"},
+///    "next": {"block": {"type": "promps_particle_ga"}}}
+/// ]}}
+/// ```
+
+use std::collections::HashMap;
+
+use promps::tokenizer::{format_token, quote_text, split_tokens};
+use serde_json::{json, Value};
+
+use super::validation::{block_word, find_block_type, find_verb_block, TokenType};
+
+/// Vertical spacing between imported sentences
+const IMPORT_ROW_HEIGHT: i64 = 80;
+/// Left/top margin of imported blocks
+const IMPORT_MARGIN: i64 = 20;
+
+/// Blockly's scan angle for ordering top blocks (`WorkspaceSvg.SCAN_ANGLE`)
+const SCAN_ANGLE_DEGREES: f64 = 3.0;
+
+/// Get top-level blocks in the order of `workspace.getTopBlocks(true)`
+///
+/// Blocks are sorted top to bottom, with a slight tilt so blocks further
+/// right come later (Blockly's LTR ordering).
+fn top_blocks(workspace: &Value) -> Vec<&Value> {
+    let mut blocks: Vec<&Value> = workspace
+        .get("blocks")
+        .and_then(|b| b.get("blocks"))
+        .and_then(|b| b.as_array())
+        .map(|blocks| blocks.iter().collect())
+        .unwrap_or_default();
+
+    let offset = SCAN_ANGLE_DEGREES.to_radians().sin();
+    let key = |block: &Value| {
+        let x = block.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let y = block.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        y + offset * x
+    };
+    blocks.sort_by(|a, b| key(a).partial_cmp(&key(b)).unwrap_or(std::cmp::Ordering::Equal));
+
+    blocks
+}
+
+/// Text field value of a block (`fields.TEXT`)
+fn text_field(block: &Value) -> &str {
+    named_field(block, "TEXT")
+}
+
+/// Field value of a block (`fields.<name>`)
+fn named_field<'a>(block: &'a Value, name: &str) -> &'a str {
+    block
+        .get("fields")
+        .and_then(|f| f.get(name))
+        .and_then(|t| t.as_str())
+        .unwrap_or("")
+}
+
+/// DSL code of a single block (without its `next` chain)
+///
+/// Mirrors the `javascriptGenerator.forBlock[...]` functions: every block
+/// emits its text followed by a space, and blocks with empty output emit nothing.
+/// Unknown block types produce no code.
+pub fn block_to_code(block: &Value, locale: &str) -> String {
+    let block_type = block.get("type").and_then(|t| t.as_str()).unwrap_or("");
+
+    match block_type {
+        "promps_noun" => format!("_N:{} ", quote_text(text_field(block))),
+        "promps_other" => format!("{} ", text_field(block)),
+        "promps_verb_custom" => format!("_V:{} ", quote_text(text_field(block))),
+        "promps_adjective" => format!("_ADJ:{} ", quote_text(text_field(block))),
+        "promps_adverb" => format!("_ADV:{} ", quote_text(text_field(block))),
+        "promps_example" => format!(
+            "_IN:{} _OUT:{} ",
+            quote_text(named_field(block, "INPUT")),
+            quote_text(named_field(block, "OUTPUT"))
+        ),
+        bt if bt.starts_with("promps_verb_") => match block_word(bt, locale) {
+            Some(word) => format!("_V:{} ", word),
+            None => String::new(),
+        },
+        bt => match block_word(bt, locale) {
+            Some(word) if !word.is_empty() => format!("{} ", word),
+            _ => String::new(),
+        },
+    }
+}
+
+/// Generate DSL code for a block and the blocks chained after it
+fn chain_to_code(block: &Value, locale: &str, code: &mut String) {
+    let mut current = Some(block);
+
+    while let Some(block) = current {
+        // Disabled blocks are skipped, but the chain continues
+        if block.get("enabled").and_then(|e| e.as_bool()) != Some(false) {
+            code.push_str(&block_to_code(block, locale));
+        }
+        current = block.get("next").and_then(|n| n.get("block"));
+    }
+}
+
+/// Compile a serialized Blockly workspace into DSL
+///
+/// # Arguments
+/// * `workspace` - Workspace JSON from `Blockly.serialization.workspaces.save`
+/// * `locale` - Locale code ("ja", "en", "fr") for fixed block output
+///
+/// # Returns
+/// DSL text (same as the frontend preview)
+#[cfg(test)]
+pub fn workspace_to_dsl(workspace: &Value, locale: &str) -> String {
+    workspace_to_dsl_with_roles(workspace, locale, &HashMap::new())
+}
+
+/// Compile a serialized Blockly workspace into DSL with chat roles
+///
+/// # Arguments
+/// * `workspace` - Workspace JSON from `Blockly.serialization.workspaces.save`
+/// * `locale` - Locale code ("ja", "en", "fr") for fixed block output
+/// * `roles` - Role of each top-level block stack (top block id -> role),
+///   as stored in the `roles` field of a `.promps` file
+///
+/// # Returns
+/// DSL text with a `_ROLE:` marker before every stack that has a role
+pub fn workspace_to_dsl_with_roles(workspace: &Value, locale: &str, roles: &HashMap<String, String>) -> String {
+    let mut code = String::new();
+
+    for block in top_blocks(workspace) {
+        let mut stack = String::new();
+        chain_to_code(block, locale, &mut stack);
+        if stack.is_empty() {
+            continue;
+        }
+
+        let role = block.get("id").and_then(|id| id.as_str()).and_then(|id| roles.get(id));
+        if let Some(role) = role {
+            code.push_str(&format!("_ROLE:{} ", role));
+        }
+        code.push_str(&stack);
+    }
+
+    code.trim().to_string()
+}
+
+// ============================================================================
+// DSL Import
+// ============================================================================
+
+/// Classify a token for a locale
+fn classify(token: &str, locale: &str) -> TokenType {
+    match locale {
+        "en" => TokenType::classify_en(token),
+        "fr" => TokenType::classify_fr(token),
+        _ => TokenType::classify(token),
+    }
+}
+
+/// Check if a block type ends a sentence
+fn is_sentence_end(block_type: &str) -> bool {
+    matches!(
+        block_type,
+        "promps_punct_kuten" | "promps_punct_period" | "promps_punct_exclaim" | "promps_punct_question"
+    )
+}
+
+/// Convert DSL tokens into (block type, fields) pairs
+///
+/// - `_N:text` becomes a noun block
+/// - `_ADJ:text` / `_ADV:text` become adjective / adverb blocks
+/// - `_IN:input _OUT:output` becomes an example block
+/// - Fixed verbs (with or without `_V:`) become their verb block, other verbs `promps_verb_custom`
+/// - Particles, articles and punctuation become their fixed block
+/// - Anything else becomes a `promps_other` block
+fn tokens_to_blocks(tokens: &[&str], locale: &str) -> Vec<(&'static str, Option<Value>)> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    let text = |text: &str| Some(json!({ "TEXT": text }));
+
+    while i < tokens.len() {
+        let token = tokens[i];
+
+        if let Some(noun) = token.strip_prefix("_N:") {
+            blocks.push(("promps_noun", text(noun)));
+            i += 1;
+            continue;
+        }
+
+        if let Some(adjective) = token.strip_prefix("_ADJ:") {
+            blocks.push(("promps_adjective", text(adjective)));
+            i += 1;
+            continue;
+        }
+
+        if let Some(adverb) = token.strip_prefix("_ADV:") {
+            blocks.push(("promps_adverb", text(adverb)));
+            i += 1;
+            continue;
+        }
+
+        if let Some(input) = token.strip_prefix("_IN:") {
+            let output = tokens.get(i + 1).and_then(|t| t.strip_prefix("_OUT:"));
+            blocks.push((
+                "promps_example",
+                Some(json!({ "INPUT": input, "OUTPUT": output.unwrap_or("") })),
+            ));
+            i += if output.is_some() { 2 } else { 1 };
+            continue;
+        }
+
+        if let Some((block_type, len)) = find_verb_block(tokens, i, locale) {
+            blocks.push((block_type, None));
+            i += len;
+            continue;
+        }
+
+        if let Some(verb) = token.strip_prefix("_V:") {
+            blocks.push(("promps_verb_custom", text(verb)));
+        } else if let Some(block_type) = find_block_type(token, locale) {
+            blocks.push((block_type, None));
+        } else if classify(token, locale) == TokenType::Verb {
+            blocks.push(("promps_verb_custom", text(token)));
+        } else {
+            // Kept in DSL form so quoted markers (_FILE:"my notes.md") survive export
+            blocks.push(("promps_other", text(&format_token(token))));
+        }
+        i += 1;
+    }
+
+    blocks
+}
+
+/// Build a `next` chain from blocks
+fn build_chain(blocks: &[(&'static str, Option<Value>)]) -> Option<Value> {
+    blocks.iter().rev().fold(None, |next, (block_type, fields)| {
+        let mut block = json!({ "type": block_type });
+        if let Some(fields) = fields {
+            block["fields"] = fields.clone();
+        }
+        if let Some(next) = next {
+            block["next"] = json!({ "block": next });
+        }
+        Some(block)
+    })
+}
+
+/// Import DSL text into a serialized Blockly workspace
+///
+/// Each sentence becomes its own block chain, stacked top to bottom, so
+/// `workspace_to_dsl_with_roles` reproduces the input.
+///
+/// # Arguments
+/// * `input` - DSL text (quoted tokens such as `_N:"New York"` keep their spaces)
+/// * `locale` - Locale code ("ja", "en", "fr") used to recognize fixed blocks
+///
+/// # Returns
+/// Workspace JSON for `Blockly.serialization.workspaces.load`
+pub fn dsl_to_workspace(input: &str, locale: &str) -> Value {
+    let owned_tokens = split_tokens(input);
+    let tokens: Vec<&str> = owned_tokens.iter().map(|t| t.as_str()).collect();
+    let blocks = tokens_to_blocks(&tokens, locale);
+
+    // Split into sentences (terminator stays with its sentence)
+    let mut sentences: Vec<&[(&'static str, Option<Value>)]> = Vec::new();
+    let mut start = 0;
+    for (i, (block_type, _)) in blocks.iter().enumerate() {
+        if is_sentence_end(block_type) {
+            sentences.push(&blocks[start..=i]);
+            start = i + 1;
+        }
+    }
+    if start < blocks.len() {
+        sentences.push(&blocks[start..]);
+    }
+
+    let top_blocks: Vec<Value> = sentences
+        .iter()
+        .enumerate()
+        .filter_map(|(row, sentence)| {
+            let mut chain = build_chain(sentence)?;
+            chain["x"] = json!(IMPORT_MARGIN);
+            chain["y"] = json!(IMPORT_MARGIN + row as i64 * IMPORT_ROW_HEIGHT);
+            Some(chain)
+        })
+        .collect();
+
+    json!({
+        "blocks": {
+            "languageVersion": 0,
+            "blocks": top_blocks
+        }
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fixtures shared with `res/tests/workspace-parity.test.js`
+    const PARITY_FIXTURES: &str = include_str!("../../res/tests/fixtures/workspace-dsl.json");
+
+    #[test]
+    fn test_parity_with_js_generator() {
+        let fixtures: Value = serde_json::from_str(PARITY_FIXTURES).unwrap();
+
+        for case in fixtures.as_array().unwrap() {
+            let locale = case["locale"].as_str().unwrap();
+            let roles: HashMap<String, String> =
+                serde_json::from_value(case.get("roles").cloned().unwrap_or(json!({}))).unwrap();
+            assert_eq!(
+                workspace_to_dsl_with_roles(&case["workspace"], locale, &roles),
+                case["dsl"].as_str().unwrap(),
+                "fixture: {}",
+                case["name"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_empty_workspace() {
+        assert_eq!(workspace_to_dsl(&json!({}), "ja"), "");
+        assert_eq!(workspace_to_dsl(&json!({"blocks": {"blocks": []}}), "ja"), "");
+    }
+
+    #[test]
+    fn test_next_chain() {
+        let workspace = json!({"blocks": {"blocks": [{
+            "type": "promps_noun", "fields": {"TEXT": "Doc"},
+            "next": {"block": {"type": "promps_particle_wo",
+                "next": {"block": {"type": "promps_verb_summarize"}}}}
+        }]}});
+
+        assert_eq!(workspace_to_dsl(&workspace, "ja"), "_N:Doc を _V:要約して");
+    }
+
+    #[test]
+    fn test_top_blocks_ordered_by_position() {
+        let workspace = json!({"blocks": {"blocks": [
+            {"type": "promps_verb_analyze", "x": 10, "y": 200},
+            {"type": "promps_noun", "x": 10, "y": 10, "fields": {"TEXT": "Doc"}},
+            {"type": "promps_particle_wo", "x": 300, "y": 10}
+        ]}});
+
+        assert_eq!(workspace_to_dsl(&workspace, "ja"), "_N:Doc を _V:分析して");
+    }
+
+    #[test]
+    fn test_disabled_block_skipped() {
+        let workspace = json!({"blocks": {"blocks": [{
+            "type": "promps_noun", "fields": {"TEXT": "Doc"},
+            "next": {"block": {"type": "promps_particle_ga", "enabled": false,
+                "next": {"block": {"type": "promps_particle_wo"}}}}
+        }]}});
+
+        assert_eq!(workspace_to_dsl(&workspace, "ja"), "_N:Doc を");
+    }
+
+    #[test]
+    fn test_unknown_block_ignored() {
+        let workspace = json!({"blocks": {"blocks": [{
+            "type": "promps_noun", "fields": {"TEXT": "Doc"},
+            "next": {"block": {"type": "controls_if"}}
+        }]}});
+
+        assert_eq!(workspace_to_dsl(&workspace, "ja"), "_N:Doc");
+    }
+
+    #[test]
+    fn test_import_round_trips_parity_fixtures() {
+        let fixtures: Value = serde_json::from_str(PARITY_FIXTURES).unwrap();
+
+        for case in fixtures.as_array().unwrap() {
+            let locale = case["locale"].as_str().unwrap();
+            let dsl = case["dsl"].as_str().unwrap();
+            assert_eq!(workspace_to_dsl(&dsl_to_workspace(dsl, locale), locale), dsl, "fixture: {}", case["name"]);
+        }
+    }
+
+    #[test]
+    fn test_import_block_types() {
+        let workspace = dsl_to_workspace("_N:Doc を _V:分析して _V:整理して ください", "ja");
+        let first = &workspace["blocks"]["blocks"][0];
+
+        assert_eq!(first["type"], "promps_noun");
+        assert_eq!(first["fields"]["TEXT"], "Doc");
+        assert_eq!(first["next"]["block"]["type"], "promps_particle_wo");
+
+        let verb = &first["next"]["block"]["next"]["block"];
+        assert_eq!(verb["type"], "promps_verb_analyze");
+        assert_eq!(verb["next"]["block"]["type"], "promps_verb_custom");
+        assert_eq!(verb["next"]["block"]["fields"]["TEXT"], "整理して");
+        assert_eq!(verb["next"]["block"]["next"]["block"]["type"], "promps_other");
+    }
+
+    #[test]
+    fn test_import_plain_text_verbs() {
+        // Verbs typed without the _V: marker are still recognized
+        let workspace = dsl_to_workspace("_N:Doc を 要約して", "ja");
+        let verb = &workspace["blocks"]["blocks"][0]["next"]["block"]["next"]["block"];
+        assert_eq!(verb["type"], "promps_verb_summarize");
+
+        assert_eq!(workspace_to_dsl(&workspace, "ja"), "_N:Doc を _V:要約して");
+    }
+
+    #[test]
+    fn test_import_multi_word_french_verb() {
+        let workspace = dsl_to_workspace("_V:mettre à jour le _N:fichier", "fr");
+        let first = &workspace["blocks"]["blocks"][0];
+
+        assert_eq!(first["type"], "promps_verb_update");
+        assert_eq!(first["next"]["block"]["type"], "promps_article_the");
+    }
+
+    #[test]
+    fn test_import_sentences_stacked() {
+        let workspace = dsl_to_workspace("_N:A を _V:分析して 。 _N:B を _V:要約して 。", "ja");
+        let blocks = workspace["blocks"]["blocks"].as_array().unwrap();
+
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[1]["y"].as_i64().unwrap() > blocks[0]["y"].as_i64().unwrap());
+    }
+
+    #[test]
+    fn test_import_example_pair() {
+        let input = "_V:translate _IN:\"good morning\" _OUT:bonjour _IN:x";
+        let workspace = dsl_to_workspace(input, "en");
+        let example = &workspace["blocks"]["blocks"][0]["next"]["block"];
+
+        assert_eq!(example["type"], "promps_example");
+        assert_eq!(example["fields"]["INPUT"], "good morning");
+        assert_eq!(example["fields"]["OUTPUT"], "bonjour");
+        assert_eq!(example["next"]["block"]["fields"]["OUTPUT"], "");
+        assert_eq!(workspace_to_dsl(&workspace, "en"), "_V:translate _IN:\"good morning\" _OUT:bonjour _IN:x _OUT:");
+    }
+
+    #[test]
+    fn test_import_quoted_marker_round_trip() {
+        let input = "_FILE:\"my notes.md\" を 要約して 。";
+        let workspace = dsl_to_workspace(input, "ja");
+        let first = &workspace["blocks"]["blocks"][0];
+
+        assert_eq!(first["type"], "promps_other");
+        assert_eq!(first["fields"]["TEXT"], "_FILE:\"my notes.md\"");
+        assert_eq!(workspace_to_dsl(&workspace, "ja"), "_FILE:\"my notes.md\" を _V:要約して 。");
+    }
+
+    #[test]
+    fn test_import_empty_input() {
+        let workspace = dsl_to_workspace("   ", "ja");
+        assert_eq!(workspace["blocks"]["blocks"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_empty_particles_in_english() {
+        let block = json!({"type": "promps_particle_ga"});
+        assert_eq!(block_to_code(&block, "en"), "");
+        assert_eq!(block_to_code(&block, "ja"), "が ");
+    }
+}
@@ -0,0 +1,525 @@
+//! Promps DSL Tokenizer
+//!
+//! Splits DSL text into tokens. Shared by the prompt generator
+//! (`parse_input`) and the grammar validator so both see the same tokens.
+//!
+//! # Syntax
+//! - Tokens are separated by delimiters (space, tab and full-width space U+3000)
+//! - Two or more delimiters in a row, or a line break, start a new sentence
+//! - `_X:` at the start of a token is a marker (`_N:` noun, `_V:` verb, ...)
+//! - A marker may be followed by a quoted text: `_N:"New York"`
+//! - Backslash escapes: `\"`, `\\`, `\ ` (delimiter), and `\_` at token start
+//!   (`\_N:text` is literal text, not a noun)
+//!
+//! A `"` that does not follow a marker is an ordinary character, so the
+//! quote punctuation blocks keep working.
+//!
+//! # Verbatim blocks
+//! `_CODE:lang` starts a verbatim block that runs until an `_END` token.
+//! Its content is kept byte for byte (indentation, line breaks) and the
+//! whole block is a single token:
+//!
+//! ```text
+//! _CODE:json
+//! {
+//!   "id": 1
+//! }
+//! _END
+//! ```
+
+/// Tokenizer settings
+#[derive(Debug, Clone)]
+pub struct TokenizerConfig {
+    /// Characters that separate tokens
+    pub delimiters: Vec<char>,
+    /// Number of consecutive delimiters that start a new sentence
+    pub sentence_break: usize,
+}
+
+impl Default for TokenizerConfig {
+    fn default() -> Self {
+        TokenizerConfig {
+            delimiters: vec![' ', '\t', '\u{3000}'],
+            sentence_break: 2,
+        }
+    }
+}
+
+/// Marker that starts a verbatim block
+pub const VERBATIM_MARKER: &str = "CODE";
+
+/// Token that ends a verbatim block
+pub const VERBATIM_END: &str = "_END";
+
+impl TokenizerConfig {
+    fn is_delimiter(&self, c: char) -> bool {
+        self.delimiters.contains(&c)
+    }
+}
+
+/// A DSL token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Marker name without `_` and `:` (e.g. "N", "V")
+    pub marker: Option<String>,
+    /// Token text with marker, quotes and escapes removed
+    pub text: String,
+    /// Whether the text was quoted
+    pub quoted: bool,
+    /// Whether the token started with an escaped marker (`\_N:`)
+    pub escaped: bool,
+    /// Whether a new sentence starts at this token
+    pub sentence_break: bool,
+    /// Content of a verbatim block (`_CODE:lang` ... `_END`); `text` holds the language
+    pub verbatim: Option<String>,
+    /// Byte offset of the token in the input
+    pub start: usize,
+    /// Byte offset after the token
+    pub end: usize,
+}
+
+impl Token {
+    /// Check if the token is a noun (`_N:`)
+    pub fn is_noun(&self) -> bool {
+        self.marker.as_deref() == Some("N")
+    }
+
+    /// Token in single-token DSL form for classification
+    ///
+    /// Markers are kept (`_N:New York`), and escaped markers keep their
+    /// backslash so they are not classified as markers.
+    pub fn as_dsl(&self) -> String {
+        if let Some(content) = &self.verbatim {
+            return format!("_{}:{}\n{}\n{}", VERBATIM_MARKER, self.text, content, VERBATIM_END);
+        }
+        match &self.marker {
+            Some(marker) => format!("_{}:{}", marker, self.text),
+            None if self.escaped => format!("\\{}", self.text),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// Tokenize DSL text with the default settings
+pub fn tokenize(input: &str) -> Vec<Token> {
+    tokenize_with(input, &TokenizerConfig::default())
+}
+
+/// Tokenize DSL text
+///
+/// # Arguments
+/// * `input` - DSL text
+/// * `config` - Delimiters and sentence break settings
+///
+/// # Returns
+/// Tokens in input order
+pub fn tokenize_with(input: &str, config: &TokenizerConfig) -> Vec<Token> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let n = chars.len();
+    let byte_at = |i: usize| if i < n { chars[i].0 } else { input.len() };
+    let is_newline = |c: char| c == '\n' || c == '\r';
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    loop {
+        // Skip delimiters, counting them for sentence breaks
+        let mut gap = 0;
+        while i < n && (config.is_delimiter(chars[i].1) || is_newline(chars[i].1)) {
+            gap += if is_newline(chars[i].1) { config.sentence_break } else { 1 };
+            i += 1;
+        }
+        if i >= n {
+            break;
+        }
+
+        let start = byte_at(i);
+        let mut marker = None;
+        let mut escaped = false;
+        let mut quoted = false;
+        let mut text = String::new();
+
+        // Marker: _ + uppercase letters + :
+        if chars[i].1 == '_' {
+            let mut j = i + 1;
+            while j < n && chars[j].1.is_ascii_uppercase() {
+                j += 1;
+            }
+            if j > i + 1 && j < n && chars[j].1 == ':' {
+                marker = Some(input[byte_at(i + 1)..byte_at(j)].to_string());
+                i = j + 1;
+            }
+        } else if chars[i].1 == '\\' && i + 1 < n && chars[i + 1].1 == '_' {
+            // Escaped marker: literal text
+            escaped = true;
+            text.push('_');
+            i += 2;
+        }
+
+        // Quoted text after a marker
+        let mut in_quote = false;
+        if marker.is_some() && i < n && chars[i].1 == '"' {
+            quoted = true;
+            in_quote = true;
+            i += 1;
+        }
+
+        while i < n {
+            let c = chars[i].1;
+            let next = if i + 1 < n { Some(chars[i + 1].1) } else { None };
+
+            if is_newline(c) {
+                // Line breaks end the token, even inside an unterminated quote
+                break;
+            }
+            if in_quote {
+                match (c, next) {
+                    ('\\', Some(e)) if e == '"' || e == '\\' => {
+                        text.push(e);
+                        i += 2;
+                        continue;
+                    }
+                    ('"', _) => in_quote = false,
+                    _ => text.push(c),
+                }
+            } else {
+                if config.is_delimiter(c) {
+                    break;
+                }
+                match (c, next) {
+                    ('\\', Some(e)) if e == '"' || e == '\\' || config.is_delimiter(e) => {
+                        text.push(e);
+                        i += 2;
+                        continue;
+                    }
+                    _ => text.push(c),
+                }
+            }
+            i += 1;
+        }
+
+        // Verbatim block: everything up to the closing _END
+        let mut verbatim = None;
+        if marker.as_deref() == Some(VERBATIM_MARKER) && !quoted {
+            let (content, next) = read_verbatim(&chars, i, input, config);
+            verbatim = Some(content);
+            i = next;
+        }
+
+        tokens.push(Token {
+            marker,
+            text,
+            quoted,
+            escaped,
+            sentence_break: !tokens.is_empty() && gap >= config.sentence_break,
+            verbatim,
+            start,
+            end: byte_at(i),
+        });
+    }
+
+    tokens
+}
+
+/// Read the content of a verbatim block
+///
+/// `i` is the char index right after `_CODE:lang`. The content starts on the
+/// next line (or after one delimiter for inline blocks) and ends before an
+/// `_END` token. An unterminated block runs to the end of the input.
+///
+/// # Returns
+/// The content and the char index after `_END`
+fn read_verbatim(chars: &[(usize, char)], i: usize, input: &str, config: &TokenizerConfig) -> (String, usize) {
+    let n = chars.len();
+    let byte_at = |i: usize| if i < n { chars[i].0 } else { input.len() };
+    let is_newline = |c: char| c == '\n' || c == '\r';
+    let is_boundary = |i: usize| i >= n || config.is_delimiter(chars[i].1) || is_newline(chars[i].1);
+    let end_len = VERBATIM_END.chars().count();
+
+    // Skip the rest of the header line if it is blank, otherwise one delimiter
+    let mut content_start = i;
+    while content_start < n && config.is_delimiter(chars[content_start].1) {
+        content_start += 1;
+    }
+    if content_start < n && chars[content_start].1 == '\r' {
+        content_start += 1;
+    }
+    if content_start < n && chars[content_start].1 == '\n' {
+        content_start += 1;
+    } else {
+        content_start = (i + 1).min(n);
+    }
+
+    // Find _END at a token boundary
+    let mut j = content_start;
+    let close = loop {
+        if j >= n {
+            break None;
+        }
+        let at_start = j == content_start || is_boundary(j - 1);
+        if at_start && input[byte_at(j)..].starts_with(VERBATIM_END) && is_boundary(j + end_len) {
+            break Some(j);
+        }
+        j += 1;
+    };
+
+    let content_end = close.unwrap_or(n);
+    let mut content = input[byte_at(content_start)..byte_at(content_end)].to_string();
+
+    // Drop the line break (or delimiter) before _END
+    if content.ends_with('\n') {
+        content.pop();
+        if content.ends_with('\r') {
+            content.pop();
+        }
+    } else if close.is_some() && content.chars().last().is_some_and(|c| config.is_delimiter(c)) {
+        content.pop();
+    }
+
+    (content, close.map(|c| c + end_len).unwrap_or(n))
+}
+
+/// Split DSL text into single-token strings (see `Token::as_dsl`)
+///
+/// Drop-in replacement for `split_whitespace` that understands quotes and escapes.
+pub fn split_tokens(input: &str) -> Vec<String> {
+    tokenize(input).iter().map(|t| t.as_dsl()).collect()
+}
+
+/// Quote text for use after a marker if it would not survive tokenizing
+///
+/// # Returns
+/// The text unchanged, or `"..."` with `"` and `\` escaped
+pub fn quote_text(text: &str) -> String {
+    let config = TokenizerConfig::default();
+    let needs_quotes = text
+        .chars()
+        .any(|c| config.is_delimiter(c) || c == '"' || c == '\\' || c == '\n' || c == '\r');
+
+    if !needs_quotes {
+        return text.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        // Line breaks cannot be quoted; they become spaces
+        quoted.push(if c == '\n' || c == '\r' { ' ' } else { c });
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Format a single-token string (as from `split_tokens`) back into DSL
+///
+/// Marker text is quoted when needed (`_N:New York` becomes `_N:"New York"`).
+pub fn format_token(token: &str) -> String {
+    // Verbatim blocks are already in DSL form
+    if token.starts_with(&format!("_{}:", VERBATIM_MARKER)) {
+        return token.to_string();
+    }
+
+    let chars: Vec<char> = token.chars().collect();
+    let marker_len = match chars.first() {
+        Some('_') => {
+            let letters = chars[1..].iter().take_while(|c| c.is_ascii_uppercase()).count();
+            if letters > 0 && chars.get(1 + letters) == Some(&':') {
+                Some(letters + 2)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+
+    match marker_len {
+        Some(len) => {
+            let (marker, text) = token.split_at(chars[..len].iter().map(|c| c.len_utf8()).sum());
+            format!("{}{}", marker, quote_text(text))
+        }
+        None => token.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(input: &str) -> Vec<String> {
+        tokenize(input).into_iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn test_plain_tokens() {
+        let tokens = tokenize("_N:User が _V:分析して");
+
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[0].marker.as_deref(), Some("N"));
+        assert_eq!(tokens[0].text, "User");
+        assert_eq!(tokens[1].marker, None);
+        assert_eq!(tokens[2].marker.as_deref(), Some("V"));
+        assert_eq!(tokens[2].text, "分析して");
+    }
+
+    #[test]
+    fn test_quoted_noun() {
+        let tokens = tokenize(r#"_N:"New York" に 送信して"#);
+
+        assert_eq!(tokens[0].text, "New York");
+        assert!(tokens[0].quoted);
+        assert!(tokens[0].is_noun());
+        assert_eq!(tokens[0].as_dsl(), "_N:New York");
+        assert_eq!(tokens.len(), 3);
+    }
+
+    #[test]
+    fn test_quoted_path_with_escapes() {
+        let tokens = tokenize(r#"_N:"C:\\Program Files\\app \"v2\"""#);
+        assert_eq!(tokens[0].text, r#"C:\Program Files\app "v2""#);
+    }
+
+    #[test]
+    fn test_unquoted_backslash_is_literal() {
+        assert_eq!(texts(r"_N:C:\Users\me"), vec![r"C:\Users\me"]);
+    }
+
+    #[test]
+    fn test_escaped_delimiter() {
+        assert_eq!(texts(r"_N:New\ York を"), vec!["New York", "を"]);
+    }
+
+    #[test]
+    fn test_escaped_marker_is_literal() {
+        let tokens = tokenize(r"\_N:foo は マーカー");
+
+        assert_eq!(tokens[0].marker, None);
+        assert_eq!(tokens[0].text, "_N:foo");
+        assert!(tokens[0].escaped);
+        assert_eq!(tokens[0].as_dsl(), r"\_N:foo");
+    }
+
+    #[test]
+    fn test_lone_quote_is_punctuation() {
+        assert_eq!(texts(r#"" _N:A ""#), vec!["\"", "A", "\""]);
+    }
+
+    #[test]
+    fn test_full_width_space_delimiter() {
+        let tokens = tokenize("_N:ユーザー\u{3000}が\u{3000}\u{3000}_N:データ");
+
+        assert_eq!(tokens.len(), 3);
+        assert!(!tokens[1].sentence_break);
+        assert!(tokens[2].sentence_break);
+    }
+
+    #[test]
+    fn test_sentence_breaks() {
+        let tokens = tokenize("A B  C\nD");
+
+        let breaks: Vec<bool> = tokens.iter().map(|t| t.sentence_break).collect();
+        assert_eq!(breaks, vec![false, false, true, true]);
+    }
+
+    #[test]
+    fn test_unterminated_quote_ends_at_line_break() {
+        let tokens = tokenize("_N:\"New York\nB");
+
+        assert_eq!(tokens[0].text, "New York");
+        assert_eq!(tokens[1].text, "B");
+    }
+
+    #[test]
+    fn test_custom_delimiters() {
+        let config = TokenizerConfig {
+            delimiters: vec![','],
+            sentence_break: 2,
+        };
+        let tokens = tokenize_with("a b,c,,d", &config);
+
+        assert_eq!(tokens.iter().map(|t| t.text.as_str()).collect::<Vec<_>>(), vec!["a b", "c", "d"]);
+        assert!(tokens[2].sentence_break);
+    }
+
+    #[test]
+    fn test_byte_offsets() {
+        let input = "_N:\"A B\" を";
+        let tokens = tokenize(input);
+
+        assert_eq!(&input[tokens[0].start..tokens[0].end], "_N:\"A B\"");
+        assert_eq!(&input[tokens[1].start..tokens[1].end], "を");
+    }
+
+    #[test]
+    fn test_quote_text_round_trip() {
+        for text in ["User", "New York", r"C:\Program Files", "say \"hi\"", "全角\u{3000}スペース"] {
+            let dsl = format!("_N:{}", quote_text(text));
+            let tokens = tokenize(&dsl);
+            assert_eq!(tokens.len(), 1, "{}", dsl);
+            assert_eq!(tokens[0].text, text);
+        }
+        assert_eq!(quote_text("User"), "User");
+    }
+
+    #[test]
+    fn test_format_token() {
+        assert_eq!(format_token("_N:New York"), "_N:\"New York\"");
+        assert_eq!(format_token("_N:User"), "_N:User");
+        assert_eq!(format_token("を"), "を");
+        assert_eq!(format_token("\\_N:foo"), "\\_N:foo");
+    }
+
+    #[test]
+    fn test_verbatim_block_keeps_whitespace() {
+        let input = "_N:Config を\n_CODE:json\n{\n    \"id\":  1\n}\n_END\nで 説明して";
+        let tokens = tokenize(input);
+
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens[2].marker.as_deref(), Some("CODE"));
+        assert_eq!(tokens[2].text, "json");
+        assert_eq!(tokens[2].verbatim.as_deref(), Some("{\n    \"id\":  1\n}"));
+        assert_eq!(tokens[3].text, "で");
+        assert!(tokens[3].sentence_break);
+    }
+
+    #[test]
+    fn test_inline_verbatim_block() {
+        let tokens = tokenize("_CODE: a  \"b\" _END を");
+
+        assert_eq!(tokens[0].text, "");
+        assert_eq!(tokens[0].verbatim.as_deref(), Some("a  \"b\""));
+        assert_eq!(tokens[1].text, "を");
+    }
+
+    #[test]
+    fn test_verbatim_end_must_be_a_token() {
+        let tokens = tokenize("_CODE:sh\necho _ENDING\n_END");
+
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].verbatim.as_deref(), Some("echo _ENDING"));
+    }
+
+    #[test]
+    fn test_unterminated_verbatim_block() {
+        let tokens = tokenize("_CODE:py\n  x = 1\n");
+
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].verbatim.as_deref(), Some("  x = 1"));
+    }
+
+    #[test]
+    fn test_verbatim_split_tokens_round_trip() {
+        let input = "_CODE:rust\nfn main() {\n    println!(\"hi\");\n}\n_END を";
+        let split = split_tokens(input);
+
+        assert_eq!(split.len(), 2);
+        assert_eq!(format_token(&split[0]), split[0]);
+        assert_eq!(tokenize(&split[0])[0].verbatim, tokenize(input)[0].verbatim);
+    }
+
+    #[test]
+    fn test_split_tokens() {
+        assert_eq!(split_tokens(r#"_N:"New York" を"#), vec!["_N:New York", "を"]);
+    }
+}